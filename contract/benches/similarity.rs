@@ -0,0 +1,36 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use stellar_doc_verifier::{batch_compare, batch_compare_with, SimilarityConfig};
+
+/// Short notes mixed with long deeds, as seen in a real registry corpus.
+fn mixed_length_corpus() -> Vec<String> {
+    (0..40)
+        .map(|i| {
+            if i % 4 == 0 {
+                format!("plot {} title deed registered to owner {}", i, i * 7).repeat(20)
+            } else {
+                format!("note {}", i)
+            }
+        })
+        .collect()
+}
+
+fn bench_batch_compare(c: &mut Criterion) {
+    let corpus = mixed_length_corpus();
+    let documents: Vec<&str> = corpus.iter().map(String::as_str).collect();
+    let reference = documents[0];
+    let config = SimilarityConfig {
+        min_interesting: 0.8,
+    };
+
+    let mut group = c.benchmark_group("batch_compare_mixed_lengths");
+    group.bench_function("exact", |b| {
+        b.iter(|| batch_compare(black_box(reference), black_box(&documents)))
+    });
+    group.bench_function("early_exit", |b| {
+        b.iter(|| batch_compare_with(black_box(reference), black_box(&documents), &config))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_batch_compare);
+criterion_main!(benches);
@@ -0,0 +1,152 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AuditError;
+use crate::stellar::StellarError;
+
+/// Error returned by HTTP handlers, rendered as `{ "error", "code" }`.
+#[derive(Debug, Clone)]
+pub enum ApiError {
+    /// Malformed input (400).
+    Validation(String),
+    /// Missing or invalid credentials (401).
+    Unauthorized(String),
+    /// The caller may not access this resource (403).
+    Forbidden(String),
+    /// The requested resource does not exist (404).
+    NotFound(String),
+    /// The request did not finish within `REQUEST_TIMEOUT_SECS` (408).
+    Timeout(String),
+    /// The request conflicts with the current state (409).
+    Conflict(String),
+    /// The caller exceeded an endpoint's rate limit (429).
+    RateLimited(String),
+    /// Horizon failed or returned an unexpected response (502).
+    StellarUnavailable(String),
+    /// The caller's `X-Request-Timeout-Ms` deadline passed first (504).
+    DeadlineExceeded(String),
+    /// The anchoring account cannot currently sign or pay (503).
+    AccountNotReady(String),
+    /// A required setting such as the signing key is missing (503).
+    NotConfigured(String),
+    /// The cache backend failed (500).
+    Cache(String),
+    /// Any other server-side failure (500).
+    Internal(String),
+}
+
+/// JSON body of every [`ApiError`] response.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiErrorBody {
+    pub error: String,
+    pub code: String,
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::Validation(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Timeout(_) => StatusCode::REQUEST_TIMEOUT,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            Self::StellarUnavailable(_) => StatusCode::BAD_GATEWAY,
+            Self::DeadlineExceeded(_) => StatusCode::GATEWAY_TIMEOUT,
+            Self::AccountNotReady(_) | Self::NotConfigured(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Cache(_) | Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Stable machine-readable code for clients.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Validation(_) => "VALIDATION",
+            Self::Unauthorized(_) => "UNAUTHORIZED",
+            Self::Forbidden(_) => "FORBIDDEN",
+            Self::NotFound(_) => "NOT_FOUND",
+            Self::Timeout(_) => "TIMEOUT",
+            Self::Conflict(_) => "CONFLICT",
+            Self::RateLimited(_) => "RATE_LIMITED",
+            Self::StellarUnavailable(_) => "STELLAR_UNAVAILABLE",
+            Self::DeadlineExceeded(_) => "DEADLINE_EXCEEDED",
+            Self::AccountNotReady(_) => "ACCOUNT_NOT_READY",
+            Self::NotConfigured(_) => "NOT_CONFIGURED",
+            Self::Cache(_) => "CACHE_ERROR",
+            Self::Internal(_) => "INTERNAL",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Validation(m)
+            | Self::Unauthorized(m)
+            | Self::Forbidden(m)
+            | Self::NotFound(m)
+            | Self::Timeout(m)
+            | Self::Conflict(m)
+            | Self::RateLimited(m)
+            | Self::StellarUnavailable(m)
+            | Self::DeadlineExceeded(m)
+            | Self::AccountNotReady(m)
+            | Self::NotConfigured(m)
+            | Self::Cache(m)
+            | Self::Internal(m) => m,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ApiErrorBody {
+            error: self.message().to_string(),
+            code: self.code().to_string(),
+        };
+        (self.status(), Json(body)).into_response()
+    }
+}
+
+impl From<StellarError> for ApiError {
+    fn from(e: StellarError) -> Self {
+        match e {
+            StellarError::Horizon(_) => Self::StellarUnavailable(e.to_string()),
+            _ => Self::AccountNotReady(e.to_string()),
+        }
+    }
+}
+
+impl From<AuditError> for ApiError {
+    fn from(e: AuditError) -> Self {
+        match e {
+            AuditError::ConcurrencyConflict { .. } => Self::Conflict(e.to_string()),
+            _ => Self::Internal(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stellar_errors_map_to_gateway_or_unavailable() {
+        let horizon = ApiError::from(StellarError::Horizon("timeout".to_string()));
+        assert_eq!(horizon.status(), StatusCode::BAD_GATEWAY);
+        assert_eq!(horizon.code(), "STELLAR_UNAVAILABLE");
+
+        let missing = ApiError::from(StellarError::AccountNotFound("GABC".to_string()));
+        assert_eq!(missing.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(missing.code(), "ACCOUNT_NOT_READY");
+    }
+
+    #[test]
+    fn cache_errors_are_internal() {
+        let err = ApiError::Cache("redis down".to_string());
+        assert_eq!(err.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(err.code(), "CACHE_ERROR");
+        assert_eq!(err.message(), "redis down");
+    }
+}
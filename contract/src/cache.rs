@@ -1,20 +1,36 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use redis::{aio::ConnectionManager, AsyncCommands};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::RwLock;
+use tokio::time::Instant;
+use tracing::{info, warn};
 
 pub enum CacheBackend {
     Redis(RedisCache),
     InMemory(InMemoryCache),
+    /// Redis backed by an in-memory layer that keeps serving when Redis is down.
+    Layered(FallbackCache),
 }
 
 impl CacheBackend {
+    /// The process-local cache, when this backend has one.
+    pub fn memory(&self) -> Option<&InMemoryCache> {
+        match self {
+            Self::Redis(_) => None,
+            Self::InMemory(c) => Some(c),
+            Self::Layered(c) => Some(c.memory()),
+        }
+    }
+
     pub async fn check_connection(&self) -> bool {
         match self {
             Self::Redis(c) => c.check_connection().await,
             Self::InMemory(c) => c.check_connection().await,
+            Self::Layered(c) => c.check_connection().await,
         }
     }
 
@@ -22,6 +38,7 @@ impl CacheBackend {
         match self {
             Self::Redis(c) => c.get_raw(key).await,
             Self::InMemory(c) => c.get_raw(key).await,
+            Self::Layered(c) => c.get_raw(key).await,
         }
     }
 
@@ -29,6 +46,31 @@ impl CacheBackend {
         match self {
             Self::Redis(c) => c.set_raw(key, value, ttl).await,
             Self::InMemory(c) => c.set_raw(key, value, ttl).await,
+            Self::Layered(c) => c.set_raw(key, value, ttl).await,
+        }
+    }
+
+    /// Fetch several keys in one round-trip, in the order given.
+    pub async fn get_many_raw(&self, keys: &[String]) -> Result<Vec<Option<String>>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        match self {
+            Self::Redis(c) => c.get_many_raw(keys).await,
+            Self::InMemory(c) => c.get_many_raw(keys).await,
+            Self::Layered(c) => c.get_many_raw(keys).await,
+        }
+    }
+
+    /// Write several `(key, value, ttl)` entries in one round-trip.
+    pub async fn set_many_raw(&self, entries: &[(String, String, u64)]) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        match self {
+            Self::Redis(c) => c.set_many_raw(entries).await,
+            Self::InMemory(c) => c.set_many_raw(entries).await,
+            Self::Layered(c) => c.set_many_raw(entries).await,
         }
     }
 
@@ -50,14 +92,124 @@ impl CacheBackend {
         self.set_raw(key, &serialized, ttl).await
     }
 
+    /// Typed [`Self::get_many_raw`]. An entry that no longer deserializes
+    /// is reported as missing rather than failing the whole lookup.
+    pub async fn get_many<T>(&self, keys: &[String]) -> Result<Vec<Option<T>>>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        Ok(self
+            .get_many_raw(keys)
+            .await?
+            .into_iter()
+            .map(|value| value.and_then(|v| serde_json::from_str(&v).ok()))
+            .collect())
+    }
+
+    /// Typed [`Self::set_many_raw`].
+    pub async fn set_many<T>(&self, entries: &[(String, T, u64)]) -> Result<()>
+    where
+        T: Serialize,
+    {
+        let serialized = entries
+            .iter()
+            .map(|(key, value, ttl)| Ok((key.clone(), serde_json::to_string(value)?, *ttl)))
+            .collect::<Result<Vec<_>>>()?;
+        self.set_many_raw(&serialized).await
+    }
+
     pub async fn delete(&self, key: &str) -> Result<()> {
         match self {
             Self::Redis(c) => c.delete(key).await,
             Self::InMemory(c) => c.delete(key).await,
+            Self::Layered(c) => c.delete(key).await,
+        }
+    }
+
+    /// Delete every key starting with `prefix` for which `matches` holds,
+    /// returning how many were removed.
+    pub async fn delete_matching(
+        &self,
+        prefix: &str,
+        matches: &(dyn Fn(&str) -> bool + Sync),
+    ) -> Result<usize> {
+        match self {
+            Self::Redis(c) => c.delete_matching(prefix, matches).await,
+            Self::InMemory(c) => c.delete_matching(prefix, matches).await,
+            Self::Layered(c) => c.delete_matching(prefix, matches).await,
+        }
+    }
+
+    /// Remaining time to live for `key`. `None` if the key does not exist or
+    /// has no expiry.
+    pub async fn ttl(&self, key: &str) -> Result<Option<Duration>> {
+        match self {
+            Self::Redis(c) => c.ttl(key).await,
+            Self::InMemory(c) => c.ttl(key).await,
+            Self::Layered(c) => c.ttl(key).await,
+        }
+    }
+
+    /// Reset the expiry of an existing `key`. Returns `false` if it does not exist.
+    pub async fn expire(&self, key: &str, ttl: u64) -> Result<bool> {
+        match self {
+            Self::Redis(c) => c.expire(key, ttl).await,
+            Self::InMemory(c) => c.expire(key, ttl).await,
+            Self::Layered(c) => c.expire(key, ttl).await,
+        }
+    }
+
+    /// Current value of the counter at `key` (0 when missing).
+    pub async fn counter(&self, key: &str) -> Result<i64> {
+        match self {
+            Self::Redis(c) => c.counter(key).await,
+            Self::InMemory(c) => Ok(c.counter(key)),
+            Self::Layered(c) => c.counter(key).await,
+        }
+    }
+
+    /// Atomically increment the counter at `key` and return the new value.
+    pub async fn incr(&self, key: &str) -> Result<i64> {
+        match self {
+            Self::Redis(c) => c.incr(key).await,
+            Self::InMemory(c) => Ok(c.incr_if(key, None).1),
+            Self::Layered(c) => c.incr(key).await,
+        }
+    }
+
+    /// Atomically increment the counter at `counter_key` to `n` and store
+    /// `value` at `{entry_prefix}{n}` for `ttl` seconds, so a failed write
+    /// never leaves a gap in the sequence. With `expected`, nothing happens
+    /// unless the counter still equals it. The counter's expiry is reset to
+    /// `counter_ttl` (Redis only). Returns whether the entry was stored, and
+    /// the counter's value afterwards.
+    pub async fn append_counted(
+        &self,
+        counter_key: &str,
+        counter_ttl: u64,
+        entry_prefix: &str,
+        value: &str,
+        ttl: u64,
+        expected: Option<i64>,
+    ) -> Result<(bool, i64)> {
+        match self {
+            Self::Redis(c) => {
+                c.append_counted(counter_key, counter_ttl, entry_prefix, value, ttl, expected)
+                    .await
+            }
+            Self::InMemory(c) => {
+                c.append_counted(counter_key, entry_prefix, value, ttl, expected)
+                    .await
+            }
+            Self::Layered(c) => {
+                c.append_counted(counter_key, counter_ttl, entry_prefix, value, ttl, expected)
+                    .await
+            }
         }
     }
 }
 
+#[derive(Clone)]
 pub struct RedisCache {
     connection: ConnectionManager,
 }
@@ -89,15 +241,503 @@ impl RedisCache {
         Ok(())
     }
 
+    async fn get_many_raw(&self, keys: &[String]) -> Result<Vec<Option<String>>> {
+        let mut conn = self.connection.clone();
+        let values: Vec<Option<String>> =
+            redis::cmd("MGET").arg(keys).query_async(&mut conn).await?;
+        Ok(values)
+    }
+
+    async fn set_many_raw(&self, entries: &[(String, String, u64)]) -> Result<()> {
+        let mut conn = self.connection.clone();
+        let mut pipe = redis::pipe();
+        for (key, value, ttl) in entries {
+            pipe.set_ex(key, value, *ttl).ignore();
+        }
+        pipe.query_async::<_, ()>(&mut conn).await?;
+        Ok(())
+    }
+
     async fn delete(&self, key: &str) -> Result<()> {
         let mut conn = self.connection.clone();
         conn.del::<_, ()>(key).await?;
         Ok(())
     }
+
+    async fn delete_matching(
+        &self,
+        prefix: &str,
+        matches: &(dyn Fn(&str) -> bool + Sync),
+    ) -> Result<usize> {
+        const DELETE_CHUNK: usize = 500;
+        let mut conn = self.connection.clone();
+        let pattern = format!("{}*", escape_glob(prefix));
+        let mut keys = Vec::new();
+        {
+            let mut iter = conn.scan_match::<_, String>(&pattern).await?;
+            while let Some(key) = iter.next_item().await {
+                if matches(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+        for chunk in keys.chunks(DELETE_CHUNK) {
+            conn.del::<_, ()>(chunk).await?;
+        }
+        Ok(keys.len())
+    }
+
+    async fn ttl(&self, key: &str) -> Result<Option<Duration>> {
+        let mut conn = self.connection.clone();
+        // TTL returns -2 for a missing key and -1 for a key without expiry.
+        let secs: i64 = conn.ttl(key).await?;
+        Ok((secs >= 0).then(|| Duration::from_secs(secs as u64)))
+    }
+
+    async fn expire(&self, key: &str, ttl: u64) -> Result<bool> {
+        let mut conn = self.connection.clone();
+        let ttl = i64::try_from(ttl).map_err(|_| anyhow!("ttl of {}s is out of range", ttl))?;
+        let updated: bool = conn.expire(key, ttl).await?;
+        Ok(updated)
+    }
+
+    async fn counter(&self, key: &str) -> Result<i64> {
+        let mut conn = self.connection.clone();
+        let value: Option<i64> = conn.get(key).await?;
+        Ok(value.unwrap_or(0))
+    }
+
+    async fn incr(&self, key: &str) -> Result<i64> {
+        let mut conn = self.connection.clone();
+        let value: i64 = conn.incr(key, 1).await?;
+        Ok(value)
+    }
+
+    async fn append_counted(
+        &self,
+        counter_key: &str,
+        counter_ttl: u64,
+        entry_prefix: &str,
+        value: &str,
+        ttl: u64,
+        expected: Option<i64>,
+    ) -> Result<(bool, i64)> {
+        // Compare, INCR and SET in one script so concurrent writers on other
+        // instances can't interleave and a failed write can't skip a number.
+        // The entry key depends on the new counter value, so it can't be
+        // declared up front; this needs a non-clustered Redis.
+        let script = redis::Script::new(
+            r"
+            local current = tonumber(redis.call('GET', KEYS[1]) or '0')
+            if ARGV[1] ~= '' and current ~= tonumber(ARGV[1]) then
+                return {0, current}
+            end
+            local n = redis.call('INCR', KEYS[1])
+            redis.call('EXPIRE', KEYS[1], ARGV[2])
+            redis.call('SET', ARGV[3] .. n, ARGV[4], 'EX', ARGV[5])
+            return {1, n}
+            ",
+        );
+        let mut conn = self.connection.clone();
+        let (applied, counter): (i64, i64) = script
+            .key(counter_key)
+            .arg(expected.map(|e| e.to_string()).unwrap_or_default())
+            .arg(counter_ttl)
+            .arg(entry_prefix)
+            .arg(value)
+            .arg(ttl)
+            .invoke_async(&mut conn)
+            .await?;
+        Ok((applied == 1, counter))
+    }
 }
 
+/// Escape Redis `SCAN MATCH` glob characters so `prefix` matches literally.
+fn escape_glob(prefix: &str) -> String {
+    let mut escaped = String::with_capacity(prefix.len());
+    for c in prefix.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Longest wait for a Redis connection before serving from memory.
+const REDIS_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Redis with an in-memory layer underneath.
+///
+/// Every write also lands in memory. Reads go to Redis while it is
+/// reachable; a Redis error is treated as a miss and answered from memory,
+/// and Redis is then skipped for `cooldown` so requests don't each wait on
+/// a dead connection. Counters have no meaningful local fallback and fail
+/// while Redis is unavailable.
+///
+/// A write or delete that misses Redis marks the key stale: it is read from
+/// memory only, and its memory state is copied to Redis (or deleted there)
+/// before Redis serves anything again. Writes still report the Redis error
+/// so callers can queue them for retry.
+pub struct FallbackCache {
+    redis_url: String,
+    redis: Mutex<Option<RedisCache>>,
+    retry_at: Mutex<Option<Instant>>,
+    cooldown: Duration,
+    memory: InMemoryCache,
+    stale: Mutex<HashSet<String>>,
+}
+
+impl FallbackCache {
+    /// Create the cache and try to reach Redis once. Startup continues from
+    /// memory if Redis is unreachable.
+    pub async fn connect(redis_url: &str, memory: InMemoryCache, cooldown: Duration) -> Self {
+        let cache = Self {
+            redis_url: redis_url.to_string(),
+            redis: Mutex::new(None),
+            retry_at: Mutex::new(None),
+            cooldown,
+            memory,
+            stale: Mutex::new(HashSet::new()),
+        };
+        cache.redis().await;
+        cache
+    }
+
+    pub fn memory(&self) -> &InMemoryCache {
+        &self.memory
+    }
+
+    /// Redis, unless it failed within the last `cooldown` or stale keys
+    /// could not be written back to it.
+    async fn redis(&self) -> Option<RedisCache> {
+        let redis = self.connection().await?;
+        self.resync(&redis).await.then_some(redis)
+    }
+
+    async fn connection(&self) -> Option<RedisCache> {
+        if let Some(retry_at) = *self.retry_at.lock().unwrap() {
+            if Instant::now() < retry_at {
+                return None;
+            }
+        }
+        if let Some(redis) = self.redis.lock().unwrap().clone() {
+            return Some(redis);
+        }
+        match tokio::time::timeout(REDIS_CONNECT_TIMEOUT, RedisCache::new(&self.redis_url)).await {
+            Ok(Ok(redis)) => {
+                info!("Connected to Redis");
+                *self.redis.lock().unwrap() = Some(redis.clone());
+                Some(redis)
+            }
+            Ok(Err(e)) => {
+                self.mark_down(&e.to_string());
+                None
+            }
+            Err(_) => {
+                self.mark_down("connection timed out");
+                None
+            }
+        }
+    }
+
+    fn mark_down(&self, reason: &str) {
+        warn!(
+            "Redis unavailable ({}); serving cache from memory for {}s",
+            reason,
+            self.cooldown.as_secs()
+        );
+        *self.retry_at.lock().unwrap() = Some(Instant::now() + self.cooldown);
+    }
+
+    /// Unwrap a Redis result, recording a failure as Redis being down.
+    fn soft<T>(&self, result: Result<T>) -> Option<T> {
+        result.map_err(|e| self.mark_down(&e.to_string())).ok()
+    }
+
+    fn is_stale(&self, key: &str) -> bool {
+        self.stale.lock().unwrap().contains(key)
+    }
+
+    fn mark_stale<'a>(&self, keys: impl IntoIterator<Item = &'a str>) {
+        let mut stale = self.stale.lock().unwrap();
+        stale.extend(keys.into_iter().map(str::to_string));
+    }
+
+    /// Copy every stale key's memory state to Redis. Returns `false` if
+    /// Redis failed part way; the remaining keys stay stale.
+    async fn resync(&self, redis: &RedisCache) -> bool {
+        let keys: Vec<String> = self.stale.lock().unwrap().iter().cloned().collect();
+        if keys.is_empty() {
+            return true;
+        }
+        for key in &keys {
+            let value = self.memory.get_raw(key).await.ok().flatten();
+            let ttl = self.memory.ttl(key).await.ok().flatten();
+            let result = match (value, ttl) {
+                (Some(value), Some(ttl)) => redis.set_raw(key, &value, ttl.as_secs().max(1)).await,
+                _ => redis.delete(key).await,
+            };
+            if let Err(e) = result {
+                self.mark_down(&e.to_string());
+                return false;
+            }
+            self.stale.lock().unwrap().remove(key);
+        }
+        info!("Wrote {} stale cache keys back to Redis", keys.len());
+        true
+    }
+
+    /// Record a write that missed Redis and report why.
+    fn write_missed(&self, keys: &[&str], error: Option<anyhow::Error>) -> anyhow::Error {
+        self.mark_stale(keys.iter().copied());
+        match error {
+            Some(e) => {
+                self.mark_down(&e.to_string());
+                e
+            }
+            None => anyhow!("redis is unavailable"),
+        }
+    }
+
+    /// Redis for operations that cannot fall back to memory.
+    async fn required_redis(&self) -> Result<RedisCache> {
+        self.redis()
+            .await
+            .ok_or_else(|| anyhow!("redis is unavailable"))
+    }
+
+    /// Reports Redis itself, so `/health` shows the outage while requests
+    /// are still served from memory.
+    async fn check_connection(&self) -> bool {
+        match self.redis().await {
+            Some(redis) => {
+                let ok = redis.check_connection().await;
+                if !ok {
+                    self.mark_down("ping failed");
+                }
+                ok
+            }
+            None => false,
+        }
+    }
+
+    async fn get_raw(&self, key: &str) -> Result<Option<String>> {
+        if !self.is_stale(key) {
+            if let Some(redis) = self.redis().await {
+                if let Some(value) = self.soft(redis.get_raw(key).await) {
+                    return Ok(value);
+                }
+            }
+        }
+        self.memory.get_raw(key).await
+    }
+
+    async fn set_raw(&self, key: &str, value: &str, ttl: u64) -> Result<()> {
+        self.memory.set_raw(key, value, ttl).await?;
+        match self.redis().await {
+            Some(redis) => redis
+                .set_raw(key, value, ttl)
+                .await
+                .map_err(|e| self.write_missed(&[key], Some(e))),
+            None => Err(self.write_missed(&[key], None)),
+        }
+    }
+
+    async fn get_many_raw(&self, keys: &[String]) -> Result<Vec<Option<String>>> {
+        if let Some(redis) = self.redis().await {
+            if let Some(mut values) = self.soft(redis.get_many_raw(keys).await) {
+                for (key, value) in keys.iter().zip(values.iter_mut()) {
+                    if self.is_stale(key) {
+                        *value = self.memory.get_raw(key).await?;
+                    }
+                }
+                return Ok(values);
+            }
+        }
+        self.memory.get_many_raw(keys).await
+    }
+
+    async fn set_many_raw(&self, entries: &[(String, String, u64)]) -> Result<()> {
+        self.memory.set_many_raw(entries).await?;
+        let keys: Vec<&str> = entries.iter().map(|(key, _, _)| key.as_str()).collect();
+        match self.redis().await {
+            Some(redis) => redis
+                .set_many_raw(entries)
+                .await
+                .map_err(|e| self.write_missed(&keys, Some(e))),
+            None => Err(self.write_missed(&keys, None)),
+        }
+    }
+
+    /// A delete that misses Redis still succeeds: the key stays stale, so
+    /// the old Redis value is never served and is removed on reconnect.
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.memory.delete(key).await?;
+        let deleted = match self.redis().await {
+            Some(redis) => self.soft(redis.delete(key).await),
+            None => None,
+        };
+        match deleted {
+            Some(()) => {
+                self.stale.lock().unwrap().remove(key);
+            }
+            None => {
+                warn!("Redis delete of {} deferred until Redis is back", key);
+                self.mark_stale([key])
+            }
+        };
+        Ok(())
+    }
+
+    /// Fails while Redis is unavailable: the keys only Redis holds are
+    /// unknown, so the purge cannot be deferred.
+    async fn delete_matching(
+        &self,
+        prefix: &str,
+        matches: &(dyn Fn(&str) -> bool + Sync),
+    ) -> Result<usize> {
+        self.memory.delete_matching(prefix, matches).await?;
+        let result = self
+            .required_redis()
+            .await?
+            .delete_matching(prefix, matches)
+            .await;
+        result.inspect_err(|e| self.mark_down(&e.to_string()))
+    }
+
+    async fn ttl(&self, key: &str) -> Result<Option<Duration>> {
+        if !self.is_stale(key) {
+            if let Some(redis) = self.redis().await {
+                if let Some(ttl) = self.soft(redis.ttl(key).await) {
+                    return Ok(ttl);
+                }
+            }
+        }
+        self.memory.ttl(key).await
+    }
+
+    /// Fails while Redis is unavailable, like `delete_matching`.
+    async fn expire(&self, key: &str, ttl: u64) -> Result<bool> {
+        let local = self.memory.expire(key, ttl).await?;
+        if self.is_stale(key) {
+            return Ok(local);
+        }
+        let result = self.required_redis().await?.expire(key, ttl).await;
+        result.inspect_err(|e| self.mark_down(&e.to_string()))
+    }
+
+    async fn counter(&self, key: &str) -> Result<i64> {
+        let result = self.required_redis().await?.counter(key).await;
+        result.inspect_err(|e| self.mark_down(&e.to_string()))
+    }
+
+    async fn incr(&self, key: &str) -> Result<i64> {
+        let result = self.required_redis().await?.incr(key).await;
+        result.inspect_err(|e| self.mark_down(&e.to_string()))
+    }
+
+    async fn append_counted(
+        &self,
+        counter_key: &str,
+        counter_ttl: u64,
+        entry_prefix: &str,
+        value: &str,
+        ttl: u64,
+        expected: Option<i64>,
+    ) -> Result<(bool, i64)> {
+        let result = self
+            .required_redis()
+            .await?
+            .append_counted(counter_key, counter_ttl, entry_prefix, value, ttl, expected)
+            .await;
+        result.inspect_err(|e| self.mark_down(&e.to_string()))
+    }
+}
+
+struct Entry {
+    value: String,
+    expires_at: Instant,
+    /// Recency stamp; the key's position in [`Store::recency`].
+    touched: u64,
+}
+
+impl Entry {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// Entries plus a recency index ordered from least to most recently used.
+#[derive(Default)]
+struct Store {
+    entries: HashMap<String, Entry>,
+    recency: BTreeMap<u64, String>,
+    clock: u64,
+}
+
+impl Store {
+    /// Mark `key` as just used.
+    fn touch(&mut self, key: &str) {
+        self.clock += 1;
+        let clock = self.clock;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.recency.remove(&entry.touched);
+            entry.touched = clock;
+            self.recency.insert(clock, key.to_string());
+        }
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Entry> {
+        let entry = self.entries.remove(key)?;
+        self.recency.remove(&entry.touched);
+        Some(entry)
+    }
+
+    fn evict_expired(&mut self, now: Instant) -> usize {
+        let expired: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.is_expired(now))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &expired {
+            self.remove(key);
+        }
+        expired.len()
+    }
+
+    /// Drop the least recently used entry.
+    fn evict_lru(&mut self) -> bool {
+        match self.recency.pop_first() {
+            Some((_, key)) => self.entries.remove(&key).is_some(),
+            None => false,
+        }
+    }
+}
+
+type SharedStore = Arc<RwLock<Store>>;
+
+/// Deadline `ttl` seconds after `now`, rejecting TTLs the clock can't hold.
+fn expiry(now: Instant, ttl: u64) -> Result<Instant> {
+    now.checked_add(Duration::from_secs(ttl))
+        .ok_or_else(|| anyhow!("ttl of {}s is out of range", ttl))
+}
+
+/// How often the background task drops expired entries by default.
+const DEFAULT_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Process-local cache honouring per-key TTLs like Redis does.
+///
+/// Expired entries are invisible to reads and removed lazily when touched;
+/// a background task also sweeps them so untouched keys do not pile up.
+/// With a capacity set, the least recently used entry is evicted to make
+/// room for a new key.
 pub struct InMemoryCache {
-    store: Arc<RwLock<HashMap<String, String>>>,
+    store: SharedStore,
+    max_entries: Option<usize>,
+    evictions: AtomicU64,
+    counters: Arc<Mutex<HashMap<String, i64>>>,
 }
 
 impl Default for InMemoryCache {
@@ -107,30 +747,421 @@ impl Default for InMemoryCache {
 }
 
 impl InMemoryCache {
+    /// Create an unbounded cache.
     pub fn new() -> Self {
+        Self::build(None, DEFAULT_SWEEP_INTERVAL)
+    }
+
+    /// Create a cache holding at most `max_entries` keys
+    /// (`CACHE_MAX_ENTRIES`).
+    pub fn with_capacity(max_entries: usize) -> Self {
+        Self::build(Some(max_entries), DEFAULT_SWEEP_INTERVAL)
+    }
+
+    /// Create an unbounded cache whose background sweep runs every
+    /// `interval`.
+    pub fn with_sweep_interval(interval: Duration) -> Self {
+        Self::build(None, interval)
+    }
+
+    /// The sweep only starts when called inside a Tokio runtime and stops
+    /// once the cache is dropped.
+    fn build(max_entries: Option<usize>, sweep_interval: Duration) -> Self {
+        let store: SharedStore = Arc::new(RwLock::new(Store::default()));
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let weak = Arc::downgrade(&store);
+            runtime.spawn(async move {
+                let mut ticker = tokio::time::interval(sweep_interval);
+                ticker.tick().await;
+                loop {
+                    ticker.tick().await;
+                    let Some(store) = weak.upgrade() else { break };
+                    store.write().await.evict_expired(Instant::now());
+                }
+            });
+        }
         Self {
-            store: Arc::new(RwLock::new(HashMap::new())),
+            store,
+            max_entries,
+            evictions: AtomicU64::new(0),
+            counters: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    /// Number of stored entries, including expired ones not yet evicted.
+    pub async fn entries(&self) -> usize {
+        self.store.read().await.entries.len()
+    }
+
+    /// Entries evicted so far to stay within the capacity.
+    pub fn evictions(&self) -> u64 {
+        self.evictions.load(Ordering::Relaxed)
+    }
+
+    /// Remove every expired entry now and return how many were dropped.
+    pub async fn evict_expired(&self) -> usize {
+        self.store.write().await.evict_expired(Instant::now())
+    }
+
     async fn check_connection(&self) -> bool {
         true
     }
 
     async fn get_raw(&self, key: &str) -> Result<Option<String>> {
-        let store = self.store.read().await;
-        Ok(store.get(key).cloned())
+        let now = Instant::now();
+        let mut store = self.store.write().await;
+        match store.entries.get(key) {
+            None => Ok(None),
+            Some(entry) if entry.is_expired(now) => {
+                store.remove(key);
+                Ok(None)
+            }
+            Some(entry) => {
+                let value = entry.value.clone();
+                store.touch(key);
+                Ok(Some(value))
+            }
+        }
     }
 
-    async fn set_raw(&self, key: &str, key_val: &str, _ttl: u64) -> Result<()> {
+    async fn set_raw(&self, key: &str, key_val: &str, ttl: u64) -> Result<()> {
+        let now = Instant::now();
+        let expires_at = expiry(now, ttl)?;
         let mut store = self.store.write().await;
-        store.insert(key.to_string(), key_val.to_string());
+        self.insert(&mut store, key, key_val, expires_at, now);
         Ok(())
     }
 
-    async fn delete(&self, key: &str) -> Result<()> {
-        let mut store = self.store.write().await;
+    fn insert(
+        &self,
+        store: &mut Store,
+        key: &str,
+        key_val: &str,
+        expires_at: Instant,
+        now: Instant,
+    ) {
+        if let Some(max_entries) = self.max_entries {
+            if !store.entries.contains_key(key) && store.entries.len() >= max_entries {
+                store.evict_expired(now);
+                while store.entries.len() >= max_entries && store.evict_lru() {
+                    self.evictions.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
         store.remove(key);
+        store.entries.insert(
+            key.to_string(),
+            Entry {
+                value: key_val.to_string(),
+                expires_at,
+                touched: 0,
+            },
+        );
+        store.touch(key);
+    }
+
+    async fn append_counted(
+        &self,
+        counter_key: &str,
+        entry_prefix: &str,
+        value: &str,
+        ttl: u64,
+        expected: Option<i64>,
+    ) -> Result<(bool, i64)> {
+        let now = Instant::now();
+        let expires_at = expiry(now, ttl)?;
+        // Holding the store lock across the increment keeps readers from
+        // seeing the new counter before its entry.
+        let mut store = self.store.write().await;
+        let (applied, counter) = self.incr_if(counter_key, expected);
+        if applied {
+            let key = format!("{}{}", entry_prefix, counter);
+            self.insert(&mut store, &key, value, expires_at, now);
+        }
+        Ok((applied, counter))
+    }
+
+    async fn get_many_raw(&self, keys: &[String]) -> Result<Vec<Option<String>>> {
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            values.push(self.get_raw(key).await?);
+        }
+        Ok(values)
+    }
+
+    async fn set_many_raw(&self, entries: &[(String, String, u64)]) -> Result<()> {
+        for (key, value, ttl) in entries {
+            self.set_raw(key, value, *ttl).await?;
+        }
         Ok(())
     }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.store.write().await.remove(key);
+        Ok(())
+    }
+
+    async fn delete_matching(
+        &self,
+        prefix: &str,
+        matches: &(dyn Fn(&str) -> bool + Sync),
+    ) -> Result<usize> {
+        let mut store = self.store.write().await;
+        let doomed: Vec<String> = store
+            .entries
+            .keys()
+            .filter(|key| key.starts_with(prefix) && matches(key))
+            .cloned()
+            .collect();
+        for key in &doomed {
+            store.remove(key);
+        }
+        Ok(doomed.len())
+    }
+
+    async fn ttl(&self, key: &str) -> Result<Option<Duration>> {
+        let now = Instant::now();
+        let store = self.store.read().await;
+        Ok(store
+            .entries
+            .get(key)
+            .filter(|entry| !entry.is_expired(now))
+            .map(|entry| entry.expires_at.saturating_duration_since(now)))
+    }
+
+    async fn expire(&self, key: &str, ttl: u64) -> Result<bool> {
+        let now = Instant::now();
+        let expires_at = expiry(now, ttl)?;
+        let mut store = self.store.write().await;
+        match store.entries.get_mut(key) {
+            Some(entry) if !entry.is_expired(now) => {
+                entry.expires_at = expires_at;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn counter(&self, key: &str) -> i64 {
+        self.counters.lock().unwrap().get(key).copied().unwrap_or(0)
+    }
+
+    fn incr_if(&self, key: &str, expected: Option<i64>) -> (bool, i64) {
+        let mut counters = self.counters.lock().unwrap();
+        let current = counters.entry(key.to_string()).or_insert(0);
+        if expected.is_some_and(|expected| expected != *current) {
+            return (false, *current);
+        }
+        *current += 1;
+        (true, *current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn in_memory_ttl_reports_and_extends_expiry() {
+        let cache = CacheBackend::InMemory(InMemoryCache::new());
+        cache.set_raw("history:abc", "[]", 600).await.unwrap();
+
+        let ttl = cache.ttl("history:abc").await.unwrap().unwrap();
+        assert!(ttl <= Duration::from_secs(600));
+        assert!(ttl >= Duration::from_secs(599));
+
+        assert!(cache.expire("history:abc", 30).await.unwrap());
+        let ttl = cache.ttl("history:abc").await.unwrap().unwrap();
+        assert!(ttl <= Duration::from_secs(30));
+        assert!(ttl >= Duration::from_secs(29));
+
+        assert_eq!(cache.ttl("missing").await.unwrap(), None);
+        assert!(!cache.expire("missing", 30).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn in_memory_rejects_ttls_past_the_clock_range() {
+        let cache = CacheBackend::InMemory(InMemoryCache::new());
+        cache.set_raw("history:abc", "[]", 600).await.unwrap();
+
+        assert!(cache.expire("history:abc", u64::MAX).await.is_err());
+        assert!(cache.set_raw("history:abc", "[]", u64::MAX).await.is_err());
+        assert!(cache.ttl("history:abc").await.unwrap().is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn in_memory_entries_expire_after_their_ttl() {
+        let cache = CacheBackend::InMemory(InMemoryCache::new());
+        cache.set_raw("short", "1", 5).await.unwrap();
+        cache.set_raw("long", "2", 600).await.unwrap();
+
+        tokio::time::advance(Duration::from_secs(4)).await;
+        assert_eq!(cache.get_raw("short").await.unwrap().as_deref(), Some("1"));
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert_eq!(cache.get_raw("short").await.unwrap(), None);
+        assert_eq!(cache.ttl("short").await.unwrap(), None);
+        assert!(!cache.expire("short", 30).await.unwrap());
+        assert_eq!(cache.get_raw("long").await.unwrap().as_deref(), Some("2"));
+
+        let CacheBackend::InMemory(memory) = &cache else {
+            unreachable!()
+        };
+        assert_eq!(memory.entries().await, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn in_memory_evicts_untouched_expired_entries() {
+        let cache = InMemoryCache::with_sweep_interval(Duration::from_secs(10));
+        cache.set_raw("a", "1", 1).await.unwrap();
+        cache.set_raw("b", "2", 1).await.unwrap();
+        cache.set_raw("c", "3", 600).await.unwrap();
+
+        tokio::time::advance(Duration::from_secs(2)).await;
+        assert_eq!(cache.entries().await, 3);
+        assert_eq!(cache.evict_expired().await, 2);
+        assert_eq!(cache.entries().await, 1);
+
+        cache.set_raw("d", "4", 1).await.unwrap();
+        // Give the background sweep time to tick at least once.
+        tokio::time::sleep(Duration::from_secs(20)).await;
+        assert_eq!(cache.entries().await, 1);
+        assert_eq!(cache.get_raw("c").await.unwrap().as_deref(), Some("3"));
+    }
+
+    #[tokio::test]
+    async fn in_memory_evicts_least_recently_used_keys_at_capacity() {
+        let cache = InMemoryCache::with_capacity(20);
+        for i in 0..20 {
+            cache.set_raw(&format!("k{i}"), "v", 600).await.unwrap();
+        }
+        // Reading the oldest keys makes them the most recently used.
+        for i in 0..5 {
+            assert!(cache.get_raw(&format!("k{i}")).await.unwrap().is_some());
+        }
+        for i in 20..30 {
+            cache.set_raw(&format!("k{i}"), "v", 600).await.unwrap();
+        }
+
+        assert_eq!(cache.entries().await, 20);
+        assert_eq!(cache.evictions(), 10);
+        for i in 0..5 {
+            assert!(cache.get_raw(&format!("k{i}")).await.unwrap().is_some());
+        }
+        for i in 5..15 {
+            assert_eq!(cache.get_raw(&format!("k{i}")).await.unwrap(), None);
+        }
+        for i in 15..30 {
+            assert!(cache.get_raw(&format!("k{i}")).await.unwrap().is_some());
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn in_memory_prefers_dropping_expired_entries_over_evicting() {
+        let cache = InMemoryCache::with_capacity(2);
+        cache.set_raw("short", "1", 1).await.unwrap();
+        cache.set_raw("long", "2", 600).await.unwrap();
+        tokio::time::advance(Duration::from_secs(2)).await;
+
+        cache.set_raw("new", "3", 600).await.unwrap();
+        assert_eq!(cache.evictions(), 0);
+        assert!(cache.get_raw("long").await.unwrap().is_some());
+        assert!(cache.get_raw("new").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn get_many_returns_entries_in_key_order() {
+        let cache = CacheBackend::InMemory(InMemoryCache::new());
+        cache
+            .set_many(&[("a".to_string(), 1, 600), ("c".to_string(), 3, 600)])
+            .await
+            .unwrap();
+        cache.set_raw("bad", "not json", 600).await.unwrap();
+
+        let keys = ["c", "b", "a", "bad"].map(String::from);
+        let values: Vec<Option<i32>> = cache.get_many(&keys).await.unwrap();
+        assert_eq!(values, vec![Some(3), None, Some(1), None]);
+        assert!(cache.get_many::<i32>(&[]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fallback_serves_from_memory_while_redis_is_unreachable() {
+        let cache = CacheBackend::Layered(
+            FallbackCache::connect(
+                "redis://127.0.0.1:1",
+                InMemoryCache::new(),
+                Duration::from_secs(30),
+            )
+            .await,
+        );
+        assert!(!cache.check_connection().await);
+
+        // The write reports the outage but is still served from memory.
+        assert!(cache.set_raw("hash-a", "{}", 600).await.is_err());
+        assert_eq!(
+            cache.get_raw("hash-a").await.unwrap().as_deref(),
+            Some("{}")
+        );
+        assert_eq!(
+            cache
+                .get_many_raw(&["hash-a".to_string(), "hash-b".to_string()])
+                .await
+                .unwrap(),
+            vec![Some("{}".to_string()), None]
+        );
+        assert!(cache.ttl("hash-a").await.unwrap().is_some());
+        assert!(cache.incr("seq").await.is_err());
+        assert_eq!(cache.memory().unwrap().entries().await, 1);
+    }
+
+    #[tokio::test]
+    async fn delete_matching_only_removes_selected_keys_under_prefix() {
+        let cache = CacheBackend::InMemory(InMemoryCache::new());
+        for key in [
+            "status:aa",
+            "status:ab",
+            "status:b",
+            "history:aa",
+            "stellar:x",
+        ] {
+            cache.set_raw(key, "{}", 600).await.unwrap();
+        }
+
+        let deleted = cache
+            .delete_matching("status:a", &|key| key != "status:ab")
+            .await
+            .unwrap();
+        assert_eq!(deleted, 1);
+        assert_eq!(cache.get_raw("status:aa").await.unwrap(), None);
+        assert!(cache.get_raw("status:ab").await.unwrap().is_some());
+        assert!(cache.get_raw("history:aa").await.unwrap().is_some());
+        assert_eq!(cache.memory().unwrap().entries().await, 4);
+    }
+
+    #[test]
+    fn escape_glob_quotes_pattern_characters() {
+        assert_eq!(escape_glob("status:ab"), "status:ab");
+        assert_eq!(escape_glob("a*b?[c]\\"), "a\\*b\\?\\[c\\]\\\\");
+    }
+
+    #[tokio::test]
+    async fn in_memory_append_counted_only_applies_on_expected_value() {
+        let cache = CacheBackend::InMemory(InMemoryCache::new());
+        assert_eq!(cache.incr("seq").await.unwrap(), 1);
+        let append = |expected| cache.append_counted("seq", 60, "entry:", "v", 60, expected);
+        assert_eq!(append(Some(1)).await.unwrap(), (true, 2));
+        assert_eq!(append(Some(1)).await.unwrap(), (false, 2));
+        assert_eq!(append(None).await.unwrap(), (true, 3));
+        assert_eq!(cache.counter("seq").await.unwrap(), 3);
+        assert_eq!(cache.counter("other").await.unwrap(), 0);
+        assert!(cache.get_raw("entry:1").await.unwrap().is_none());
+        assert_eq!(
+            cache.get_raw("entry:2").await.unwrap().as_deref(),
+            Some("v")
+        );
+        assert_eq!(
+            cache.get_raw("entry:3").await.unwrap().as_deref(),
+            Some("v")
+        );
+    }
 }
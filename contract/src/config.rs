@@ -1,31 +1,319 @@
 use std::env;
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::PathBuf;
 
 use thiserror::Error;
 use url::Url;
 
+use crate::hash_validator::HashAlgorithm;
+use crate::secrets::{EnvSecretProvider, Secret, SecretProvider};
+use crate::stellar::AnchorMethod;
+
+/// Every environment variable `AppConfig` understands.
+const KNOWN_VARS: &[&str] = &[
+    "PORT",
+    "STELLAR_HORIZON_URL",
+    "STELLAR_SECRET_KEY",
+    "STELLAR_SECRET_KEY_FILE",
+    "REDIS_URL",
+    "RATE_LIMIT_PER_SECOND",
+    "RATE_LIMIT_BURST",
+    "STELLAR_MAX_RETRIES",
+    "LOG_LEVEL",
+    "WEBHOOK_URLS",
+    "WEBHOOK_SECRET",
+    "WEBHOOK_SECRET_FILE",
+    "ADMIN_TOKEN",
+    "ADMIN_TOKEN_FILE",
+    "CACHE_VERIFICATION_TTL",
+    "CACHE_NEGATIVE_TTL",
+    "CACHE_WRITE_BEHIND_CAPACITY",
+    "CACHE_MAX_ENTRIES",
+    "CACHE_FALLBACK_COOLDOWN_SECS",
+    "LISTEN_ADDRS",
+    "LISTEN_UDS",
+    "LISTEN_UDS_MODE",
+    "CLOCK_SKEW_TOLERANCE_SECS",
+    "SKIP_SELF_CHECK",
+    "SELF_CHECK_MIN_BALANCE_XLM",
+    "ACCEPTED_HASH_ALGORITHMS",
+    "REQUEST_TIMEOUT_SECS",
+    "SHUTDOWN_DRAIN_SECS",
+    "SHUTDOWN_PRESTOP_SECS",
+    "PUBLIC_RECENT_ENABLED",
+    "PUBLIC_RECENT_RATE_LIMIT_PER_SECOND",
+    "ANCHOR_METHOD",
+    "STELLAR_ADAPTIVE_TIMEOUT_MULTIPLIER",
+    "BATCH_CONCURRENCY",
+];
+
+/// Prefixes owned by this service; unknown variables under them are reported.
+const KNOWN_PREFIXES: &[&str] = &["STELLAR_", "WEBHOOK_", "RATE_LIMIT_", "CACHE_", "PUBLIC_"];
+
+/// Tolerance applied when comparing timestamps issued by other services.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeConfig {
+    pub skew_tolerance_secs: u64,
+}
+
+impl Default for TimeConfig {
+    fn default() -> Self {
+        Self {
+            skew_tolerance_secs: 300,
+        }
+    }
+}
+
+impl TimeConfig {
+    /// `timestamp` is within the tolerance of `now`, in either direction.
+    pub fn within_skew(&self, timestamp: i64, now: i64) -> bool {
+        timestamp.abs_diff(now) <= self.skew_tolerance_secs
+    }
+
+    /// `timestamp` is not later than `now` by more than the tolerance.
+    pub fn not_in_future(&self, timestamp: i64, now: i64) -> bool {
+        timestamp.saturating_sub(now) <= self.skew_tolerance_secs as i64
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AppConfig {
     pub port: u16,
     pub stellar_horizon_url: String,
-    pub stellar_secret_key: Option<String>,
+    /// Loaded from `STELLAR_SECRET_KEY` or `STELLAR_SECRET_KEY_FILE`.
+    pub stellar_secret_key: Option<Secret>,
     pub redis_url: String,
     pub rate_limit_per_second: u32,
     pub rate_limit_burst: u32,
     pub stellar_max_retries: u32,
     pub log_level: String,
     pub webhook_urls: Vec<String>,
-    pub webhook_secret: Option<String>,
+    /// Loaded from `WEBHOOK_SECRET` or `WEBHOOK_SECRET_FILE`.
+    pub webhook_secret: Option<Secret>,
+    /// Bearer token for the admin routes (`/admin/*` and `DELETE /cache`),
+    /// from `ADMIN_TOKEN` or `ADMIN_TOKEN_FILE`. Those routes are disabled
+    /// when unset.
+    pub admin_token: Option<Secret>,
     pub cache_verification_ttl: u64,
+    /// TTL for results that found no anchor; shorter so they re-check sooner.
+    pub cache_negative_ttl: u64,
+    /// Maximum number of failed cache writes held for background retry.
+    pub cache_write_behind_capacity: usize,
+    /// Capacity of the in-memory cache before least recently used keys are evicted.
+    pub cache_max_entries: usize,
+    /// Seconds to serve from memory after a Redis failure before retrying Redis.
+    pub cache_fallback_cooldown_secs: u64,
+    /// TCP addresses to serve on; defaults to `0.0.0.0:{port}`.
+    pub listen_addrs: Vec<SocketAddr>,
+    /// Optional Unix domain socket path served alongside the TCP listeners.
+    pub listen_uds: Option<PathBuf>,
+    /// Permission bits applied to the Unix socket file.
+    pub listen_uds_mode: u32,
+    /// Clock skew allowed when checking proof and webhook timestamps.
+    pub time: TimeConfig,
+    /// Skip the startup self-check (`SKIP_SELF_CHECK=true`).
+    pub skip_self_check: bool,
+    /// Balance below which the self-check warns about the anchoring account.
+    pub self_check_min_balance_xlm: f64,
+    /// Hash algorithms this deployment verifies and anchors; defaults to all.
+    pub accepted_hash_algorithms: Vec<HashAlgorithm>,
+    /// Requests running longer than this are answered with `408`.
+    pub request_timeout_secs: u64,
+    /// How long shutdown waits for in-flight requests before exiting.
+    pub shutdown_drain_secs: u64,
+    /// How long `/health` reports `draining` while listeners keep accepting,
+    /// so load balancers stop routing here before connections are refused.
+    pub shutdown_prestop_secs: u64,
+    /// Serve `GET /public/recent`; tenants treating partial hashes as
+    /// sensitive set `PUBLIC_RECENT_ENABLED=false`.
+    pub public_recent_enabled: bool,
+    /// Rate limit for `GET /public/recent`, separate from the API limit.
+    pub public_recent_rate_limit_per_second: u32,
+    /// How submitted hashes are recorded on-chain (`memo` or `manage_data`).
+    pub anchor_method: AnchorMethod,
+    /// When set, Horizon requests time out after this multiple of the
+    /// client's average latency. Unset disables adaptive timeouts.
+    pub stellar_adaptive_timeout_multiplier: Option<f64>,
+    /// Most Horizon queries a single batch verification runs at once.
+    pub batch_concurrency: usize,
+    /// Non-fatal issues found while loading (e.g. unknown variables).
+    pub warnings: Vec<ConfigIssue>,
+}
+
+/// Where a configuration value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueSource {
+    Environment,
+    /// A secret read from the file named by `NAME_FILE`.
+    File,
+    Default,
+    Unset,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single configuration problem. Errors are fatal, warnings are not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub field: String,
+    pub value_source: ValueSource,
+    pub message: String,
+    pub severity: Severity,
+}
+
+impl ConfigIssue {
+    fn error(field: &str, value_source: ValueSource, message: String) -> Self {
+        Self {
+            field: field.to_string(),
+            value_source,
+            message,
+            severity: Severity::Error,
+        }
+    }
+
+    fn warning(field: &str, value_source: ValueSource, message: String) -> Self {
+        Self {
+            field: field.to_string(),
+            value_source,
+            message,
+            severity: Severity::Warning,
+        }
+    }
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Environment => write!(f, "env"),
+            Self::File => write!(f, "file"),
+            Self::Default => write!(f, "default"),
+            Self::Unset => write!(f, "unset"),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+        }
+    }
 }
 
 #[derive(Debug, Error)]
 pub enum ConfigError {
-    #[error("configuration validation failed:\n{0}")]
-    Validation(String),
+    #[error("configuration validation failed:\n{}", summarize_issues(.0))]
+    Validation(Vec<ConfigIssue>),
+}
+
+impl ConfigError {
+    pub fn issues(&self) -> &[ConfigIssue] {
+        match self {
+            Self::Validation(issues) => issues,
+        }
+    }
+}
+
+fn summarize_issues(issues: &[ConfigIssue]) -> String {
+    issues
+        .iter()
+        .map(|issue| match issue.severity {
+            Severity::Error => format!("- {}", issue.message),
+            Severity::Warning => format!("- warning: {}", issue.message),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Render issues as a fixed-width table for startup output.
+pub fn format_issue_table(issues: &[ConfigIssue]) -> String {
+    let field_width = issues
+        .iter()
+        .map(|i| i.field.len())
+        .chain(std::iter::once("FIELD".len()))
+        .max()
+        .unwrap_or(0);
+
+    let mut out = format!(
+        "{:<8} {:<field_width$} {:<8} MESSAGE\n",
+        "SEVERITY", "FIELD", "SOURCE"
+    );
+    for issue in issues {
+        out.push_str(&format!(
+            "{:<8} {:<field_width$} {:<8} {}\n",
+            issue.severity.to_string(),
+            issue.field,
+            issue.value_source.to_string(),
+            issue.message
+        ));
+    }
+    out
+}
+
+/// Flag variables that look like ours but match no known setting: anything
+/// under one of our prefixes, or a near-miss spelling of a known name.
+/// Where the environment provider reads secret `name` from: the file named
+/// by `NAME_FILE` when only that is set, otherwise `NAME` itself.
+fn secret_source(name: &str) -> ValueSource {
+    let file_set = env::var(format!("{}_FILE", name)).is_ok_and(|p| !p.trim().is_empty());
+    if file_set && env::var(name).is_err() {
+        ValueSource::File
+    } else {
+        ValueSource::Environment
+    }
+}
+
+fn unknown_variable_warnings<I>(vars: I) -> Vec<ConfigIssue>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut warnings: Vec<ConfigIssue> = vars
+        .into_iter()
+        .filter(|name| !KNOWN_VARS.contains(&name.as_str()))
+        .filter_map(|name| {
+            let prefixed = KNOWN_PREFIXES.iter().any(|p| name.starts_with(p));
+            // Outside our prefixes only near-exact typos are worth flagging;
+            // short unrelated names (`HOST` vs `PORT`) are often within 2.
+            let max_distance = if prefixed { 2 } else { 1 };
+            let suggestion = KNOWN_VARS
+                .iter()
+                .map(|known| (known, crate::levenshtein_distance(&name, known)))
+                .filter(|(_, distance)| *distance <= max_distance)
+                .min_by_key(|(_, distance)| *distance)
+                .map(|(known, _)| *known);
+
+            let message = match suggestion {
+                Some(known) => format!(
+                    "unknown variable {} is ignored; did you mean {}?",
+                    name, known
+                ),
+                None if prefixed => format!("unknown variable {} is ignored", name),
+                None => return None,
+            };
+            Some(ConfigIssue::warning(
+                &name,
+                ValueSource::Environment,
+                message,
+            ))
+        })
+        .collect();
+    warnings.sort_by(|a, b| a.field.cmp(&b.field));
+    warnings
 }
 
 impl AppConfig {
     pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_env_with(&EnvSecretProvider)
+    }
+
+    /// Like [`AppConfig::from_env`], but secrets come from `secrets`.
+    pub fn from_env_with(secrets: &dyn SecretProvider) -> Result<Self, ConfigError> {
         let mut errors = Vec::new();
 
         // Helper to read env var with default
@@ -33,6 +321,14 @@ impl AppConfig {
             env::var(key).unwrap_or_else(|_| default.to_string())
         }
 
+        fn source_of(key: &str) -> ValueSource {
+            if env::var(key).is_ok() {
+                ValueSource::Environment
+            } else {
+                ValueSource::Default
+            }
+        }
+
         // Basic string values with defaults
         let port_raw = get_env_or_default("PORT", "8080");
         let stellar_horizon_url =
@@ -41,26 +337,61 @@ impl AppConfig {
         let log_level = get_env_or_default("LOG_LEVEL", "info");
         let webhook_urls_raw = get_env_or_default("WEBHOOK_URLS", "");
 
-        let stellar_secret_key = match env::var("STELLAR_SECRET_KEY") {
-            Ok(key) => {
+        let stellar_secret_key = match secrets.load("STELLAR_SECRET_KEY") {
+            Ok(Some(key)) => {
                 // Validate the secret key format (should be 56 chars starting with 'S')
-                if key.len() != 56 || !key.starts_with('S') {
-                    errors.push(
+                let raw = key.expose();
+                if raw.len() != 56 || !raw.starts_with('S') {
+                    errors.push(ConfigIssue::error(
+                        "STELLAR_SECRET_KEY",
+                        secret_source("STELLAR_SECRET_KEY"),
                         "STELLAR_SECRET_KEY must be a 56-character string starting with 'S'"
                             .to_string(),
-                    );
+                    ));
                 }
                 Some(key)
             }
-            Err(_) => {
-                errors.push(
-                    "STELLAR_SECRET_KEY is required but not set. Please set the environment variable."
+            Ok(None) => {
+                errors.push(ConfigIssue::error(
+                    "STELLAR_SECRET_KEY",
+                    ValueSource::Unset,
+                    "STELLAR_SECRET_KEY is required but not set. Please set STELLAR_SECRET_KEY or STELLAR_SECRET_KEY_FILE."
                         .to_string(),
-                );
+                ));
+                None
+            }
+            Err(e) => {
+                errors.push(ConfigIssue::error(
+                    "STELLAR_SECRET_KEY",
+                    secret_source("STELLAR_SECRET_KEY"),
+                    e.to_string(),
+                ));
+                None
+            }
+        };
+        let webhook_secret = match secrets.load("WEBHOOK_SECRET") {
+            Ok(secret) => secret,
+            Err(e) => {
+                errors.push(ConfigIssue::error(
+                    "WEBHOOK_SECRET",
+                    secret_source("WEBHOOK_SECRET"),
+                    e.to_string(),
+                ));
+                None
+            }
+        };
+
+        let admin_token = match secrets.load("ADMIN_TOKEN") {
+            Ok(secret) => secret,
+            Err(e) => {
+                errors.push(ConfigIssue::error(
+                    "ADMIN_TOKEN",
+                    secret_source("ADMIN_TOKEN"),
+                    e.to_string(),
+                ));
                 None
             }
         };
-        let webhook_secret = env::var("WEBHOOK_SECRET").ok();
 
         // Numeric values with defaults
         let rate_limit_per_second_raw = get_env_or_default("RATE_LIMIT_PER_SECOND", "10");
@@ -68,25 +399,43 @@ impl AppConfig {
             get_env_or_default("RATE_LIMIT_BURST", &rate_limit_per_second_raw);
         let stellar_max_retries_raw = get_env_or_default("STELLAR_MAX_RETRIES", "3");
         let cache_verification_ttl_raw = get_env_or_default("CACHE_VERIFICATION_TTL", "3600");
+        let cache_negative_ttl_raw = get_env_or_default("CACHE_NEGATIVE_TTL", "300");
+        let cache_write_behind_capacity_raw =
+            get_env_or_default("CACHE_WRITE_BEHIND_CAPACITY", "1000");
+        let cache_max_entries_raw = get_env_or_default("CACHE_MAX_ENTRIES", "100000");
+        let cache_fallback_cooldown_secs_raw =
+            get_env_or_default("CACHE_FALLBACK_COOLDOWN_SECS", "30");
 
         // Parse and validate port
         let port: u16 = match port_raw.parse() {
             Ok(p) if p > 0 => p,
             Ok(_) => {
-                errors.push("PORT must be between 1 and 65535".to_string());
+                errors.push(ConfigIssue::error(
+                    "PORT",
+                    source_of("PORT"),
+                    "PORT must be between 1 and 65535".to_string(),
+                ));
                 8080
             }
             Err(_) => {
-                errors.push(format!("PORT must be a valid u16, got '{}'", port_raw));
+                errors.push(ConfigIssue::error(
+                    "PORT",
+                    source_of("PORT"),
+                    format!("PORT must be a valid u16, got '{}'", port_raw),
+                ));
                 8080
             }
         };
 
         // Validate horizon URL
         if Url::parse(&stellar_horizon_url).is_err() {
-            errors.push(format!(
-                "STELLAR_HORIZON_URL must be a valid URL, got '{}'",
-                stellar_horizon_url
+            errors.push(ConfigIssue::error(
+                "STELLAR_HORIZON_URL",
+                source_of("STELLAR_HORIZON_URL"),
+                format!(
+                    "STELLAR_HORIZON_URL must be a valid URL, got '{}'",
+                    stellar_horizon_url
+                ),
             ));
         }
 
@@ -94,13 +443,21 @@ impl AppConfig {
         let rate_limit_per_second: u32 = match rate_limit_per_second_raw.parse() {
             Ok(v) if v > 0 => v,
             Ok(_) => {
-                errors.push("RATE_LIMIT_PER_SECOND must be greater than 0".to_string());
+                errors.push(ConfigIssue::error(
+                    "RATE_LIMIT_PER_SECOND",
+                    source_of("RATE_LIMIT_PER_SECOND"),
+                    "RATE_LIMIT_PER_SECOND must be greater than 0".to_string(),
+                ));
                 10
             }
             Err(_) => {
-                errors.push(format!(
-                    "RATE_LIMIT_PER_SECOND must be a valid u32, got '{}'",
-                    rate_limit_per_second_raw
+                errors.push(ConfigIssue::error(
+                    "RATE_LIMIT_PER_SECOND",
+                    source_of("RATE_LIMIT_PER_SECOND"),
+                    format!(
+                        "RATE_LIMIT_PER_SECOND must be a valid u32, got '{}'",
+                        rate_limit_per_second_raw
+                    ),
                 ));
                 10
             }
@@ -109,9 +466,13 @@ impl AppConfig {
         let rate_limit_burst: u32 = match rate_limit_burst_raw.parse() {
             Ok(v) => v,
             Err(_) => {
-                errors.push(format!(
-                    "RATE_LIMIT_BURST must be a valid u32, got '{}'",
-                    rate_limit_burst_raw
+                errors.push(ConfigIssue::error(
+                    "RATE_LIMIT_BURST",
+                    source_of("RATE_LIMIT_BURST"),
+                    format!(
+                        "RATE_LIMIT_BURST must be a valid u32, got '{}'",
+                        rate_limit_burst_raw
+                    ),
                 ));
                 rate_limit_per_second
             }
@@ -120,9 +481,13 @@ impl AppConfig {
         let stellar_max_retries: u32 = match stellar_max_retries_raw.parse() {
             Ok(v) => v,
             Err(_) => {
-                errors.push(format!(
-                    "STELLAR_MAX_RETRIES must be a valid u32, got '{}'",
-                    stellar_max_retries_raw
+                errors.push(ConfigIssue::error(
+                    "STELLAR_MAX_RETRIES",
+                    source_of("STELLAR_MAX_RETRIES"),
+                    format!(
+                        "STELLAR_MAX_RETRIES must be a valid u32, got '{}'",
+                        stellar_max_retries_raw
+                    ),
                 ));
                 3
             }
@@ -131,14 +496,336 @@ impl AppConfig {
         let cache_verification_ttl: u64 = match cache_verification_ttl_raw.parse() {
             Ok(v) => v,
             Err(_) => {
-                errors.push(format!(
-                    "CACHE_VERIFICATION_TTL must be a valid u64, got '{}'",
-                    cache_verification_ttl_raw
+                errors.push(ConfigIssue::error(
+                    "CACHE_VERIFICATION_TTL",
+                    source_of("CACHE_VERIFICATION_TTL"),
+                    format!(
+                        "CACHE_VERIFICATION_TTL must be a valid u64, got '{}'",
+                        cache_verification_ttl_raw
+                    ),
                 ));
                 3600
             }
         };
 
+        let cache_negative_ttl: u64 = match cache_negative_ttl_raw.parse() {
+            Ok(v) => v,
+            Err(_) => {
+                errors.push(ConfigIssue::error(
+                    "CACHE_NEGATIVE_TTL",
+                    source_of("CACHE_NEGATIVE_TTL"),
+                    format!(
+                        "CACHE_NEGATIVE_TTL must be a valid u64, got '{}'",
+                        cache_negative_ttl_raw
+                    ),
+                ));
+                300
+            }
+        };
+
+        let cache_write_behind_capacity: usize = match cache_write_behind_capacity_raw.parse() {
+            Ok(v) => v,
+            Err(_) => {
+                errors.push(ConfigIssue::error(
+                    "CACHE_WRITE_BEHIND_CAPACITY",
+                    source_of("CACHE_WRITE_BEHIND_CAPACITY"),
+                    format!(
+                        "CACHE_WRITE_BEHIND_CAPACITY must be a valid usize, got '{}'",
+                        cache_write_behind_capacity_raw
+                    ),
+                ));
+                1000
+            }
+        };
+
+        let cache_max_entries: usize = match cache_max_entries_raw.parse() {
+            Ok(v) if v > 0 => v,
+            _ => {
+                errors.push(ConfigIssue::error(
+                    "CACHE_MAX_ENTRIES",
+                    source_of("CACHE_MAX_ENTRIES"),
+                    format!(
+                        "CACHE_MAX_ENTRIES must be a positive integer, got '{}'",
+                        cache_max_entries_raw
+                    ),
+                ));
+                100_000
+            }
+        };
+
+        let cache_fallback_cooldown_secs: u64 = match cache_fallback_cooldown_secs_raw.parse() {
+            Ok(v) => v,
+            Err(_) => {
+                errors.push(ConfigIssue::error(
+                    "CACHE_FALLBACK_COOLDOWN_SECS",
+                    source_of("CACHE_FALLBACK_COOLDOWN_SECS"),
+                    format!(
+                        "CACHE_FALLBACK_COOLDOWN_SECS must be a valid u64, got '{}'",
+                        cache_fallback_cooldown_secs_raw
+                    ),
+                ));
+                30
+            }
+        };
+
+        // Listener addresses (comma-separated, IPv4 or IPv6)
+        let listen_addrs_raw = get_env_or_default("LISTEN_ADDRS", &format!("0.0.0.0:{}", port));
+        let mut listen_addrs = Vec::new();
+        for raw in listen_addrs_raw
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            match raw.parse::<SocketAddr>() {
+                Ok(addr) => listen_addrs.push(addr),
+                Err(_) => errors.push(ConfigIssue::error(
+                    "LISTEN_ADDRS",
+                    source_of("LISTEN_ADDRS"),
+                    format!("LISTEN_ADDRS entry must be a socket address, got '{}'", raw),
+                )),
+            }
+        }
+
+        let listen_uds = env::var("LISTEN_UDS")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .map(PathBuf::from);
+
+        if listen_addrs.is_empty() && listen_uds.is_none() {
+            errors.push(ConfigIssue::error(
+                "LISTEN_ADDRS",
+                source_of("LISTEN_ADDRS"),
+                "at least one of LISTEN_ADDRS or LISTEN_UDS must be set".to_string(),
+            ));
+        }
+
+        let listen_uds_mode_raw = get_env_or_default("LISTEN_UDS_MODE", "660");
+        let listen_uds_mode =
+            match u32::from_str_radix(listen_uds_mode_raw.trim_start_matches("0o"), 8) {
+                Ok(mode) if mode <= 0o777 => mode,
+                _ => {
+                    errors.push(ConfigIssue::error(
+                        "LISTEN_UDS_MODE",
+                        source_of("LISTEN_UDS_MODE"),
+                        format!(
+                            "LISTEN_UDS_MODE must be octal permission bits, got '{}'",
+                            listen_uds_mode_raw
+                        ),
+                    ));
+                    0o660
+                }
+            };
+
+        let skew_raw = get_env_or_default("CLOCK_SKEW_TOLERANCE_SECS", "300");
+        let time = match skew_raw.parse() {
+            Ok(skew_tolerance_secs) => TimeConfig {
+                skew_tolerance_secs,
+            },
+            Err(_) => {
+                errors.push(ConfigIssue::error(
+                    "CLOCK_SKEW_TOLERANCE_SECS",
+                    source_of("CLOCK_SKEW_TOLERANCE_SECS"),
+                    format!(
+                        "CLOCK_SKEW_TOLERANCE_SECS must be a valid u64, got '{}'",
+                        skew_raw
+                    ),
+                ));
+                TimeConfig::default()
+            }
+        };
+
+        let skip_self_check_raw = get_env_or_default("SKIP_SELF_CHECK", "false");
+        let skip_self_check = match skip_self_check_raw.to_lowercase().as_str() {
+            "true" | "1" | "yes" => true,
+            "false" | "0" | "no" => false,
+            _ => {
+                errors.push(ConfigIssue::error(
+                    "SKIP_SELF_CHECK",
+                    source_of("SKIP_SELF_CHECK"),
+                    format!(
+                        "SKIP_SELF_CHECK must be true or false, got '{}'",
+                        skip_self_check_raw
+                    ),
+                ));
+                false
+            }
+        };
+
+        let min_balance_raw = get_env_or_default("SELF_CHECK_MIN_BALANCE_XLM", "5");
+        let self_check_min_balance_xlm = match min_balance_raw.parse::<f64>() {
+            Ok(v) if v >= 0.0 => v,
+            _ => {
+                errors.push(ConfigIssue::error(
+                    "SELF_CHECK_MIN_BALANCE_XLM",
+                    source_of("SELF_CHECK_MIN_BALANCE_XLM"),
+                    format!(
+                        "SELF_CHECK_MIN_BALANCE_XLM must be a non-negative number, got '{}'",
+                        min_balance_raw
+                    ),
+                ));
+                5.0
+            }
+        };
+
+        let accepted_raw = env::var("ACCEPTED_HASH_ALGORITHMS").unwrap_or_default();
+        let mut accepted_hash_algorithms = Vec::new();
+        for name in accepted_raw
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            match HashAlgorithm::from_name(name) {
+                Some(algorithm) if !accepted_hash_algorithms.contains(&algorithm) => {
+                    accepted_hash_algorithms.push(algorithm)
+                }
+                Some(_) => {}
+                None => errors.push(ConfigIssue::error(
+                    "ACCEPTED_HASH_ALGORITHMS",
+                    ValueSource::Environment,
+                    format!(
+                        "ACCEPTED_HASH_ALGORITHMS entry must be one of sha1, sha256, sha512, got '{}'",
+                        name
+                    ),
+                )),
+            }
+        }
+        if accepted_hash_algorithms.is_empty() {
+            accepted_hash_algorithms = HashAlgorithm::ALL.to_vec();
+        }
+
+        let request_timeout_raw = get_env_or_default("REQUEST_TIMEOUT_SECS", "30");
+        let request_timeout_secs: u64 = match request_timeout_raw.parse() {
+            Ok(v) if v > 0 => v,
+            _ => {
+                errors.push(ConfigIssue::error(
+                    "REQUEST_TIMEOUT_SECS",
+                    source_of("REQUEST_TIMEOUT_SECS"),
+                    format!(
+                        "REQUEST_TIMEOUT_SECS must be a positive integer, got '{}'",
+                        request_timeout_raw
+                    ),
+                ));
+                30
+            }
+        };
+
+        let shutdown_drain_raw = get_env_or_default("SHUTDOWN_DRAIN_SECS", "30");
+        let shutdown_drain_secs: u64 = match shutdown_drain_raw.parse() {
+            Ok(v) => v,
+            Err(_) => {
+                errors.push(ConfigIssue::error(
+                    "SHUTDOWN_DRAIN_SECS",
+                    source_of("SHUTDOWN_DRAIN_SECS"),
+                    format!(
+                        "SHUTDOWN_DRAIN_SECS must be a valid u64, got '{}'",
+                        shutdown_drain_raw
+                    ),
+                ));
+                30
+            }
+        };
+
+        let shutdown_prestop_raw = get_env_or_default("SHUTDOWN_PRESTOP_SECS", "5");
+        let shutdown_prestop_secs: u64 = match shutdown_prestop_raw.parse() {
+            Ok(v) => v,
+            Err(_) => {
+                errors.push(ConfigIssue::error(
+                    "SHUTDOWN_PRESTOP_SECS",
+                    source_of("SHUTDOWN_PRESTOP_SECS"),
+                    format!(
+                        "SHUTDOWN_PRESTOP_SECS must be a valid u64, got '{}'",
+                        shutdown_prestop_raw
+                    ),
+                ));
+                5
+            }
+        };
+
+        let public_recent_raw = get_env_or_default("PUBLIC_RECENT_ENABLED", "true");
+        let public_recent_enabled = match public_recent_raw.to_lowercase().as_str() {
+            "true" | "1" | "yes" => true,
+            "false" | "0" | "no" => false,
+            _ => {
+                errors.push(ConfigIssue::error(
+                    "PUBLIC_RECENT_ENABLED",
+                    source_of("PUBLIC_RECENT_ENABLED"),
+                    format!(
+                        "PUBLIC_RECENT_ENABLED must be true or false, got '{}'",
+                        public_recent_raw
+                    ),
+                ));
+                true
+            }
+        };
+
+        let public_rate_raw = get_env_or_default("PUBLIC_RECENT_RATE_LIMIT_PER_SECOND", "5");
+        let public_recent_rate_limit_per_second: u32 = match public_rate_raw.parse() {
+            Ok(v) if v > 0 => v,
+            _ => {
+                errors.push(ConfigIssue::error(
+                    "PUBLIC_RECENT_RATE_LIMIT_PER_SECOND",
+                    source_of("PUBLIC_RECENT_RATE_LIMIT_PER_SECOND"),
+                    format!(
+                        "PUBLIC_RECENT_RATE_LIMIT_PER_SECOND must be a positive integer, got '{}'",
+                        public_rate_raw
+                    ),
+                ));
+                5
+            }
+        };
+
+        let anchor_method_raw = get_env_or_default("ANCHOR_METHOD", "manage_data");
+        let anchor_method = match AnchorMethod::from_name(&anchor_method_raw) {
+            Some(method) => method,
+            None => {
+                errors.push(ConfigIssue::error(
+                    "ANCHOR_METHOD",
+                    source_of("ANCHOR_METHOD"),
+                    format!(
+                        "ANCHOR_METHOD must be memo or manage_data, got '{}'",
+                        anchor_method_raw
+                    ),
+                ));
+                AnchorMethod::default()
+            }
+        };
+
+        let adaptive_timeout_raw =
+            env::var("STELLAR_ADAPTIVE_TIMEOUT_MULTIPLIER").unwrap_or_default();
+        let stellar_adaptive_timeout_multiplier = match adaptive_timeout_raw.trim() {
+            "" => None,
+            raw => match raw.parse::<f64>() {
+                Ok(v) if v >= 1.0 => Some(v),
+                _ => {
+                    errors.push(ConfigIssue::error(
+                        "STELLAR_ADAPTIVE_TIMEOUT_MULTIPLIER",
+                        ValueSource::Environment,
+                        format!(
+                            "STELLAR_ADAPTIVE_TIMEOUT_MULTIPLIER must be a number of at least 1, got '{}'",
+                            raw
+                        ),
+                    ));
+                    None
+                }
+            },
+        };
+
+        let batch_concurrency_raw = get_env_or_default("BATCH_CONCURRENCY", "8");
+        let batch_concurrency: usize = match batch_concurrency_raw.parse() {
+            Ok(v) if v > 0 => v,
+            _ => {
+                errors.push(ConfigIssue::error(
+                    "BATCH_CONCURRENCY",
+                    source_of("BATCH_CONCURRENCY"),
+                    format!(
+                        "BATCH_CONCURRENCY must be a positive integer, got '{}'",
+                        batch_concurrency_raw
+                    ),
+                ));
+                8
+            }
+        };
+
         // Parse webhook URLs (comma-separated, ignore empty)
         let webhook_urls: Vec<String> = webhook_urls_raw
             .split(',')
@@ -147,9 +834,11 @@ impl AppConfig {
             .map(String::from)
             .collect();
 
+        let warnings = unknown_variable_warnings(env::vars().map(|(key, _)| key));
+
         if !errors.is_empty() {
-            let joined = errors.join("\n- ");
-            return Err(ConfigError::Validation(format!("- {}", joined)));
+            errors.extend(warnings);
+            return Err(ConfigError::Validation(errors));
         }
 
         Ok(Self {
@@ -163,7 +852,28 @@ impl AppConfig {
             log_level,
             webhook_urls,
             webhook_secret,
+            admin_token,
             cache_verification_ttl,
+            cache_negative_ttl,
+            cache_write_behind_capacity,
+            cache_max_entries,
+            cache_fallback_cooldown_secs,
+            listen_addrs,
+            listen_uds,
+            listen_uds_mode,
+            time,
+            skip_self_check,
+            self_check_min_balance_xlm,
+            accepted_hash_algorithms,
+            request_timeout_secs,
+            shutdown_drain_secs,
+            shutdown_prestop_secs,
+            public_recent_enabled,
+            public_recent_rate_limit_per_second,
+            anchor_method,
+            stellar_adaptive_timeout_multiplier,
+            batch_concurrency,
+            warnings,
         })
     }
 }
@@ -180,6 +890,7 @@ mod tests {
             "PORT",
             "STELLAR_HORIZON_URL",
             "STELLAR_SECRET_KEY",
+            "STELLAR_SECRET_KEY_FILE",
             "REDIS_URL",
             "RATE_LIMIT_PER_SECOND",
             "RATE_LIMIT_BURST",
@@ -187,7 +898,29 @@ mod tests {
             "LOG_LEVEL",
             "WEBHOOK_URLS",
             "WEBHOOK_SECRET",
+            "WEBHOOK_SECRET_FILE",
+            "ADMIN_TOKEN",
+            "ADMIN_TOKEN_FILE",
             "CACHE_VERIFICATION_TTL",
+            "CACHE_NEGATIVE_TTL",
+            "CACHE_WRITE_BEHIND_CAPACITY",
+            "CACHE_MAX_ENTRIES",
+            "CACHE_FALLBACK_COOLDOWN_SECS",
+            "LISTEN_ADDRS",
+            "LISTEN_UDS",
+            "LISTEN_UDS_MODE",
+            "CLOCK_SKEW_TOLERANCE_SECS",
+            "SKIP_SELF_CHECK",
+            "SELF_CHECK_MIN_BALANCE_XLM",
+            "ACCEPTED_HASH_ALGORITHMS",
+            "REQUEST_TIMEOUT_SECS",
+            "SHUTDOWN_DRAIN_SECS",
+            "SHUTDOWN_PRESTOP_SECS",
+            "PUBLIC_RECENT_ENABLED",
+            "PUBLIC_RECENT_RATE_LIMIT_PER_SECOND",
+            "ANCHOR_METHOD",
+            "STELLAR_ADAPTIVE_TIMEOUT_MULTIPLIER",
+            "BATCH_CONCURRENCY",
         ];
         for key in keys {
             env::remove_var(key);
@@ -212,6 +945,61 @@ mod tests {
         assert_eq!(cfg.redis_url, "redis://127.0.0.1:6379");
         assert_eq!(cfg.rate_limit_per_second, 10);
         assert_eq!(cfg.cache_verification_ttl, 3600);
+        assert_eq!(cfg.cache_negative_ttl, 300);
+        assert_eq!(cfg.cache_write_behind_capacity, 1000);
+        assert_eq!(cfg.cache_max_entries, 100_000);
+        assert_eq!(cfg.cache_fallback_cooldown_secs, 30);
+        assert_eq!(cfg.listen_addrs, vec!["0.0.0.0:8080".parse().unwrap()]);
+        assert_eq!(cfg.listen_uds, None);
+        assert_eq!(cfg.time.skew_tolerance_secs, 300);
+        assert!(!cfg.skip_self_check);
+        assert_eq!(cfg.accepted_hash_algorithms, HashAlgorithm::ALL.to_vec());
+        assert_eq!(cfg.request_timeout_secs, 30);
+        assert_eq!(cfg.shutdown_drain_secs, 30);
+        assert_eq!(cfg.shutdown_prestop_secs, 5);
+        assert!(cfg.public_recent_enabled);
+        assert_eq!(cfg.anchor_method, AnchorMethod::ManageData);
+        assert_eq!(cfg.stellar_adaptive_timeout_multiplier, None);
+        assert_eq!(cfg.batch_concurrency, 8);
+    }
+
+    #[test]
+    fn time_config_skew_boundaries() {
+        let time = TimeConfig::default();
+        let now = 1_700_000_000;
+
+        assert!(time.within_skew(now - 300, now));
+        assert!(time.within_skew(now + 300, now));
+        assert!(!time.within_skew(now - 301, now));
+        assert!(!time.within_skew(now + 301, now));
+
+        assert!(time.not_in_future(now + 300, now));
+        assert!(!time.not_in_future(now + 301, now));
+        assert!(time.not_in_future(now - 86_400, now));
+    }
+
+    #[test]
+    fn from_env_parses_listeners() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_env();
+        env::set_var(
+            "STELLAR_SECRET_KEY",
+            "SAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA",
+        );
+        env::set_var("LISTEN_ADDRS", "127.0.0.1:9000, [::1]:9000");
+        env::set_var("LISTEN_UDS", "/tmp/verifier.sock");
+        env::set_var("LISTEN_UDS_MODE", "0600");
+
+        let cfg = AppConfig::from_env().expect("config should load");
+
+        assert_eq!(cfg.listen_addrs.len(), 2);
+        assert!(cfg.listen_addrs[1].is_ipv6());
+        assert_eq!(cfg.listen_uds, Some(PathBuf::from("/tmp/verifier.sock")));
+        assert_eq!(cfg.listen_uds_mode, 0o600);
+
+        env::set_var("LISTEN_ADDRS", "localhost:9000");
+        let err = AppConfig::from_env().expect_err("hostnames are not socket addresses");
+        assert!(err.to_string().contains("LISTEN_ADDRS entry"));
     }
 
     #[test]
@@ -230,6 +1018,73 @@ mod tests {
         assert!(msg.contains("RATE_LIMIT_PER_SECOND must be greater than 0"));
     }
 
+    #[test]
+    fn from_env_reports_structured_issues() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_env();
+        env::set_var("PORT", "not-a-port");
+
+        let err = AppConfig::from_env().expect_err("config should fail");
+        let issues = err.issues();
+
+        let port = issues.iter().find(|i| i.field == "PORT").unwrap();
+        assert_eq!(port.severity, Severity::Error);
+        assert_eq!(port.value_source, ValueSource::Environment);
+        assert!(port.message.contains("got 'not-a-port'"));
+
+        let secret = issues
+            .iter()
+            .find(|i| i.field == "STELLAR_SECRET_KEY")
+            .unwrap();
+        assert_eq!(secret.severity, Severity::Error);
+        assert_eq!(secret.value_source, ValueSource::Unset);
+
+        let table = format_issue_table(issues);
+        assert!(table.starts_with("SEVERITY"));
+        assert!(table.contains("PORT"));
+    }
+
+    #[test]
+    fn from_env_warns_about_misspelled_variables() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_env();
+        env::set_var(
+            "STELLAR_SECRET_KEY",
+            "SAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA",
+        );
+        env::set_var("STELLER_HORIZON_URL", "https://horizon.stellar.org");
+        env::set_var("CACHE_TTL_SECONDS", "60");
+
+        let cfg = AppConfig::from_env();
+        env::remove_var("STELLER_HORIZON_URL");
+        env::remove_var("CACHE_TTL_SECONDS");
+        let cfg = cfg.expect("warnings must not be fatal");
+
+        let typo = cfg
+            .warnings
+            .iter()
+            .find(|w| w.field == "STELLER_HORIZON_URL")
+            .expect("typo should be reported");
+        assert_eq!(typo.severity, Severity::Warning);
+        assert!(typo.message.contains("did you mean STELLAR_HORIZON_URL?"));
+
+        assert!(cfg.warnings.iter().any(|w| w.field == "CACHE_TTL_SECONDS"));
+        assert_eq!(
+            cfg.stellar_horizon_url,
+            "https://horizon-testnet.stellar.org"
+        );
+    }
+
+    #[test]
+    fn unrelated_variables_are_not_mistaken_for_typos() {
+        let warnings = unknown_variable_warnings(
+            ["HOST", "PATH", "PORTS", "CACHE_NEGATIVE_TTLS"].map(String::from),
+        );
+        let fields: Vec<&str> = warnings.iter().map(|w| w.field.as_str()).collect();
+        assert_eq!(fields, ["CACHE_NEGATIVE_TTLS", "PORTS"]);
+        assert!(warnings[1].message.contains("did you mean PORT?"));
+    }
+
     #[test]
     fn from_env_parses_valid_config() {
         let _guard = ENV_LOCK.lock().unwrap();
@@ -252,4 +1107,133 @@ mod tests {
         assert_eq!(cfg.rate_limit_per_second, 100);
         assert_eq!(cfg.webhook_urls.len(), 2);
     }
+
+    const FILE_SECRET: &str = "SBFILEFILEFILEFILEFILEFILEFILEFILEFILEFILEFILEFILEFILEFI";
+
+    #[test]
+    fn from_env_reads_secrets_from_files() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_env();
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("stellar-key");
+        let webhook_path = dir.path().join("webhook-secret");
+        std::fs::write(&key_path, format!("{}\n", FILE_SECRET)).unwrap();
+        std::fs::write(&webhook_path, "  hook-secret \n").unwrap();
+        env::set_var("STELLAR_SECRET_KEY_FILE", &key_path);
+        env::set_var("WEBHOOK_SECRET_FILE", &webhook_path);
+
+        let cfg = AppConfig::from_env().expect("file secrets should load");
+        clear_env();
+
+        assert_eq!(cfg.stellar_secret_key.unwrap().expose(), FILE_SECRET);
+        assert_eq!(cfg.webhook_secret.unwrap().expose(), "hook-secret");
+        assert!(cfg.warnings.is_empty());
+    }
+
+    #[test]
+    fn from_env_rejects_conflicting_or_missing_secret_files() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_env();
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("stellar-key");
+        std::fs::write(&key_path, FILE_SECRET).unwrap();
+        env::set_var("STELLAR_SECRET_KEY", FILE_SECRET);
+        env::set_var("STELLAR_SECRET_KEY_FILE", &key_path);
+        env::set_var("WEBHOOK_SECRET_FILE", dir.path().join("missing"));
+
+        let err = AppConfig::from_env().expect_err("conflicting sources must fail");
+        clear_env();
+        let msg = err.to_string();
+
+        assert!(msg.contains("STELLAR_SECRET_KEY and STELLAR_SECRET_KEY_FILE are both set"));
+        assert!(msg.contains("WEBHOOK_SECRET_FILE points to"));
+        assert!(!msg.contains(FILE_SECRET));
+        let source = |field: &str| {
+            err.issues()
+                .iter()
+                .find(|issue| issue.field == field)
+                .unwrap()
+                .value_source
+        };
+        assert_eq!(source("STELLAR_SECRET_KEY"), ValueSource::Environment);
+        assert_eq!(source("WEBHOOK_SECRET"), ValueSource::File);
+    }
+
+    #[test]
+    fn invalid_secret_from_file_reports_file_source() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_env();
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("stellar-key");
+        std::fs::write(&key_path, "not-a-stellar-key").unwrap();
+        env::set_var("STELLAR_SECRET_KEY_FILE", &key_path);
+
+        let err = AppConfig::from_env().expect_err("malformed key must fail");
+        clear_env();
+
+        let issue = &err.issues()[0];
+        assert_eq!(issue.field, "STELLAR_SECRET_KEY");
+        assert_eq!(issue.value_source, ValueSource::File);
+        assert_eq!(issue.value_source.to_string(), "file");
+    }
+
+    #[test]
+    fn secrets_never_appear_in_formatted_config_or_errors() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_env();
+        env::set_var("STELLAR_SECRET_KEY", FILE_SECRET);
+        env::set_var("WEBHOOK_SECRET", "hook-secret-value");
+        let cfg = AppConfig::from_env().expect("config should load");
+        let debug = format!("{:?}", cfg);
+        assert!(!debug.contains(FILE_SECRET));
+        assert!(!debug.contains("hook-secret-value"));
+
+        // Wrong length, so validation fails on the secret itself.
+        let bad_key = &FILE_SECRET[..40];
+        env::set_var("STELLAR_SECRET_KEY", bad_key);
+        let err = AppConfig::from_env().expect_err("short key must fail");
+        clear_env();
+        assert!(!err.to_string().contains(bad_key));
+        assert!(!format!("{:?}", err).contains(bad_key));
+        assert!(!format_issue_table(err.issues()).contains(bad_key));
+    }
+
+    struct StaticSecrets;
+
+    impl SecretProvider for StaticSecrets {
+        fn name(&self) -> &str {
+            "static"
+        }
+
+        fn load(&self, name: &str) -> Result<Option<Secret>, crate::secrets::SecretError> {
+            Ok((name == "STELLAR_SECRET_KEY").then(|| Secret::new(FILE_SECRET)))
+        }
+    }
+
+    #[test]
+    fn from_env_with_uses_custom_secret_provider() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_env();
+        let cfg = AppConfig::from_env_with(&StaticSecrets).expect("provider supplies the key");
+        assert_eq!(cfg.stellar_secret_key.unwrap().expose(), FILE_SECRET);
+        assert!(cfg.webhook_secret.is_none());
+    }
+
+    #[test]
+    fn from_env_parses_accepted_hash_algorithms() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_env();
+        env::set_var("STELLAR_SECRET_KEY", FILE_SECRET);
+        env::set_var("ACCEPTED_HASH_ALGORITHMS", "SHA256, sha-512");
+        let cfg = AppConfig::from_env().expect("config should load");
+        assert_eq!(
+            cfg.accepted_hash_algorithms,
+            vec![HashAlgorithm::SHA256, HashAlgorithm::SHA512]
+        );
+
+        env::set_var("ACCEPTED_HASH_ALGORITHMS", "sha256,md5");
+        let err = AppConfig::from_env().expect_err("unknown algorithm must fail");
+        clear_env();
+        assert!(err.to_string().contains("got 'md5'"));
+    }
 }
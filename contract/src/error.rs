@@ -0,0 +1,26 @@
+use axum::response::{IntoResponse, Response};
+use thiserror::Error;
+
+/// Errors raised while recording or reading audit events.
+#[derive(Debug, Error)]
+pub enum AuditError {
+    #[error("event serialization failed: {0}")]
+    SerializationError(String),
+    #[error("event storage failed: {0}")]
+    StorageError(String),
+    /// Another writer appended since the caller last read the aggregate.
+    #[error("expected sequence {expected} for {aggregate_id}, but it is at {actual}")]
+    ConcurrencyConflict {
+        aggregate_id: String,
+        expected: u64,
+        actual: u64,
+    },
+}
+
+impl IntoResponse for AuditError {
+    fn into_response(self) -> Response {
+        crate::api_error::ApiError::from(self).into_response()
+    }
+}
+
+pub type Result<T> = std::result::Result<T, AuditError>;
@@ -1,5 +1,8 @@
+use crate::cache::CacheBackend;
+use crate::error::AuditError;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Core event type for audit trail
@@ -62,6 +65,201 @@ impl Event {
     }
 }
 
+/// Append-only audit log per aggregate. Each event is stored under
+/// `events:{aggregate_id}:{sequence}`; sequences come from an atomic
+/// counter at `eventseq:{aggregate_id}` so they stay dense across instances.
+///
+/// Events are also copied into a short-lived per-type feed
+/// (`eventfeed:{event_type}:{position}`) so the latest events of a type can
+/// be read without knowing their aggregates.
+///
+/// Events that don't change a document's state (verifications) are appended
+/// with [`EventStore::append_transient`]: they expire much sooner and skip
+/// the feed.
+pub struct EventStore {
+    cache: Arc<CacheBackend>,
+}
+
+impl EventStore {
+    /// Events are kept as long as transfer history (10 years).
+    const TTL_SECS: u64 = 60 * 60 * 24 * 365 * 10;
+    /// Feed entries only back "recent" views, so a week is plenty.
+    const FEED_TTL_SECS: u64 = 60 * 60 * 24 * 7;
+    /// Read-only events are only useful for recent activity.
+    const TRANSIENT_TTL_SECS: u64 = 60 * 60 * 24 * 30;
+
+    pub fn new(cache: Arc<CacheBackend>) -> Self {
+        Self { cache }
+    }
+
+    fn event_prefix(aggregate_id: &str) -> String {
+        format!("events:{}:", aggregate_id)
+    }
+
+    fn event_key(aggregate_id: &str, sequence: u64) -> String {
+        format!("{}{}", Self::event_prefix(aggregate_id), sequence)
+    }
+
+    fn sequence_key(aggregate_id: &str) -> String {
+        format!("eventseq:{}", aggregate_id)
+    }
+
+    fn feed_key(event_type: &str, position: u64) -> String {
+        format!("eventfeed:{}:{}", event_type, position)
+    }
+
+    fn feed_position_key(event_type: &str) -> String {
+        format!("eventfeedseq:{}", event_type)
+    }
+
+    /// Append `event` if the aggregate is still at `expected_sequence` (the
+    /// last sequence the caller observed, 0 for none). Otherwise fails with
+    /// [`AuditError::ConcurrencyConflict`].
+    pub async fn append(
+        &self,
+        event: Event,
+        expected_sequence: u64,
+    ) -> crate::error::Result<Event> {
+        let event = self
+            .store(event, Some(expected_sequence), Self::TTL_SECS)
+            .await?;
+        self.publish(&event).await?;
+        Ok(event)
+    }
+
+    /// Append `event` at the next sequence regardless of concurrent writers.
+    pub async fn append_unchecked(&self, event: Event) -> crate::error::Result<Event> {
+        let event = self.store(event, None, Self::TTL_SECS).await?;
+        self.publish(&event).await?;
+        Ok(event)
+    }
+
+    /// Append a read-only `event` at the next sequence. It is kept for
+    /// [`Self::TRANSIENT_TTL_SECS`] and not copied into the feed.
+    pub async fn append_transient(&self, event: Event) -> crate::error::Result<Event> {
+        self.store(event, None, Self::TRANSIENT_TTL_SECS).await
+    }
+
+    /// Takes the next sequence and writes the event in one atomic step, so
+    /// a failed write can't leave a gap. Unchecked appends only learn their
+    /// sequence afterwards; readers take it from the key, not the payload.
+    async fn store(
+        &self,
+        mut event: Event,
+        expected_sequence: Option<u64>,
+        ttl: u64,
+    ) -> crate::error::Result<Event> {
+        event.sequence = expected_sequence.map_or(0, |expected| expected + 1);
+        let json = event.to_json()?;
+        // The counter outlives every event it numbers, so it always gets the
+        // longest TTL.
+        let (applied, sequence) = self
+            .cache
+            .append_counted(
+                &Self::sequence_key(&event.aggregate_id),
+                Self::TTL_SECS,
+                &Self::event_prefix(&event.aggregate_id),
+                &json,
+                ttl,
+                expected_sequence.map(|expected| expected as i64),
+            )
+            .await
+            .map_err(|e| AuditError::StorageError(e.to_string()))?;
+        if !applied {
+            return Err(AuditError::ConcurrencyConflict {
+                aggregate_id: event.aggregate_id,
+                expected: expected_sequence.unwrap_or_default(),
+                actual: sequence as u64,
+            });
+        }
+        event.sequence = sequence as u64;
+        Ok(event)
+    }
+
+    async fn publish(&self, event: &Event) -> crate::error::Result<()> {
+        let json = event.to_json()?;
+        let position = self
+            .cache
+            .incr(&Self::feed_position_key(&event.event_type))
+            .await
+            .map_err(|e| AuditError::StorageError(e.to_string()))?;
+        self.cache
+            .set_raw(
+                &Self::feed_key(&event.event_type, position as u64),
+                &json,
+                Self::FEED_TTL_SECS,
+            )
+            .await
+            .map_err(|e| AuditError::StorageError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Up to `limit` most recent events of `event_type`, newest first.
+    pub async fn recent(&self, event_type: &str, limit: usize) -> crate::error::Result<Vec<Event>> {
+        let last = self
+            .cache
+            .counter(&Self::feed_position_key(event_type))
+            .await
+            .map_err(|e| AuditError::StorageError(e.to_string()))? as u64;
+        let mut events = Vec::with_capacity(limit);
+        for position in (1..=last).rev().take(limit) {
+            let raw = self
+                .cache
+                .get_raw(&Self::feed_key(event_type, position))
+                .await
+                .map_err(|e| AuditError::StorageError(e.to_string()))?;
+            match raw {
+                Some(raw) => events.push(Event::from_json(&raw)?),
+                // Older entries have expired.
+                None => break,
+            }
+        }
+        Ok(events)
+    }
+
+    /// Last sequence assigned for `aggregate_id` (0 when it has no events).
+    pub async fn last_sequence(&self, aggregate_id: &str) -> crate::error::Result<u64> {
+        self.cache
+            .counter(&Self::sequence_key(aggregate_id))
+            .await
+            .map(|v| v as u64)
+            .map_err(|e| AuditError::StorageError(e.to_string()))
+    }
+
+    /// All events recorded for `aggregate_id`, in sequence order.
+    pub async fn list(&self, aggregate_id: &str) -> crate::error::Result<Vec<Event>> {
+        self.list_after(aggregate_id, 0, usize::MAX).await
+    }
+
+    /// Up to `limit` events for `aggregate_id` with a sequence above
+    /// `after_sequence`, in sequence order.
+    pub async fn list_after(
+        &self,
+        aggregate_id: &str,
+        after_sequence: u64,
+        limit: usize,
+    ) -> crate::error::Result<Vec<Event>> {
+        let last = self.last_sequence(aggregate_id).await?;
+        let mut events = Vec::new();
+        for sequence in after_sequence.saturating_add(1)..=last {
+            if events.len() >= limit {
+                break;
+            }
+            let raw = self
+                .cache
+                .get_raw(&Self::event_key(aggregate_id, sequence))
+                .await
+                .map_err(|e| AuditError::StorageError(e.to_string()))?;
+            if let Some(raw) = raw {
+                let mut event = Event::from_json(&raw)?;
+                event.sequence = sequence;
+                events.push(event);
+            }
+        }
+        Ok(events)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,4 +293,132 @@ mod tests {
         assert_eq!(event.id, deserialized.id);
         assert_eq!(event.aggregate_id, deserialized.aggregate_id);
     }
+
+    fn event(aggregate_id: &str) -> Event {
+        Event::new(
+            aggregate_id.to_string(),
+            "Updated".to_string(),
+            serde_json::json!({}),
+            "user-1".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn append_rejects_stale_expected_sequence() {
+        let store = EventStore::new(Arc::new(CacheBackend::InMemory(
+            crate::cache::InMemoryCache::new(),
+        )));
+
+        assert_eq!(store.append(event("doc-1"), 0).await.unwrap().sequence, 1);
+        match store.append(event("doc-1"), 0).await {
+            Err(AuditError::ConcurrencyConflict {
+                expected, actual, ..
+            }) => assert_eq!((expected, actual), (0, 1)),
+            other => panic!("expected ConcurrencyConflict, got {:?}", other),
+        }
+        assert_eq!(store.append(event("doc-1"), 1).await.unwrap().sequence, 2);
+        assert_eq!(store.list("doc-1").await.unwrap().len(), 2);
+
+        let after: Vec<u64> = store
+            .list_after("doc-1", 1, 10)
+            .await
+            .unwrap()
+            .iter()
+            .map(|e| e.sequence)
+            .collect();
+        assert_eq!(after, vec![2]);
+        assert_eq!(
+            store.list_after("doc-1", 0, 1).await.unwrap()[0].sequence,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn recent_returns_newest_events_of_a_type() {
+        let store = EventStore::new(Arc::new(CacheBackend::InMemory(
+            crate::cache::InMemoryCache::new(),
+        )));
+        for id in ["doc-1", "doc-2", "doc-3"] {
+            store.append_unchecked(event(id)).await.unwrap();
+        }
+        store
+            .append_unchecked(Event::new(
+                "doc-1".to_string(),
+                "Revoked".to_string(),
+                serde_json::json!({}),
+                "user-1".to_string(),
+            ))
+            .await
+            .unwrap();
+
+        let recent: Vec<String> = store
+            .recent("Updated", 2)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.aggregate_id)
+            .collect();
+        assert_eq!(recent, vec!["doc-3", "doc-2"]);
+        assert_eq!(store.recent("Revoked", 10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn transient_events_expire_sooner_and_skip_the_feed() {
+        let cache = Arc::new(CacheBackend::InMemory(crate::cache::InMemoryCache::new()));
+        let store = EventStore::new(cache.clone());
+        store.append_unchecked(event("doc-1")).await.unwrap();
+        let verified = store
+            .append_transient(Event::new(
+                "doc-1".to_string(),
+                "Verified".to_string(),
+                serde_json::json!({}),
+                "user-1".to_string(),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(verified.sequence, 2);
+        assert!(store.recent("Verified", 10).await.unwrap().is_empty());
+        let ttl = |sequence| {
+            let cache = cache.clone();
+            async move {
+                cache
+                    .ttl(&EventStore::event_key("doc-1", sequence))
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .as_secs()
+            }
+        };
+        assert!(ttl(2).await <= EventStore::TRANSIENT_TTL_SECS);
+        assert!(ttl(1).await > EventStore::TRANSIENT_TTL_SECS);
+    }
+
+    #[tokio::test]
+    async fn concurrent_instances_produce_dense_sequences() {
+        let cache = Arc::new(CacheBackend::InMemory(crate::cache::InMemoryCache::new()));
+        let instances = [
+            Arc::new(EventStore::new(cache.clone())),
+            Arc::new(EventStore::new(cache.clone())),
+        ];
+
+        let tasks: Vec<_> = (0..40)
+            .map(|i| {
+                let store = instances[i % 2].clone();
+                tokio::spawn(async move { store.append_unchecked(event("doc-1")).await })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        let sequences: Vec<u64> = instances[1]
+            .list("doc-1")
+            .await
+            .unwrap()
+            .iter()
+            .map(|e| e.sequence)
+            .collect();
+        assert_eq!(sequences, (1..=40).collect::<Vec<_>>());
+    }
 }
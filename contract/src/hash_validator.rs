@@ -1,16 +1,76 @@
+use serde::Deserialize;
+
 #[derive(Debug)]
 pub enum ValidationError {
-    WrongLength { expected: usize, actual: usize },
-    InvalidCharacter { position: usize, character: char },
+    WrongLength {
+        expected: usize,
+        actual: usize,
+    },
+    InvalidCharacter {
+        position: usize,
+        character: char,
+    },
     EmptyHash,
+    /// The hash is well-formed but its algorithm is not allow-listed.
+    AlgorithmNotAccepted {
+        algorithm: HashAlgorithm,
+    },
+    /// The hash is well-formed but the operation only supports another
+    /// algorithm.
+    AlgorithmUnsupported {
+        algorithm: HashAlgorithm,
+    },
+}
+
+impl ValidationError {
+    /// Stable label for metrics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::WrongLength { .. } => "wrong_length",
+            Self::InvalidCharacter { .. } => "invalid_character",
+            Self::EmptyHash => "empty",
+            Self::AlgorithmNotAccepted { .. } => "algorithm_not_accepted",
+            Self::AlgorithmUnsupported { .. } => "algorithm_unsupported",
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum HashAlgorithm {
+    SHA1,
     SHA256,
     SHA512,
 }
 
+impl HashAlgorithm {
+    pub const ALL: [HashAlgorithm; 3] = [Self::SHA1, Self::SHA256, Self::SHA512];
+
+    /// Lowercase name, as accepted in requests and configuration.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::SHA1 => "sha1",
+            Self::SHA256 => "sha256",
+            Self::SHA512 => "sha512",
+        }
+    }
+
+    /// Parse a name such as `sha256` or `SHA-256`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase().replace('-', "");
+        Self::ALL.into_iter().find(|a| a.name() == name)
+    }
+
+    /// Length of the hex-encoded digest.
+    pub fn hex_len(self) -> usize {
+        match self {
+            Self::SHA1 => 40,
+            Self::SHA256 => 64,
+            Self::SHA512 => 128,
+        }
+    }
+}
+
 pub struct HashValidator;
 
 impl HashValidator {
@@ -18,6 +78,10 @@ impl HashValidator {
         hash.trim().to_lowercase()
     }
 
+    pub fn validate_sha1(hash: &str) -> Result<(), ValidationError> {
+        Self::validate_with_length(hash, 40)
+    }
+
     pub fn validate_sha256(hash: &str) -> Result<(), ValidationError> {
         Self::validate_with_length(hash, 64)
     }
@@ -26,6 +90,23 @@ impl HashValidator {
         Self::validate_with_length(hash, 128)
     }
 
+    /// Validate a hash against the digest length of the given algorithm.
+    pub fn validate(hash: &str, algorithm: HashAlgorithm) -> Result<(), ValidationError> {
+        Self::validate_with_length(hash, algorithm.hex_len())
+    }
+
+    /// Validate a hash for an operation that only handles `algorithm`. A
+    /// well-formed digest of another algorithm is reported as
+    /// [`ValidationError::AlgorithmUnsupported`] rather than a wrong length.
+    pub fn validate_only(hash: &str, algorithm: HashAlgorithm) -> Result<(), ValidationError> {
+        match Self::detect_algorithm(hash) {
+            Some(detected) if detected != algorithm => Err(ValidationError::AlgorithmUnsupported {
+                algorithm: detected,
+            }),
+            _ => Self::validate(hash, algorithm),
+        }
+    }
+
     fn validate_with_length(hash: &str, expected_len: usize) -> Result<(), ValidationError> {
         let normalized = Self::normalize(hash);
 
@@ -54,9 +135,26 @@ impl HashValidator {
         Ok(())
     }
 
+    /// Reject a hash whose algorithm (explicit, or detected from its length)
+    /// is not in `accepted`. Hashes of undetectable length pass through so
+    /// the regular validation reports them.
+    pub fn ensure_accepted(
+        hash: &str,
+        algorithm: Option<HashAlgorithm>,
+        accepted: &[HashAlgorithm],
+    ) -> Result<(), ValidationError> {
+        match algorithm.or_else(|| Self::detect_algorithm(hash)) {
+            Some(algorithm) if !accepted.contains(&algorithm) => {
+                Err(ValidationError::AlgorithmNotAccepted { algorithm })
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub fn detect_algorithm(hash: &str) -> Option<HashAlgorithm> {
         let normalized = Self::normalize(hash);
         match normalized.len() {
+            40 => Some(HashAlgorithm::SHA1),
             64 => Some(HashAlgorithm::SHA256),
             128 => Some(HashAlgorithm::SHA512),
             _ => None,
@@ -150,9 +248,59 @@ mod tests {
         assert_eq!(algo, Some(HashAlgorithm::SHA512));
     }
 
+    #[test]
+    fn detect_algorithm_identifies_sha1() {
+        let algo = HashValidator::detect_algorithm("da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_eq!(algo, Some(HashAlgorithm::SHA1));
+    }
+
+    #[test]
+    fn validate_with_explicit_algorithm_rejects_other_lengths() {
+        assert!(HashValidator::validate(sample_sha512(), HashAlgorithm::SHA512).is_ok());
+        match HashValidator::validate(sample_sha256(), HashAlgorithm::SHA512) {
+            Err(ValidationError::WrongLength { expected, actual }) => {
+                assert_eq!(expected, 128);
+                assert_eq!(actual, 64);
+            }
+            other => panic!("expected WrongLength error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn validate_only_reports_other_algorithms_as_unsupported() {
+        assert!(HashValidator::validate_only(sample_sha256(), HashAlgorithm::SHA256).is_ok());
+        match HashValidator::validate_only(sample_sha512(), HashAlgorithm::SHA256) {
+            Err(ValidationError::AlgorithmUnsupported { algorithm }) => {
+                assert_eq!(algorithm, HashAlgorithm::SHA512)
+            }
+            other => panic!("expected AlgorithmUnsupported, got {:?}", other),
+        }
+        assert!(matches!(
+            HashValidator::validate_only("abc123", HashAlgorithm::SHA256),
+            Err(ValidationError::WrongLength { .. })
+        ));
+    }
+
     #[test]
     fn detect_algorithm_returns_none_for_other_lengths() {
         let algo = HashValidator::detect_algorithm("abc123");
         assert_eq!(algo, None);
     }
+
+    #[test]
+    fn ensure_accepted_rejects_detected_algorithm_outside_allow_list() {
+        let sha256_only = [HashAlgorithm::SHA256];
+        assert!(HashValidator::ensure_accepted(sample_sha256(), None, &sha256_only).is_ok());
+        match HashValidator::ensure_accepted(sample_sha512(), None, &sha256_only) {
+            Err(ValidationError::AlgorithmNotAccepted { algorithm }) => {
+                assert_eq!(algorithm, HashAlgorithm::SHA512)
+            }
+            other => panic!("expected AlgorithmNotAccepted, got {:?}", other),
+        }
+        assert!(HashValidator::ensure_accepted("abc123", None, &sha256_only).is_ok());
+        assert_eq!(
+            HashAlgorithm::from_name("SHA-512"),
+            Some(HashAlgorithm::SHA512)
+        );
+    }
 }
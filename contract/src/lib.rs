@@ -1,30 +1,60 @@
+pub mod api_error;
 pub mod cache;
+pub mod canonical;
 pub mod config;
+pub mod error;
+pub mod event;
 pub mod hash_validator;
 pub mod metrics;
+pub mod proof;
+pub mod public;
 pub mod rate_limit;
+pub mod secrets;
+pub mod self_check;
+pub mod server;
+pub mod singleflight;
 pub mod stellar;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+pub mod webhook;
+pub mod write_behind;
 
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{rejection::QueryRejection, Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::{self, Next},
     response::{IntoResponse, Response},
-    routing::{get, post},
+    routing::{delete, get, post},
     Json, Router,
 };
 use chrono::{NaiveDate, Utc};
-use futures::future::join_all;
+use futures::{future::join_all, StreamExt};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use tower_http::timeout::TimeoutLayer;
 use tower_http::trace::TraceLayer;
 use tracing::{info, warn};
 
+use api_error::ApiError;
 use cache::CacheBackend;
-use hash_validator::{HashValidator, ValidationError as HashValidationError};
+use config::TimeConfig;
+use error::AuditError;
+use event::{Event, EventStore};
+use hash_validator::{HashAlgorithm, HashValidator, ValidationError as HashValidationError};
 use metrics::MetricsRegistry;
-use stellar::{derive_account_id, StellarClient, TransactionRecord};
+use proof::VerificationProof;
+use public::{PublicRecentFeed, RecentAnchor, RecentAnchorsResponse, ANCHORED_EVENT};
+use singleflight::SingleFlight;
+use stellar::{
+    derive_account_id, RevocationStatus, StellarClient, StellarError, TransactionEvidence,
+    TransactionRecord, TransactionVerification,
+};
+use webhook::WebhookDispatcher;
+use write_behind::WriteBehindQueue;
 
 // Application state
 #[derive(Clone)]
@@ -33,6 +63,35 @@ pub struct AppState {
     pub cache: Arc<CacheBackend>,
     pub metrics: Arc<MetricsRegistry>,
     pub stellar_secret_key: String,
+    pub webhook_secret: Option<String>,
+    pub webhooks: Arc<WebhookDispatcher>,
+    /// Addresses the server is listening on, reported by `/health`.
+    pub listeners: Arc<Vec<String>>,
+    /// Failed verification cache writes awaiting background retry.
+    pub write_behind: Arc<WriteBehindQueue>,
+    /// Audit trail of document lifecycle events.
+    pub events: Arc<EventStore>,
+    /// Hash algorithms this deployment accepts (`ACCEPTED_HASH_ALGORITHMS`).
+    pub accepted_algorithms: Arc<Vec<HashAlgorithm>>,
+    /// Per-request deadline (`REQUEST_TIMEOUT_SECS`).
+    pub request_timeout: Duration,
+    /// Set once shutdown begins; `/health` then reports `draining`.
+    pub draining: Arc<AtomicBool>,
+    /// `None` when `PUBLIC_RECENT_ENABLED=false`; `/public/recent` then 404s.
+    pub public_recent: Option<Arc<PublicRecentFeed>>,
+    /// Concurrent Horizon queries per batch verification (`BATCH_CONCURRENCY`).
+    pub batch_concurrency: usize,
+    /// Coalesces concurrent Stellar lookups of the same uncached hash.
+    pub verify_flights: Arc<SingleFlight<Result<VerifyResponse, ApiError>>>,
+    /// Seconds a verified result stays cached (`CACHE_VERIFICATION_TTL`).
+    pub cache_ttl: u64,
+    /// Seconds an unverified result stays cached (`CACHE_NEGATIVE_TTL`).
+    pub cache_negative_ttl: u64,
+    /// Bearer token for `DELETE /cache` (`ADMIN_TOKEN`); `None` disables it.
+    pub admin_token: Option<String>,
+    /// Clock skew allowed on timestamps from other services
+    /// (`CLOCK_SKEW_TOLERANCE_SECS`).
+    pub time: TimeConfig,
 }
 
 // Request/Response types
@@ -40,18 +99,53 @@ pub struct AppState {
 pub struct VerifyRequest {
     pub document_hash: String,
     pub transaction_id: Option<String>,
+    /// Digest algorithm of `document_hash`; detected from its length when omitted.
+    #[serde(default)]
+    pub algorithm: Option<HashAlgorithm>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerifyResponse {
     pub verified: bool,
     pub transaction_id: Option<String>,
     pub timestamp: Option<i64>,
     pub cached: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub revoked: Option<bool>,
+    #[serde(default)]
+    pub revoked: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub revoked_at: Option<i64>,
+    /// Transaction that recorded the revocation, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revoked_transaction_id: Option<String>,
+    /// Unix timestamp of the last time this result was confirmed against
+    /// Stellar. Preserved on cache hits.
+    #[serde(default)]
+    pub last_checked_at: i64,
+    /// Horizon transaction snapshot; only returned with `?include=evidence`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<TransactionEvidence>,
+    /// Why a `transaction_id` hint did not verify the hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Query parameters shared by the verify endpoints.
+#[derive(Debug, Default, Deserialize)]
+pub struct VerifyQuery {
+    /// Maximum acceptable age (seconds) of a cached confirmation before a
+    /// fresh Stellar check is forced.
+    pub max_age_secs: Option<u64>,
+    /// Comma-separated optional sections to include (`evidence`).
+    pub include: Option<String>,
+}
+
+impl VerifyQuery {
+    fn includes(&self, section: &str) -> bool {
+        self.include
+            .as_deref()
+            .map(|list| list.split(',').any(|s| s.trim() == section))
+            .unwrap_or(false)
+    }
 }
 
 /// Request type for submitting a document hash to Stellar blockchain
@@ -90,6 +184,7 @@ pub struct HealthResponse {
     pub status: String,
     pub stellar_connected: bool,
     pub redis_connected: bool,
+    pub listeners: Vec<String>,
 }
 
 /// Response type for document verification history
@@ -101,11 +196,6 @@ pub struct HistoryResponse {
     pub cached: bool,
 }
 
-#[derive(Debug, Serialize)]
-pub struct ValidationErrorResponse {
-    pub error: String,
-}
-
 #[derive(Debug, Deserialize)]
 pub struct BatchVerifyRequest {
     pub hashes: Vec<String>,
@@ -119,7 +209,40 @@ pub struct BatchVerifyResponse {
     pub failed_count: usize,
 }
 
-#[derive(Debug, Serialize)]
+/// Counts-only answer of `POST /verify/batch/summary`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchVerifySummary {
+    pub total: usize,
+    pub verified_count: usize,
+    /// Hashes not verified, including those in `error_count`.
+    pub failed_count: usize,
+    /// Hashes that were invalid or could not be checked.
+    pub error_count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TargetedVerifyRequest {
+    pub hash: String,
+    pub transaction_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TargetedVerifyItem {
+    pub transaction_id: String,
+    pub matched: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TargetedVerifyResponse {
+    pub hash: String,
+    pub verified: bool,
+    /// Ids whose memo carries the hash, in request order.
+    pub matching_transaction_ids: Vec<String>,
+    pub results: Vec<TargetedVerifyItem>,
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct BatchVerifyItem {
     pub hash: String,
     pub verified: bool,
@@ -128,6 +251,42 @@ pub struct BatchVerifyItem {
     pub error: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct BatchSubmitRequest {
+    pub hashes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchSubmitItem {
+    pub hash: String,
+    pub transaction_id: Option<String>,
+    pub anchored_at: Option<i64>,
+    /// Already anchored; nothing was submitted.
+    pub skipped: bool,
+    pub error: Option<String>,
+}
+
+impl BatchSubmitItem {
+    fn failed(hash: String, error: String) -> Self {
+        Self {
+            hash,
+            transaction_id: None,
+            anchored_at: None,
+            skipped: false,
+            error: Some(error),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchSubmitResponse {
+    pub results: Vec<BatchSubmitItem>,
+    pub total: usize,
+    pub submitted_count: usize,
+    pub skipped_count: usize,
+    pub failed_count: usize,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct TransferRequest {
     pub document_hash: String,
@@ -135,6 +294,11 @@ pub struct TransferRequest {
     pub to_owner: String,
     pub transfer_date: String,
     pub transfer_reference: String,
+    /// Last event sequence the caller saw for the document (see
+    /// `GET /documents/:hash/events`). When set, the transfer is refused with
+    /// `409` if other events were recorded since.
+    #[serde(default)]
+    pub expected_sequence: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -147,16 +311,144 @@ pub struct TransferRecord {
     pub transfer_hash: String,
     pub memo: String,
     pub anchored_at: String,
+    /// Transfer hash scheme used for `transfer_hash`; records written before
+    /// versioning used the legacy scheme (1).
+    #[serde(default = "legacy_transfer_hash_version")]
+    pub hash_version: u8,
+}
+
+/// Current transfer hash scheme (canonical JSON preimage).
+pub const TRANSFER_HASH_VERSION: u8 = 2;
+
+fn legacy_transfer_hash_version() -> u8 {
+    1
+}
+
+/// Versioned preimage hashed by [`compute_transfer_hash`].
+#[derive(Serialize)]
+struct TransferHashPreimage<'a> {
+    v: u8,
+    document_hash: &'a str,
+    from_owner: &'a str,
+    to_owner: &'a str,
+    transfer_date: &'a str,
 }
 
 #[derive(Debug, Serialize)]
 pub struct TransferResponse {
     pub transfer_hash: String,
     pub memo: String,
+    /// Stellar transaction that anchored the transfer.
+    pub transaction_id: Option<String>,
+}
+
+/// Cache a fresh verification result, handing it to the write-behind queue
+/// if the cache is unavailable. Unverified results use the shorter negative
+/// TTL so a hash anchored shortly afterwards is picked up sooner.
+async fn cache_verification_result(state: &AppState, hash: &str, response: &VerifyResponse) {
+    let ttl = if response.verified {
+        state.cache_ttl
+    } else {
+        state.cache_negative_ttl
+    };
+    let serialized = match serde_json::to_string(response) {
+        Ok(s) => s,
+        Err(e) => {
+            warn!("Failed to serialize result for hash {}: {}", hash, e);
+            return;
+        }
+    };
+    if let Err(e) = state.cache.set_raw(hash, &serialized, ttl).await {
+        warn!("Failed to cache result for hash {}: {}; deferring", hash, e);
+        state.write_behind.push(hash, serialized, ttl);
+    }
+}
+
+/// Record the anchor age of a successful verification, when known.
+fn record_verification_age(metrics: &MetricsRegistry, verified: bool, timestamp: Option<i64>) {
+    if let (true, Some(anchored_at)) = (verified, timestamp) {
+        metrics.observe_document_age(Utc::now().timestamp() - anchored_at);
+    }
+}
+
+/// Returns `true` if a cached result is recent enough for the caller.
+fn is_fresh(cached: &VerifyResponse, max_age_secs: Option<u64>, now: i64) -> bool {
+    match max_age_secs {
+        Some(max_age) => now.saturating_sub(cached.last_checked_at) <= max_age as i64,
+        None => true,
+    }
+}
+
+fn map_validation_error(metrics: &MetricsRegistry, err: HashValidationError) -> ApiError {
+    metrics.increment_validation_failure(err.kind());
+    ApiError::Validation(validation_error_message(&err))
+}
+
+/// Derive the anchoring account from the configured signing key.
+fn anchor_account_id(state: &AppState) -> Result<String, ApiError> {
+    derive_account_id(&state.stellar_secret_key).map_err(|e| {
+        warn!("Failed to derive anchor account id: {}", e);
+        state.metrics.increment_error_count();
+        ApiError::Internal(format!("failed to derive anchor account id: {}", e))
+    })
+}
+
+/// Audit event types recorded by the handlers. Anchors use
+/// [`public::ANCHORED_EVENT`], which also feeds `GET /public/recent`.
+const VERIFIED_EVENT: &str = "Verified";
+const REVOKED_EVENT: &str = "Revoked";
+const TRANSFERRED_EVENT: &str = "Transferred";
+
+/// Who to record on audit events: the `X-Actor` header, or `anonymous`.
+fn request_actor(headers: &HeaderMap) -> String {
+    headers
+        .get("x-actor")
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|actor| !actor.is_empty())
+        .unwrap_or("anonymous")
+        .to_string()
+}
+
+/// Append an audit event. The action it describes has already happened,
+/// so a storage failure is logged rather than failing the request.
+/// Verifications don't change the document, so they are kept briefly.
+async fn record_event(state: &AppState, event: Event) {
+    let appended = if event.event_type == VERIFIED_EVENT {
+        state.events.append_transient(event.clone()).await
+    } else {
+        state.events.append_unchecked(event.clone()).await
+    };
+    if let Err(e) = appended {
+        warn!(
+            "Failed to record {} event for {}: {}",
+            event.event_type, event.aggregate_id, e
+        );
+    }
+}
+
+/// Append an audit event the caller expected to follow `expected_sequence`.
+/// The action is already on-chain by now, so an event that raced in since
+/// the caller's check doesn't drop this one: it is appended after it.
+async fn record_expected_event(state: &AppState, event: Event, expected_sequence: u64) {
+    match state.events.append(event.clone(), expected_sequence).await {
+        Err(AuditError::ConcurrencyConflict { actual, .. }) => {
+            warn!(
+                "{} event for {} raced another writer (expected sequence {}, found {})",
+                event.event_type, event.aggregate_id, expected_sequence, actual
+            );
+            record_event(state, event).await;
+        }
+        Err(e) => warn!(
+            "Failed to record {} event for {}: {}",
+            event.event_type, event.aggregate_id, e
+        ),
+        Ok(_) => {}
+    }
 }
 
-fn map_validation_error(err: HashValidationError) -> (StatusCode, ValidationErrorResponse) {
-    let message = match err {
+fn validation_error_message(err: &HashValidationError) -> String {
+    match *err {
         HashValidationError::EmptyHash => "hash must not be empty".to_string(),
         HashValidationError::WrongLength { expected, actual } => format!(
             "hash has wrong length: expected {} characters, got {}",
@@ -169,12 +461,68 @@ fn map_validation_error(err: HashValidationError) -> (StatusCode, ValidationErro
             "hash contains invalid character '{}' at position {}",
             character, position
         ),
+        HashValidationError::AlgorithmNotAccepted { algorithm } => format!(
+            "hash algorithm {} is not accepted by this deployment",
+            algorithm.name()
+        ),
+        HashValidationError::AlgorithmUnsupported { algorithm } => format!(
+            "hash algorithm {} is unsupported for this endpoint",
+            algorithm.name()
+        ),
+    }
+}
+
+/// Returns `true` when the client asked for XML via `Accept` (legacy
+/// integrations): XML must carry a higher `q` weight than JSON. JSON wins
+/// ties, and a type with `q=0` is refused.
+fn wants_xml(headers: &HeaderMap) -> bool {
+    let accept = match headers.get(header::ACCEPT).and_then(|v| v.to_str().ok()) {
+        Some(accept) => accept,
+        None => return false,
     };
 
-    (
-        StatusCode::BAD_REQUEST,
-        ValidationErrorResponse { error: message },
-    )
+    let mut xml = 0.0_f32;
+    let mut json = 0.0_f32;
+    for media in accept.split(',') {
+        let mut params = media.split(';');
+        let media_type = params.next().unwrap_or_default().trim();
+        let quality = params
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0)
+            .clamp(0.0, 1.0);
+        match media_type {
+            "application/xml" | "text/xml" => xml = xml.max(quality),
+            "application/json" => json = json.max(quality),
+            _ => {}
+        }
+    }
+
+    xml > json
+}
+
+/// Serialize a `VerifyResponse` as XML when requested, otherwise JSON.
+fn negotiate_verify_response(
+    headers: &HeaderMap,
+    response: VerifyResponse,
+) -> Result<Response, ApiError> {
+    if !wants_xml(headers) {
+        return Ok(Json(response).into_response());
+    }
+
+    match quick_xml::se::to_string(&response) {
+        Ok(body) => Ok((
+            [(header::CONTENT_TYPE, "application/xml")],
+            format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>{}", body),
+        )
+            .into_response()),
+        Err(e) => {
+            warn!("Failed to serialize verify response as XML: {}", e);
+            Err(ApiError::Internal(
+                "failed to serialize response as XML".to_string(),
+            ))
+        }
+    }
 }
 
 pub fn app(state: AppState) -> Router {
@@ -183,240 +531,1149 @@ pub fn app(state: AppState) -> Router {
         .route("/metrics", get(metrics_handler))
         .route("/verify", post(verify_document))
         .route("/verify/batch", post(batch_verify_documents))
+        .route("/verify/batch/summary", post(batch_verify_summary))
+        .route("/verify/stream", post(stream_verify_documents))
+        .route("/verify/targeted", post(targeted_verify_document))
         .route("/verify/:hash", get(verify_document_by_hash))
         .route("/verify/:hash/history", get(verify_document_history))
+        .route("/verify/:hash/proof", get(verify_document_proof))
+        .route("/verify/proof", post(validate_document_proof))
         .route("/submit", post(submit_document))
+        .route("/submit/batch", post(batch_submit_documents))
         .route("/revoke", post(revoke_document))
         .route("/transfer", post(record_transfer))
+        .route("/transfer/:document_hash", get(get_transfer_history))
+        .route("/documents/:hash/status", get(document_status))
+        .route("/documents/:hash/events", get(document_events))
+        .route("/public/recent", get(public_recent_anchors))
+        .route("/similarity", post(similarity_handler))
+        .route("/duplicates", post(duplicates_handler))
+        .merge(admin_routes(state.clone()))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            enforce_client_deadline,
+        ))
+        .layer(TimeoutLayer::new(state.request_timeout))
+        .layer(middleware::map_response(timeout_error_body))
         .layer(TraceLayer::new_for_http())
         .with_state(state)
 }
 
-// Health check endpoint
-pub async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
-    let stellar_ok = state.stellar.check_connection().await;
-    let redis_ok = state.cache.check_connection().await;
-
-    let status = if stellar_ok && redis_ok {
-        "healthy"
-    } else {
-        "degraded"
-    };
-
-    Json(HealthResponse {
-        status: status.to_string(),
-        stellar_connected: stellar_ok,
-        redis_connected: redis_ok,
-    })
+/// Cache administration routes, all behind the admin bearer token.
+fn admin_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/admin/cache/:key", get(admin_cache_inspect))
+        .route("/admin/cache/:key/expire", post(admin_cache_expire))
+        .route("/cache", delete(purge_cache_prefix))
+        .route("/cache/:hash", delete(invalidate_cached_verification))
+        .route_layer(middleware::from_fn_with_state(state, require_admin))
 }
 
-// Metrics endpoint
-pub async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
-    state.metrics.render()
+/// `TimeoutLayer` answers with an empty `408`; give it the usual JSON body.
+async fn timeout_error_body(response: Response) -> Response {
+    if response.status() == StatusCode::REQUEST_TIMEOUT
+        && !response.headers().contains_key(header::CONTENT_TYPE)
+    {
+        return ApiError::Timeout("request timed out".to_string()).into_response();
+    }
+    response
 }
 
-/// Compute deterministic transfer hash from core fields.
-///
-/// SHA-256(document_hash + from_owner + to_owner + transfer_date)
-pub fn compute_transfer_hash(req: &TransferRequest) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(req.document_hash.as_bytes());
-    hasher.update(req.from_owner.as_bytes());
-    hasher.update(req.to_owner.as_bytes());
-    hasher.update(req.transfer_date.as_bytes());
-    let digest = hasher.finalize();
-    hex::encode(digest)
-}
+/// Header carrying the caller's own deadline for a request, in milliseconds.
+pub const REQUEST_TIMEOUT_HEADER: &str = "x-request-timeout-ms";
 
-/// Validate that the provided date is a valid ISO 8601 calendar date (YYYY-MM-DD).
-fn is_valid_iso8601_date(date: &str) -> bool {
-    NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok()
+/// Parse `X-Request-Timeout-Ms`, if present.
+fn client_deadline(headers: &HeaderMap) -> Result<Option<Duration>, ApiError> {
+    let Some(value) = headers.get(REQUEST_TIMEOUT_HEADER) else {
+        return Ok(None);
+    };
+    match value
+        .to_str()
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+    {
+        Some(ms) if ms > 0 => Ok(Some(Duration::from_millis(ms))),
+        _ => Err(ApiError::Validation(format!(
+            "{} must be a positive number of milliseconds",
+            REQUEST_TIMEOUT_HEADER
+        ))),
+    }
 }
 
-/// Build a Stellar memo string for a transfer hash, respecting the 28-byte
-/// text memo limit and using the required TRANSFER: prefix.
-fn build_transfer_memo(transfer_hash: &str) -> String {
-    const PREFIX: &str = "TRANSFER:";
-    const MAX_MEMO_LEN: usize = 28;
-
-    let remaining = MAX_MEMO_LEN.saturating_sub(PREFIX.len());
-    let truncated = if transfer_hash.len() > remaining {
-        &transfer_hash[..remaining]
-    } else {
-        transfer_hash
+/// Bound the request by the caller's `X-Request-Timeout-Ms`, answering 504
+/// once it passes. Dropping the handler cancels its in-flight Horizon calls.
+/// Deadlines longer than `REQUEST_TIMEOUT_SECS` are left to that limit.
+async fn enforce_client_deadline(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let deadline = match client_deadline(request.headers()) {
+        Ok(Some(deadline)) if deadline < state.request_timeout => deadline,
+        Ok(_) => return next.run(request).await,
+        Err(e) => return e.into_response(),
     };
+    match tokio::time::timeout(deadline, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => ApiError::DeadlineExceeded(format!(
+            "request exceeded its {} ms deadline",
+            deadline.as_millis()
+        ))
+        .into_response(),
+    }
+}
 
-    format!("{}{}", PREFIX, truncated)
+const DEFAULT_PUBLIC_RECENT_LIMIT: usize = 10;
+const MAX_PUBLIC_RECENT_LIMIT: usize = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct PublicRecentQuery {
+    pub limit: Option<usize>,
 }
 
-/// POST /transfer — anchor an ownership transfer on Stellar and persist history in Redis.
-pub async fn record_transfer(
+/// GET /public/recent — latest anchors for the public portal, with hashes
+/// truncated to their first 8 and last 4 characters.
+pub async fn public_recent_anchors(
     State(state): State<AppState>,
-    Json(req): Json<TransferRequest>,
-) -> Result<Json<TransferResponse>, StatusCode> {
-    if !is_valid_iso8601_date(&req.transfer_date) {
-        return Err(StatusCode::BAD_REQUEST);
+    query: Result<Query<PublicRecentQuery>, QueryRejection>,
+) -> Result<Response, ApiError> {
+    let feed = state
+        .public_recent
+        .as_ref()
+        .ok_or_else(|| ApiError::NotFound("not found".to_string()))?;
+    if !feed.try_acquire() {
+        return Err(ApiError::RateLimited("too many requests".to_string()));
     }
 
-    let transfer_hash = compute_transfer_hash(&req);
-    let memo = build_transfer_memo(&transfer_hash);
+    let Query(query) = query.map_err(|e| ApiError::Validation(e.body_text()))?;
+    let limit = query.limit.unwrap_or(DEFAULT_PUBLIC_RECENT_LIMIT);
+    if limit == 0 || limit > MAX_PUBLIC_RECENT_LIMIT {
+        return Err(ApiError::Validation(format!(
+            "limit must be between 1 and {}",
+            MAX_PUBLIC_RECENT_LIMIT
+        )));
+    }
 
-    let anchor_account_id = derive_account_id(&state.stellar_secret_key).map_err(|e| {
-        warn!("Failed to derive anchor account id: {}", e);
-        state.metrics.increment_error_count();
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    let anchors = state
+        .events
+        .recent(ANCHORED_EVENT, limit)
+        .await?
+        .iter()
+        .map(RecentAnchor::from_event)
+        .collect();
 
-    if let Err(e) = state
-        .stellar
-        .anchor_transfer(
-            &transfer_hash,
-            &anchor_account_id,
-            &state.stellar_secret_key,
-        )
-        .await
-    {
-        warn!("Failed to anchor transfer on Stellar: {}", e);
-        state.metrics.increment_error_count();
-        return Err(StatusCode::INTERNAL_SERVER_ERROR);
-    }
+    Ok((
+        [(header::CACHE_CONTROL, "public, max-age=30")],
+        Json(RecentAnchorsResponse { anchors }),
+    )
+        .into_response())
+}
 
-    let record = TransferRecord {
-        document_hash: req.document_hash.clone(),
-        from_owner: req.from_owner.clone(),
-        to_owner: req.to_owner.clone(),
-        transfer_date: req.transfer_date.clone(),
-        transfer_reference: req.transfer_reference.clone(),
-        transfer_hash: transfer_hash.clone(),
-        memo: memo.clone(),
-        anchored_at: Utc::now().to_rfc3339(),
-    };
+/// Upper bounds for the similarity endpoints; Levenshtein is quadratic in
+/// document length and `/duplicates` is quadratic in document count.
+const MAX_SIMILARITY_DOCUMENTS: usize = 50;
+const MAX_SIMILARITY_DOCUMENT_LEN: usize = 4096;
 
-    let key = format!("transfer:{}", record.document_hash);
+#[derive(Debug, Deserialize)]
+pub struct SimilarityRequest {
+    pub reference: String,
+    pub candidates: Vec<String>,
+}
 
-    let mut history: Vec<TransferRecord> = match state.cache.get(&key).await {
-        Ok(Some(existing)) => existing,
-        Ok(None) => Vec::new(),
-        Err(e) => {
-            warn!("Failed to read transfer history from cache: {}", e);
-            state.metrics.increment_error_count();
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
-        }
-    };
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimilarityScore {
+    /// Position of the candidate in the request.
+    pub index: usize,
+    pub cosine: f64,
+    pub levenshtein: f64,
+    pub combined: f64,
+}
 
-    history.push(record);
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimilarityResponse {
+    pub results: Vec<SimilarityScore>,
+}
 
-    // Set a long but finite TTL (10 years) to keep an auditable history
-    const TEN_YEARS_SECONDS: u64 = 60 * 60 * 24 * 365 * 10;
-    if let Err(e) = state.cache.set(&key, &history, TEN_YEARS_SECONDS).await {
-        warn!("Failed to persist transfer history: {}", e);
-        state.metrics.increment_error_count();
-        return Err(StatusCode::INTERNAL_SERVER_ERROR);
-    }
+#[derive(Debug, Deserialize)]
+pub struct DuplicatesRequest {
+    pub documents: Vec<String>,
+    pub threshold: f64,
+}
 
-    Ok(Json(TransferResponse {
-        transfer_hash,
-        memo,
-    }))
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DuplicatePair {
+    pub first: usize,
+    pub second: usize,
+    pub similarity: f64,
 }
 
-/// GET /transfer/:document_hash — retrieve transfer history for a document.
-pub async fn get_transfer_history(
-    State(state): State<AppState>,
-    Path(document_hash): Path<String>,
-) -> Result<Json<Vec<TransferRecord>>, StatusCode> {
-    let key = format!("transfer:{}", document_hash);
-    match state.cache.get::<Vec<TransferRecord>>(&key).await {
-        Ok(Some(history)) => Ok(Json(history)),
-        Ok(None) => Ok(Json(Vec::new())),
-        Err(e) => {
-            warn!("Failed to fetch transfer history from cache: {}", e);
-            state.metrics.increment_error_count();
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DuplicatesResponse {
+    pub pairs: Vec<DuplicatePair>,
+}
+
+/// Errors name documents as `{label} {index}`, matching the `index` the
+/// response reports for them.
+fn validate_similarity_documents<'a>(
+    label: &str,
+    documents: impl IntoIterator<Item = &'a String>,
+) -> Result<(), String> {
+    for (idx, doc) in documents.into_iter().enumerate() {
+        if doc.len() > MAX_SIMILARITY_DOCUMENT_LEN {
+            return Err(format!(
+                "{} {} exceeds maximum length of {} bytes",
+                label, idx, MAX_SIMILARITY_DOCUMENT_LEN
+            ));
         }
     }
+    Ok(())
 }
 
-// Verify document by POST
-pub async fn verify_document(
-    State(state): State<AppState>,
-    Json(req): Json<VerifyRequest>,
-) -> Response {
-    let normalized_hash = HashValidator::normalize(&req.document_hash);
-    if let Err(err) = HashValidator::validate_sha256(&normalized_hash) {
-        let (status, body) = map_validation_error(err);
-        return (status, Json(body)).into_response();
+/// POST /similarity — score each candidate against a reference document.
+pub async fn similarity_handler(
+    Json(req): Json<SimilarityRequest>,
+) -> Result<Json<SimilarityResponse>, ApiError> {
+    if req.candidates.len() > MAX_SIMILARITY_DOCUMENTS {
+        return Err(ApiError::Validation(format!(
+            "candidates exceed maximum of {} documents",
+            MAX_SIMILARITY_DOCUMENTS
+        )));
     }
-
-    info!("Verifying document hash: {}", normalized_hash);
-    state.metrics.increment_request_count();
-
-    // Check cache first
-    if let Ok(Some(cached)) = state.cache.get::<VerifyResponse>(&normalized_hash).await {
-        info!("Cache hit for hash: {}", normalized_hash);
-        state.metrics.increment_cache_hits();
-        return Json(cached).into_response();
+    if req.reference.len() > MAX_SIMILARITY_DOCUMENT_LEN {
+        return Err(ApiError::Validation(format!(
+            "reference exceeds maximum length of {} bytes",
+            MAX_SIMILARITY_DOCUMENT_LEN
+        )));
     }
+    validate_similarity_documents("candidate", &req.candidates).map_err(ApiError::Validation)?;
+
+    let results = tokio::task::spawn_blocking(move || {
+        let candidates: Vec<&str> = req.candidates.iter().map(String::as_str).collect();
+        batch_compare(&req.reference, &candidates)
+            .into_iter()
+            .enumerate()
+            .map(|(index, r)| SimilarityScore {
+                index,
+                cosine: r.cosine,
+                levenshtein: r.levenshtein,
+                combined: r.combined,
+            })
+            .collect()
+    })
+    .await;
 
-    state.metrics.increment_cache_misses();
-
-    let anchor_account_id = match derive_account_id(&state.stellar_secret_key) {
-        Ok(id) => id,
+    match results {
+        Ok(results) => Ok(Json(SimilarityResponse { results })),
         Err(e) => {
-            warn!("Failed to derive anchor account id: {}", e);
+            warn!("Similarity comparison task failed: {}", e);
+            Err(ApiError::Internal(
+                "similarity comparison failed".to_string(),
+            ))
+        }
+    }
+}
+
+/// POST /duplicates — pairs of documents at or above `threshold`, most similar first.
+pub async fn duplicates_handler(
+    Json(req): Json<DuplicatesRequest>,
+) -> Result<Json<DuplicatesResponse>, ApiError> {
+    if !(0.0..=1.0).contains(&req.threshold) {
+        return Err(ApiError::Validation(
+            "threshold must be between 0 and 1".to_string(),
+        ));
+    }
+    if req.documents.len() > MAX_SIMILARITY_DOCUMENTS {
+        return Err(ApiError::Validation(format!(
+            "documents exceed maximum of {} documents",
+            MAX_SIMILARITY_DOCUMENTS
+        )));
+    }
+    validate_similarity_documents("document", &req.documents).map_err(ApiError::Validation)?;
+
+    let pairs = tokio::task::spawn_blocking(move || {
+        let documents: Vec<&str> = req.documents.iter().map(String::as_str).collect();
+        find_duplicates_parallel(&documents, req.threshold)
+            .into_iter()
+            .map(|(first, second, similarity)| DuplicatePair {
+                first,
+                second,
+                similarity,
+            })
+            .collect()
+    })
+    .await;
+
+    match pairs {
+        Ok(pairs) => Ok(Json(DuplicatesResponse { pairs })),
+        Err(e) => {
+            warn!("Duplicate detection task failed: {}", e);
+            Err(ApiError::Internal("duplicate detection failed".to_string()))
+        }
+    }
+}
+
+/// Cache key namespaces the admin endpoints may touch.
+const ADMIN_CACHE_PREFIXES: &[&str] = &["stellar:verify:", "transfer:", "history:", "status:"];
+
+/// Accepts a bare hash (verification cache) or a hash under one of
+/// [`ADMIN_CACHE_PREFIXES`]; anything else is refused.
+fn is_admin_cache_key(key: &str) -> bool {
+    let hash = ADMIN_CACHE_PREFIXES
+        .iter()
+        .find_map(|prefix| key.strip_prefix(prefix))
+        .unwrap_or(key);
+    match HashValidator::detect_algorithm(hash) {
+        Some(algorithm) => {
+            hash == HashValidator::normalize(hash)
+                && HashValidator::validate(hash, algorithm).is_ok()
+        }
+        None => false,
+    }
+}
+
+fn admin_cache_forbidden() -> ApiError {
+    ApiError::Forbidden("key is outside the inspectable cache namespaces".to_string())
+}
+
+const ADMIN_CACHE_PREVIEW_CHARS: usize = 256;
+
+/// Longest TTL `POST /admin/cache/:key/expire` will set; larger requests
+/// are clamped to it.
+const MAX_ADMIN_CACHE_TTL: u64 = 60 * 60 * 24 * 365;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CacheEntryInfo {
+    pub key: String,
+    pub ttl_secs: Option<u64>,
+    pub size_bytes: usize,
+    /// Leading characters of the stored value.
+    pub preview: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CacheExpireRequest {
+    pub ttl_secs: u64,
+}
+
+/// GET /admin/cache/:key — report a cached entry's TTL and size.
+pub async fn admin_cache_inspect(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+) -> Result<Json<CacheEntryInfo>, ApiError> {
+    if !is_admin_cache_key(&key) {
+        return Err(admin_cache_forbidden());
+    }
+
+    cache_entry_info(&state, key).await.map(Json)
+}
+
+async fn cache_entry_info(state: &AppState, key: String) -> Result<CacheEntryInfo, ApiError> {
+    let value = state
+        .cache
+        .get_raw(&key)
+        .await
+        .map_err(|e| {
+            warn!("Failed to read cache entry {}: {}", key, e);
+            ApiError::Cache(format!("failed to read cache entry: {}", e))
+        })?
+        .ok_or_else(|| ApiError::NotFound("cache entry not found".to_string()))?;
+
+    let ttl = state.cache.ttl(&key).await.map_err(|e| {
+        warn!("Failed to read TTL for {}: {}", key, e);
+        ApiError::Cache(format!("failed to read cache TTL: {}", e))
+    })?;
+
+    Ok(CacheEntryInfo {
+        ttl_secs: ttl.map(|d| d.as_secs()),
+        size_bytes: value.len(),
+        preview: value.chars().take(ADMIN_CACHE_PREVIEW_CHARS).collect(),
+        key,
+    })
+}
+
+/// POST /admin/cache/:key/expire — reset the TTL of an existing entry.
+pub async fn admin_cache_expire(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Json(req): Json<CacheExpireRequest>,
+) -> Result<Json<CacheEntryInfo>, ApiError> {
+    if !is_admin_cache_key(&key) {
+        return Err(admin_cache_forbidden());
+    }
+    if req.ttl_secs == 0 {
+        return Err(ApiError::Validation(
+            "ttl_secs must be greater than 0".to_string(),
+        ));
+    }
+
+    let ttl_secs = req.ttl_secs.min(MAX_ADMIN_CACHE_TTL);
+
+    match state.cache.expire(&key, ttl_secs).await {
+        Ok(true) => {}
+        Ok(false) => return Err(ApiError::NotFound("cache entry not found".to_string())),
+        Err(e) => {
+            warn!("Failed to update TTL for {}: {}", key, e);
+            return Err(ApiError::Cache(format!(
+                "failed to update cache TTL: {}",
+                e
+            )));
+        }
+    }
+    info!("Cache TTL for {} set to {}s", key, ttl_secs);
+
+    cache_entry_info(&state, key).await.map(Json)
+}
+
+/// Require `Authorization: Bearer <ADMIN_TOKEN>` before running an admin
+/// route.
+async fn require_admin(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let Some(expected) = state.admin_token.as_deref() else {
+        return Err(ApiError::NotConfigured(
+            "ADMIN_TOKEN is not configured".to_string(),
+        ));
+    };
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| ApiError::Unauthorized("missing bearer token".to_string()))?;
+    if !tokens_match(presented, expected) {
+        return Err(ApiError::Unauthorized("invalid admin token".to_string()));
+    }
+    Ok(next.run(request).await)
+}
+
+/// Compare tokens in constant time. Comparing digests also hides the
+/// expected token's length.
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    let presented = Sha256::digest(presented.as_bytes());
+    let expected = Sha256::digest(expected.as_bytes());
+    presented
+        .iter()
+        .zip(expected.iter())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+/// DELETE /cache/:hash — drop a cached verification (and the document
+/// status built from it) so the next request re-checks Stellar.
+pub async fn invalidate_cached_verification(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let normalized_hash = HashValidator::normalize(&hash);
+    let algorithm =
+        HashValidator::detect_algorithm(&normalized_hash).unwrap_or(HashAlgorithm::SHA256);
+    HashValidator::ensure_accepted(
+        &normalized_hash,
+        Some(algorithm),
+        &state.accepted_algorithms,
+    )
+    .and_then(|()| HashValidator::validate(&normalized_hash, algorithm))
+    .map_err(|err| map_validation_error(&state.metrics, err))?;
+
+    state.cache.delete(&normalized_hash).await.map_err(|e| {
+        warn!("Failed to invalidate cache for {}: {}", normalized_hash, e);
+        ApiError::Cache(format!("failed to delete cache entry: {}", e))
+    })?;
+    invalidate_document_status(&state, &normalized_hash).await;
+    info!("Cached verification for {} invalidated", normalized_hash);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Namespaces `DELETE /cache` may purge; `""` is the verification cache.
+const PURGEABLE_CACHE_NAMESPACES: &[&str] = &["status:", "history:", ""];
+
+#[derive(Debug, Deserialize)]
+pub struct CachePurgeQuery {
+    pub prefix: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CachePurgeResponse {
+    pub prefix: String,
+    pub deleted: usize,
+}
+
+/// Checks that `prefix` is a purgeable namespace followed by the start of a
+/// lowercase hex hash. Bare verification prefixes need at least one digit.
+fn validate_purge_prefix(prefix: &str) -> Result<(), ApiError> {
+    let valid = PURGEABLE_CACHE_NAMESPACES.iter().any(|namespace| {
+        prefix.strip_prefix(namespace).is_some_and(|rest| {
+            (!namespace.is_empty() || !rest.is_empty())
+                && rest.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+        })
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(ApiError::Validation(
+            "prefix must be a lowercase hex hash prefix, optionally under status: or history:"
+                .to_string(),
+        ))
+    }
+}
+
+/// Namespace of a cache key, as listed in [`PURGEABLE_CACHE_NAMESPACES`].
+fn cache_key_namespace(key: &str) -> &str {
+    key.find(':').map_or("", |end| &key[..=end])
+}
+
+/// DELETE /cache?prefix= — drop every cached status, history or
+/// verification entry whose key starts with `prefix`.
+pub async fn purge_cache_prefix(
+    State(state): State<AppState>,
+    query: Result<Query<CachePurgeQuery>, QueryRejection>,
+) -> Result<Json<CachePurgeResponse>, ApiError> {
+    let Query(query) = query.map_err(|e| ApiError::Validation(e.body_text()))?;
+    let prefix = query
+        .prefix
+        .ok_or_else(|| ApiError::Validation("prefix is required".to_string()))?;
+    validate_purge_prefix(&prefix)?;
+
+    // Only whole cache keys of the requested namespace are eligible, so
+    // events, counters and other namespaces are never caught by a prefix.
+    let namespace = cache_key_namespace(&prefix).to_string();
+    let matches = move |key: &str| cache_key_namespace(key) == namespace && is_admin_cache_key(key);
+    let deleted = state
+        .cache
+        .delete_matching(&prefix, &matches)
+        .await
+        .map_err(|e| {
+            warn!("Failed to purge cache prefix {}: {}", prefix, e);
+            ApiError::Cache(format!("failed to purge cache: {}", e))
+        })?;
+    info!("Purged {} cache entries under {:?}", deleted, prefix);
+
+    Ok(Json(CachePurgeResponse { prefix, deleted }))
+}
+
+/// Health check endpoint.
+///
+/// Answers `503` with status `draining` once shutdown has begun so load
+/// balancers stop routing new requests here.
+pub async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
+    let stellar_ok = state.stellar.check_connection().await;
+    let redis_ok = state.cache.check_connection().await;
+    let draining = state.draining.load(Ordering::SeqCst);
+
+    let status = if draining {
+        "draining"
+    } else if stellar_ok && redis_ok {
+        "healthy"
+    } else {
+        "degraded"
+    };
+    let code = if draining {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+
+    (
+        code,
+        Json(HealthResponse {
+            status: status.to_string(),
+            stellar_connected: stellar_ok,
+            redis_connected: redis_ok,
+            listeners: state.listeners.as_ref().clone(),
+        }),
+    )
+}
+
+// Metrics endpoint
+pub async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
+    if let Some(memory) = state.cache.memory() {
+        state
+            .metrics
+            .observe_in_memory_cache(memory.entries().await, memory.evictions());
+    }
+    state.metrics.render()
+}
+
+/// Compute deterministic transfer hash from core fields.
+///
+/// SHA-256 of the canonical JSON of the versioned preimage, so field
+/// boundaries are unambiguous.
+pub fn compute_transfer_hash(req: &TransferRequest) -> String {
+    canonical::hash_canonical(&TransferHashPreimage {
+        v: TRANSFER_HASH_VERSION,
+        document_hash: &req.document_hash,
+        from_owner: &req.from_owner,
+        to_owner: &req.to_owner,
+        transfer_date: &req.transfer_date,
+    })
+}
+
+/// Legacy (version 1) transfer hash, kept to verify records written before
+/// the canonical scheme.
+///
+/// SHA-256(document_hash + from_owner + to_owner + transfer_date)
+pub fn compute_legacy_transfer_hash(req: &TransferRequest) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(req.document_hash.as_bytes());
+    hasher.update(req.from_owner.as_bytes());
+    hasher.update(req.to_owner.as_bytes());
+    hasher.update(req.transfer_date.as_bytes());
+    let digest = hasher.finalize();
+    hex::encode(digest)
+}
+
+/// Recompute a stored record's transfer hash with the scheme it was written
+/// under and compare.
+pub fn verify_transfer_record(record: &TransferRecord) -> bool {
+    let req = TransferRequest {
+        document_hash: record.document_hash.clone(),
+        from_owner: record.from_owner.clone(),
+        to_owner: record.to_owner.clone(),
+        transfer_date: record.transfer_date.clone(),
+        transfer_reference: record.transfer_reference.clone(),
+        expected_sequence: None,
+    };
+    let expected = match record.hash_version {
+        1 => compute_legacy_transfer_hash(&req),
+        _ => compute_transfer_hash(&req),
+    };
+    expected == record.transfer_hash
+}
+
+/// Validate that the provided date is a valid ISO 8601 calendar date (YYYY-MM-DD).
+fn is_valid_iso8601_date(date: &str) -> bool {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok()
+}
+
+/// Build a Stellar memo string for a transfer hash, respecting the 28-byte
+/// text memo limit and using the required TRANSFER: prefix.
+fn build_transfer_memo(transfer_hash: &str) -> String {
+    const PREFIX: &str = "TRANSFER:";
+    const MAX_MEMO_LEN: usize = 28;
+
+    let remaining = MAX_MEMO_LEN.saturating_sub(PREFIX.len());
+    let truncated = if transfer_hash.len() > remaining {
+        &transfer_hash[..remaining]
+    } else {
+        transfer_hash
+    };
+
+    format!("{}{}", PREFIX, truncated)
+}
+
+/// POST /transfer — anchor an ownership transfer on Stellar and persist history in Redis.
+pub async fn record_transfer(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<TransferRequest>,
+) -> Result<Json<TransferResponse>, ApiError> {
+    HashValidator::validate_sha256(&HashValidator::normalize(&req.document_hash))
+        .map_err(|err| map_validation_error(&state.metrics, err))?;
+    if !is_valid_iso8601_date(&req.transfer_date) {
+        return Err(ApiError::Validation(
+            "transfer_date must be an ISO 8601 date (YYYY-MM-DD)".to_string(),
+        ));
+    }
+
+    let normalized_hash = HashValidator::normalize(&req.document_hash);
+    // Check before anchoring so a stale caller doesn't submit a transaction.
+    if let Some(expected) = req.expected_sequence {
+        let actual = state.events.last_sequence(&normalized_hash).await?;
+        if actual != expected {
+            return Err(AuditError::ConcurrencyConflict {
+                aggregate_id: normalized_hash,
+                expected,
+                actual,
+            }
+            .into());
+        }
+    }
+
+    let transfer_hash = compute_transfer_hash(&req);
+    let memo = build_transfer_memo(&transfer_hash);
+
+    let anchor_account_id = anchor_account_id(&state)?;
+
+    let anchor = state
+        .stellar
+        .anchor_transfer(
+            &transfer_hash,
+            &anchor_account_id,
+            &state.stellar_secret_key,
+        )
+        .await
+        .map_err(|e| {
+            warn!("Failed to anchor transfer on Stellar: {}", e);
+            state.metrics.increment_error_count();
+            ApiError::StellarUnavailable(format!("failed to anchor transfer: {}", e))
+        })?;
+
+    let record = TransferRecord {
+        document_hash: req.document_hash.clone(),
+        from_owner: req.from_owner.clone(),
+        to_owner: req.to_owner.clone(),
+        transfer_date: req.transfer_date.clone(),
+        transfer_reference: req.transfer_reference.clone(),
+        transfer_hash: transfer_hash.clone(),
+        memo: memo.clone(),
+        anchored_at: Utc::now().to_rfc3339(),
+        hash_version: TRANSFER_HASH_VERSION,
+    };
+
+    let key = format!(
+        "transfer:{}",
+        HashValidator::normalize(&record.document_hash)
+    );
+
+    let mut history: Vec<TransferRecord> = match state.cache.get(&key).await {
+        Ok(Some(existing)) => existing,
+        Ok(None) => Vec::new(),
+        Err(e) => {
+            warn!("Failed to read transfer history from cache: {}", e);
+            state.metrics.increment_error_count();
+            return Err(ApiError::Cache(format!(
+                "failed to read transfer history: {}",
+                e
+            )));
+        }
+    };
+
+    history.push(record);
+
+    // Set a long but finite TTL (10 years) to keep an auditable history
+    const TEN_YEARS_SECONDS: u64 = 60 * 60 * 24 * 365 * 10;
+    if let Err(e) = state.cache.set(&key, &history, TEN_YEARS_SECONDS).await {
+        warn!("Failed to persist transfer history: {}", e);
+        state.metrics.increment_error_count();
+        return Err(ApiError::Cache(format!(
+            "failed to persist transfer history: {}",
+            e
+        )));
+    }
+
+    invalidate_document_status(&state, &normalized_hash).await;
+    let event = Event::new(
+        normalized_hash,
+        TRANSFERRED_EVENT.to_string(),
+        serde_json::json!({
+            "transfer_hash": transfer_hash,
+            "from_owner": req.from_owner,
+            "to_owner": req.to_owner,
+            "transaction_id": anchor.tx_hash,
+        }),
+        request_actor(&headers),
+    );
+    match req.expected_sequence {
+        Some(expected) => record_expected_event(&state, event, expected).await,
+        None => record_event(&state, event).await,
+    }
+
+    Ok(Json(TransferResponse {
+        transfer_hash,
+        memo,
+        transaction_id: Some(anchor.tx_hash),
+    }))
+}
+
+const DEFAULT_TRANSFER_PAGE_SIZE: usize = 50;
+const MAX_TRANSFER_PAGE_SIZE: usize = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct TransferHistoryQuery {
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransferHistoryResponse {
+    /// Records on this page, newest `anchored_at` first.
+    pub records: Vec<TransferRecord>,
+    pub total: usize,
+    pub page: usize,
+    pub per_page: usize,
+}
+
+/// GET /transfer/:document_hash — retrieve a page of transfer history for a document.
+pub async fn get_transfer_history(
+    State(state): State<AppState>,
+    Path(document_hash): Path<String>,
+    query: Result<Query<TransferHistoryQuery>, QueryRejection>,
+) -> Result<Json<TransferHistoryResponse>, ApiError> {
+    let Query(query) = query.map_err(|e| ApiError::Validation(e.body_text()))?;
+    let page = query.page.unwrap_or(1);
+    let per_page = query.per_page.unwrap_or(DEFAULT_TRANSFER_PAGE_SIZE);
+    if page == 0 {
+        return Err(ApiError::Validation("page must be at least 1".to_string()));
+    }
+    if per_page == 0 || per_page > MAX_TRANSFER_PAGE_SIZE {
+        return Err(ApiError::Validation(format!(
+            "per_page must be between 1 and {}",
+            MAX_TRANSFER_PAGE_SIZE
+        )));
+    }
+
+    let key = format!("transfer:{}", HashValidator::normalize(&document_hash));
+    let mut history = match state.cache.get::<Vec<TransferRecord>>(&key).await {
+        Ok(history) => history.unwrap_or_default(),
+        Err(e) => {
+            warn!("Failed to fetch transfer history from cache: {}", e);
+            state.metrics.increment_error_count();
+            return Err(ApiError::Cache(format!(
+                "failed to read transfer history: {}",
+                e
+            )));
+        }
+    };
+
+    history.sort_by_key(|record| std::cmp::Reverse(transfer_anchored_millis(record)));
+
+    let total = history.len();
+    let records = history
+        .into_iter()
+        .skip((page - 1).saturating_mul(per_page))
+        .take(per_page)
+        .collect();
+
+    Ok(Json(TransferHistoryResponse {
+        records,
+        total,
+        page,
+        per_page,
+    }))
+}
+
+/// `anchored_at` in milliseconds, for ordering; unparseable dates sort first.
+fn transfer_anchored_millis(record: &TransferRecord) -> i64 {
+    chrono::DateTime::parse_from_rfc3339(&record.anchored_at)
+        .map(|dt| dt.timestamp_millis())
+        .unwrap_or(i64::MIN)
+}
+
+/// How long a composite `GET /documents/:hash/status` answer is reused.
+const DOCUMENT_STATUS_TTL: u64 = 60;
+
+/// Everything a document detail page needs, in one response.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DocumentStatus {
+    pub hash: String,
+    pub verification: VerifyResponse,
+    /// Transaction that recorded the latest revocation, if any.
+    pub revocation: Option<TransactionEvidence>,
+    pub transfer_count: usize,
+    pub latest_transfer: Option<TransferRecord>,
+    /// When the hash was anchored.
+    pub anchored_at: Option<i64>,
+    pub revoked_at: Option<i64>,
+    /// `anchored_at` of the latest transfer.
+    pub last_transferred_at: Option<String>,
+    /// When this status was assembled; cached answers keep the original.
+    pub generated_at: i64,
+    pub cached: bool,
+}
+
+fn document_status_key(normalized_hash: &str) -> String {
+    format!("status:{}", normalized_hash)
+}
+
+/// Drop the cached document status after the document changes.
+async fn invalidate_document_status(state: &AppState, normalized_hash: &str) {
+    if let Err(e) = state
+        .cache
+        .delete(&document_status_key(normalized_hash))
+        .await
+    {
+        warn!(
+            "Failed to invalidate document status for {}: {}",
+            normalized_hash, e
+        );
+    }
+}
+
+/// GET /documents/:hash/status — verification, revocation and transfer
+/// summary for one document.
+pub async fn document_status(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+) -> Result<Json<DocumentStatus>, ApiError> {
+    let normalized_hash = HashValidator::normalize(&hash);
+    HashValidator::ensure_accepted(&normalized_hash, None, &state.accepted_algorithms)
+        .and_then(|()| HashValidator::validate_sha256(&normalized_hash))
+        .map_err(|err| map_validation_error(&state.metrics, err))?;
+
+    state.metrics.increment_request_count();
+
+    let status_key = document_status_key(&normalized_hash);
+    if let Ok(Some(mut status)) = state.cache.get::<DocumentStatus>(&status_key).await {
+        status.cached = true;
+        return Ok(Json(status));
+    }
+
+    let anchor_account_id = anchor_account_id(&state)?;
+    let transfer_key = format!("transfer:{}", normalized_hash);
+    let (verification, revocation, transfers) = tokio::join!(
+        verify_hash_cached(&state, &normalized_hash, None),
+        state
+            .stellar
+            .find_revocation_evidence(&normalized_hash, &anchor_account_id),
+        state.cache.get::<Vec<TransferRecord>>(&transfer_key),
+    );
+
+    let mut verification = verification?;
+    verification.evidence = None;
+
+    // Like anchor evidence, the revocation transaction is best-effort.
+    let revocation = revocation.unwrap_or_else(|e| {
+        warn!(
+            "Failed to look up revocation for {}: {}",
+            normalized_hash, e
+        );
+        None
+    });
+
+    let transfers = transfers
+        .map_err(|e| {
+            warn!("Failed to fetch transfer history from cache: {}", e);
             state.metrics.increment_error_count();
-            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
-        }
+            ApiError::Cache(format!("failed to read transfer history: {}", e))
+        })?
+        .unwrap_or_default();
+    let transfer_count = transfers.len();
+    let latest_transfer = transfers.into_iter().max_by_key(transfer_anchored_millis);
+
+    let status = DocumentStatus {
+        hash: normalized_hash,
+        anchored_at: verification.timestamp,
+        revoked_at: verification.revoked_at,
+        last_transferred_at: latest_transfer.as_ref().map(|t| t.anchored_at.clone()),
+        verification,
+        revocation,
+        transfer_count,
+        latest_transfer,
+        generated_at: Utc::now().timestamp(),
+        cached: false,
     };
 
-    // Query Stellar blockchain
-    let result = match state
-        .stellar
-        .verify_hash(&normalized_hash, &anchor_account_id)
+    if let Err(e) = state
+        .cache
+        .set(&status_key, &status, DOCUMENT_STATUS_TTL)
         .await
     {
-        Ok(verification) => verification,
-        Err(e) => {
-            warn!("Stellar query failed: {}", e);
-            state.metrics.increment_error_count();
-            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        warn!("Failed to cache document status for {}: {}", status.hash, e);
+    }
+
+    Ok(Json(status))
+}
+
+const DEFAULT_EVENTS_PAGE_SIZE: usize = 50;
+const MAX_EVENTS_PAGE_SIZE: usize = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct DocumentEventsQuery {
+    pub limit: Option<usize>,
+    /// Only return events after this sequence; pass the last one seen to page.
+    pub after_sequence: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DocumentEventsResponse {
+    pub hash: String,
+    /// Events in sequence order.
+    pub events: Vec<Event>,
+    /// Latest sequence recorded for the document.
+    pub last_sequence: u64,
+}
+
+/// GET /documents/:hash/events — audit trail of one document.
+pub async fn document_events(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+    query: Result<Query<DocumentEventsQuery>, QueryRejection>,
+) -> Result<Json<DocumentEventsResponse>, ApiError> {
+    let Query(query) = query.map_err(|e| ApiError::Validation(e.body_text()))?;
+    let normalized_hash = HashValidator::normalize(&hash);
+    HashValidator::validate_sha256(&normalized_hash)
+        .map_err(|err| map_validation_error(&state.metrics, err))?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_EVENTS_PAGE_SIZE);
+    if limit == 0 || limit > MAX_EVENTS_PAGE_SIZE {
+        return Err(ApiError::Validation(format!(
+            "limit must be between 1 and {}",
+            MAX_EVENTS_PAGE_SIZE
+        )));
+    }
+
+    let events = state
+        .events
+        .list_after(&normalized_hash, query.after_sequence.unwrap_or(0), limit)
+        .await?;
+    let last_sequence = state.events.last_sequence(&normalized_hash).await?;
+
+    Ok(Json(DocumentEventsResponse {
+        hash: normalized_hash,
+        events,
+        last_sequence,
+    }))
+}
+
+// Verify document by POST
+pub async fn verify_document(
+    State(state): State<AppState>,
+    Query(query): Query<VerifyQuery>,
+    headers: HeaderMap,
+    Json(req): Json<VerifyRequest>,
+) -> Result<Response, ApiError> {
+    let normalized_hash = HashValidator::normalize(&req.document_hash);
+    let algorithm = req
+        .algorithm
+        .or_else(|| HashValidator::detect_algorithm(&normalized_hash))
+        .unwrap_or(HashAlgorithm::SHA256);
+    if let Err(err) = HashValidator::ensure_accepted(
+        &normalized_hash,
+        Some(algorithm),
+        &state.accepted_algorithms,
+    )
+    .and_then(|()| HashValidator::validate(&normalized_hash, algorithm))
+    {
+        return Err(map_validation_error(&state.metrics, err));
+    }
+
+    info!("Verifying document hash: {}", normalized_hash);
+    state.metrics.increment_request_count();
+
+    let include_evidence = query.includes("evidence");
+
+    let mut response = match req.transaction_id.as_deref().filter(|id| !id.is_empty()) {
+        Some(tx_id) => verify_with_transaction_hint(&state, &normalized_hash, tx_id).await?,
+        None => verify_hash_cached(&state, &normalized_hash, query.max_age_secs).await?,
+    };
+
+    // Only anchored documents have a history worth auditing; recording
+    // lookups of arbitrary hashes would let anyone grow the event store.
+    if response.verified {
+        record_event(
+            &state,
+            Event::new(
+                normalized_hash.clone(),
+                VERIFIED_EVENT.to_string(),
+                serde_json::json!({
+                    "revoked": response.revoked,
+                    "transaction_id": response.transaction_id,
+                }),
+                request_actor(&headers),
+            ),
+        )
+        .await;
+    }
+
+    if !include_evidence {
+        response.evidence = None;
+    }
+    negotiate_verify_response(&headers, response)
+}
+
+/// Check the hash against the caller's `transaction_id` only, skipping the
+/// cache and the account scan. The transaction must come from the anchoring
+/// account, and a match is still checked for revocation.
+async fn verify_with_transaction_hint(
+    state: &AppState,
+    normalized_hash: &str,
+    tx_id: &str,
+) -> Result<VerifyResponse, ApiError> {
+    let tx_id = validate_transaction_id(tx_id)?;
+    let tx_id = tx_id.as_str();
+    let anchor_account_id = anchor_account_id(state)?;
+    let stellar_unavailable = |e: anyhow::Error| {
+        warn!("Stellar transaction lookup failed: {}", e);
+        state.metrics.increment_error_count();
+        ApiError::StellarUnavailable(format!("Stellar query failed: {}", e))
+    };
+    let verification = state
+        .stellar
+        .verify_hash_in_transaction(normalized_hash, tx_id, &anchor_account_id)
+        .await
+        .map_err(stellar_unavailable)?;
+
+    let (evidence, error) = match verification {
+        TransactionVerification::Matched(evidence) => (evidence, None),
+        TransactionVerification::MemoMismatch(evidence) => {
+            let error = format!(
+                "memo mismatch: transaction {} does not carry this document hash",
+                tx_id
+            );
+            (evidence, Some(error))
+        }
+        TransactionVerification::ForeignSource(evidence) => {
+            let error = format!(
+                "transaction {} was not submitted by the anchoring account",
+                tx_id
+            );
+            (evidence, Some(error))
+        }
+        TransactionVerification::NotFound => {
+            return Err(ApiError::NotFound(format!(
+                "transaction {} not found",
+                tx_id
+            )))
         }
     };
 
-    let response = VerifyResponse {
-        verified: result.anchored,
-        transaction_id: result.transaction_id,
-        timestamp: result.timestamp,
-        cached: false,
-        revoked: None,
-        revoked_at: None,
+    let verified = error.is_none();
+    let timestamp = verified
+        .then(|| chrono::DateTime::parse_from_rfc3339(&evidence.created_at).ok())
+        .flatten()
+        .map(|dt| dt.timestamp());
+    record_verification_age(&state.metrics, verified, timestamp);
+
+    let revocation = if verified {
+        state
+            .stellar
+            .revocation_status(normalized_hash, &anchor_account_id)
+            .await
+            .map_err(stellar_unavailable)?
+    } else {
+        RevocationStatus::default()
     };
 
-    Json(response).into_response()
+    Ok(VerifyResponse {
+        verified,
+        transaction_id: Some(evidence.transaction_id.clone()),
+        timestamp,
+        cached: false,
+        revoked: revocation.revoked,
+        revoked_at: revocation.revoked_at,
+        revoked_transaction_id: revocation.transaction_id,
+        last_checked_at: Utc::now().timestamp(),
+        evidence: Some(evidence),
+        error,
+    })
 }
 
 // Verify document by GET with hash in path
 pub async fn verify_document_by_hash(
     State(state): State<AppState>,
+    query: Query<VerifyQuery>,
+    headers: HeaderMap,
     Path(hash): Path<String>,
-) -> Response {
+) -> Result<Response, ApiError> {
     let req = VerifyRequest {
         document_hash: hash,
         transaction_id: None,
+        algorithm: None,
     };
-    verify_document(State(state), Json(req)).await
+    verify_document(State(state), query, headers, Json(req)).await
 }
 
 // Verify document history by hash
 pub async fn verify_document_history(
     State(state): State<AppState>,
     Path(hash): Path<String>,
-) -> Response {
+) -> Result<Json<HistoryResponse>, ApiError> {
     let normalized_hash = HashValidator::normalize(&hash);
-    if let Err(err) = HashValidator::validate_sha256(&normalized_hash) {
-        let (status, body) = map_validation_error(err);
-        return (status, Json(body)).into_response();
-    }
+    HashValidator::validate_sha256(&normalized_hash)
+        .map_err(|err| map_validation_error(&state.metrics, err))?;
 
     let cache_key = format!("history:{}", normalized_hash);
     let transactions: Vec<TransactionRecord> = match state.cache.get(&cache_key).await {
@@ -424,64 +1681,357 @@ pub async fn verify_document_history(
         Ok(None) => Vec::new(),
         Err(e) => {
             warn!("Failed to fetch history from cache: {}", e);
-            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            return Err(ApiError::Cache(format!("failed to read history: {}", e)));
         }
     };
 
     let count = transactions.len();
     let cached = !transactions.is_empty();
 
-    Json(HistoryResponse {
+    Ok(Json(HistoryResponse {
         document_hash: normalized_hash,
         transactions,
         count,
         cached,
-    })
-    .into_response()
+    }))
 }
 
-// Batch verify documents
-pub async fn batch_verify_documents(
+/// GET /verify/:hash/proof — signed attestation for offline audit.
+///
+/// The proof is signed with the webhook secret, so anyone holding it can
+/// validate the attestation without contacting this service.
+pub async fn verify_document_proof(
     State(state): State<AppState>,
-    Json(req): Json<BatchVerifyRequest>,
-) -> Response {
-    // Validate batch size
-    if req.hashes.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ValidationErrorResponse {
-                error: "hashes array cannot be empty".to_string(),
-            }),
-        )
-            .into_response();
+    Path(hash): Path<String>,
+) -> Result<Json<VerificationProof>, ApiError> {
+    let normalized_hash = HashValidator::normalize(&hash);
+    HashValidator::validate_sha256(&normalized_hash)
+        .map_err(|err| map_validation_error(&state.metrics, err))?;
+
+    let secret = state
+        .webhook_secret
+        .as_deref()
+        .ok_or_else(|| ApiError::NotConfigured("proof signing is not configured".to_string()))?;
+
+    let anchor_account_id = anchor_account_id(&state)?;
+
+    let record = match state
+        .stellar
+        .verify_hash(&normalized_hash, &anchor_account_id)
+        .await
+    {
+        Ok(record) => record,
+        Err(e) => {
+            warn!("Stellar query failed: {}", e);
+            state.metrics.increment_error_count();
+            return Err(ApiError::StellarUnavailable(format!(
+                "Stellar query failed: {}",
+                e
+            )));
+        }
+    };
+
+    if !record.anchored {
+        return Err(ApiError::NotFound(
+            "document hash is not anchored".to_string(),
+        ));
     }
 
-    if req.hashes.len() > 50 {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ValidationErrorResponse {
-                error: "batch size exceeds maximum of 50 hashes".to_string(),
-            }),
-        )
-            .into_response();
+    // Fall back to our own submit record for the transaction details the
+    // account data entry does not carry.
+    let submitted = state
+        .cache
+        .get::<SubmitResponse>(&format!("stellar:verify:{}", normalized_hash))
+        .await
+        .unwrap_or(None);
+    let transaction_id = record
+        .transaction_id
+        .or_else(|| submitted.as_ref().and_then(|s| s.transaction_id.clone()));
+    let timestamp = record
+        .timestamp
+        .or_else(|| submitted.as_ref().and_then(|s| s.anchored_at));
+    let ledger = record.evidence.map(|e| e.ledger);
+
+    Ok(Json(VerificationProof::new_signed(
+        normalized_hash,
+        transaction_id,
+        ledger,
+        timestamp,
+        state.stellar.horizon_url().to_string(),
+        state.stellar.network_name().to_string(),
+        secret,
+    )))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProofValidationResponse {
+    pub valid: bool,
+}
+
+/// POST /verify/proof — check a proof issued by `GET /verify/:hash/proof`.
+///
+/// Valid when the signature matches and the anchor timestamp is not later
+/// than now by more than `CLOCK_SKEW_TOLERANCE_SECS`.
+pub async fn validate_document_proof(
+    State(state): State<AppState>,
+    Json(proof): Json<VerificationProof>,
+) -> Result<Json<ProofValidationResponse>, ApiError> {
+    let secret = state
+        .webhook_secret
+        .as_deref()
+        .ok_or_else(|| ApiError::NotConfigured("proof signing is not configured".to_string()))?;
+    state.metrics.increment_request_count();
+    Ok(Json(ProofValidationResponse {
+        valid: proof.validate(secret, &state.time, Utc::now().timestamp()),
+    }))
+}
+
+const MAX_TARGETED_TRANSACTIONS: usize = 20;
+/// Transactions never change once in a ledger, so they can be kept for a day.
+const TRANSACTION_CACHE_TTL: u64 = 60 * 60 * 24;
+
+/// Horizon transaction ids are the hex SHA-256 of the envelope.
+fn validate_transaction_id(transaction_id: &str) -> Result<String, ApiError> {
+    let normalized = transaction_id.trim().to_ascii_lowercase();
+    if normalized.len() != 64 || !normalized.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ApiError::Validation(format!(
+            "invalid transaction id {:?}: expected 64 hex characters",
+            transaction_id
+        )));
+    }
+    Ok(normalized)
+}
+
+/// POST /verify/targeted — check a hash against a caller-supplied list of
+/// transaction ids instead of scanning the anchoring account.
+///
+/// Each transaction is fetched by id (and cached) and reported as matched
+/// when the anchoring account submitted it and its memo or a `ManageData`
+/// operation carries the hash.
+pub async fn targeted_verify_document(
+    State(state): State<AppState>,
+    Json(req): Json<TargetedVerifyRequest>,
+) -> Result<Json<TargetedVerifyResponse>, ApiError> {
+    let normalized_hash = HashValidator::normalize(&req.hash);
+    let algorithm =
+        HashValidator::detect_algorithm(&normalized_hash).unwrap_or(HashAlgorithm::SHA256);
+    HashValidator::ensure_accepted(
+        &normalized_hash,
+        Some(algorithm),
+        &state.accepted_algorithms,
+    )
+    .and_then(|()| HashValidator::validate(&normalized_hash, algorithm))
+    .map_err(|err| map_validation_error(&state.metrics, err))?;
+
+    if req.transaction_ids.is_empty() {
+        return Err(ApiError::Validation(
+            "transaction_ids array cannot be empty".to_string(),
+        ));
+    }
+    if req.transaction_ids.len() > MAX_TARGETED_TRANSACTIONS {
+        return Err(ApiError::Validation(format!(
+            "transaction_ids exceed maximum of {} ids",
+            MAX_TARGETED_TRANSACTIONS
+        )));
     }
 
-    info!("Batch verifying {} document hashes", req.hashes.len());
+    let transaction_ids = req
+        .transaction_ids
+        .iter()
+        .map(|id| validate_transaction_id(id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let anchor_account_id = anchor_account_id(&state)?;
+
     state.metrics.increment_request_count();
 
-    // Process all hashes concurrently
-    let verification_futures: Vec<_> = req
-        .hashes
+    let lookups = transaction_ids.into_iter().map(|id| {
+        let state = state.clone();
+        let hash = normalized_hash.clone();
+        let anchor_account_id = anchor_account_id.clone();
+        async move {
+            let verification = match cached_transaction_evidence(&state, &id).await {
+                Ok(evidence) => {
+                    state
+                        .stellar
+                        .match_transaction(&hash, evidence, &anchor_account_id)
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            (id, verification)
+        }
+    });
+
+    let results: Vec<TargetedVerifyItem> = join_all(lookups)
+        .await
+        .into_iter()
+        .map(|(transaction_id, verification)| match verification {
+            Ok(verification) => TargetedVerifyItem {
+                matched: matches!(verification, TransactionVerification::Matched(_)),
+                transaction_id,
+                error: None,
+            },
+            Err(e) => {
+                warn!("Failed to fetch transaction {}: {}", transaction_id, e);
+                TargetedVerifyItem {
+                    transaction_id,
+                    matched: false,
+                    error: Some(e.to_string()),
+                }
+            }
+        })
+        .collect();
+
+    let matching_transaction_ids: Vec<String> = results
         .iter()
-        .map(|hash| {
-            let state = state.clone();
-            let hash = hash.clone();
+        .filter(|item| item.matched)
+        .map(|item| item.transaction_id.clone())
+        .collect();
+
+    Ok(Json(TargetedVerifyResponse {
+        hash: normalized_hash,
+        verified: !matching_transaction_ids.is_empty(),
+        matching_transaction_ids,
+        results,
+    }))
+}
+
+/// Fetch a transaction snapshot, reusing the cached copy when present.
+async fn cached_transaction_evidence(
+    state: &AppState,
+    transaction_id: &str,
+) -> anyhow::Result<TransactionEvidence> {
+    let key = format!("stellar:tx:{}", transaction_id);
+    if let Ok(Some(evidence)) = state.cache.get::<TransactionEvidence>(&key).await {
+        return Ok(evidence);
+    }
+
+    let evidence = state.stellar.transaction_evidence(transaction_id).await?;
+    if let Err(e) = state
+        .cache
+        .set(&key, &evidence, TRANSACTION_CACHE_TTL)
+        .await
+    {
+        warn!("Failed to cache transaction {}: {}", transaction_id, e);
+    }
+    Ok(evidence)
+}
+
+/// Largest batch accepted by `POST /verify/batch`.
+const MAX_BATCH_VERIFY: usize = 50;
+
+/// The summary response stays tiny, so it accepts far larger batches.
+const MAX_BATCH_VERIFY_SUMMARY: usize = 500;
+
+fn validate_batch_size(hashes: &[String], max: usize) -> Result<(), ApiError> {
+    if hashes.is_empty() {
+        return Err(ApiError::Validation(
+            "hashes array cannot be empty".to_string(),
+        ));
+    }
+    if hashes.len() > max {
+        return Err(ApiError::Validation(format!(
+            "batch size exceeds maximum of {} hashes",
+            max
+        )));
+    }
+    Ok(())
+}
+
+/// Verify hashes at most `state.batch_concurrency` at a time, returning
+/// results in input order. Hashes repeated after normalization are looked
+/// up once and the result is copied to each position.
+async fn verify_hashes(
+    state: &AppState,
+    hashes: &[String],
+    max_age_secs: Option<u64>,
+) -> Vec<BatchVerifyItem> {
+    info!("Batch verifying {} document hashes", hashes.len());
+    state.metrics.increment_request_count();
 
-            async move { verify_single_hash(&state, hash).await }
+    let mut unique: Vec<String> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let slots: Vec<usize> = hashes
+        .iter()
+        .map(|hash| {
+            *positions
+                .entry(HashValidator::normalize(hash))
+                .or_insert_with(|| {
+                    unique.push(hash.clone());
+                    unique.len() - 1
+                })
         })
         .collect();
 
-    let results = join_all(verification_futures).await;
+    // Answer what we can from the cache in one round-trip, then go to
+    // Stellar only for the misses.
+    let validated: Vec<Result<String, ApiError>> = unique
+        .iter()
+        .map(|hash| validate_batch_hash(state, hash))
+        .collect();
+    let keys: Vec<String> = validated
+        .iter()
+        .filter_map(|normalized| normalized.as_ref().ok().cloned())
+        .collect();
+    let mut cached = match state.cache.get_many::<VerifyResponse>(&keys).await {
+        Ok(cached) => cached,
+        Err(e) => {
+            warn!("Batch cache lookup failed: {}", e);
+            vec![None; keys.len()]
+        }
+    }
+    .into_iter();
+
+    let mut results: Vec<Option<BatchVerifyItem>> = vec![None; unique.len()];
+    let mut misses = Vec::new();
+    for (index, (hash, normalized)) in unique.into_iter().zip(validated).enumerate() {
+        let normalized = match normalized {
+            Ok(normalized) => normalized,
+            Err(e) => {
+                results[index] = Some(batch_item(hash, Err(e)));
+                continue;
+            }
+        };
+        let entry = cached.next().flatten();
+        match use_cached(state, &normalized, entry, max_age_secs) {
+            Some(hit) => results[index] = Some(batch_item(hash, Ok(hit))),
+            None => misses.push((index, hash, normalized)),
+        }
+    }
+
+    let fetched: Vec<(usize, BatchVerifyItem)> = futures::stream::iter(misses)
+        .map(|(index, hash, normalized)| async move {
+            let _inflight = state.metrics.batch_inflight_query();
+            let result = verify_uncached(state, &normalized).await;
+            (index, batch_item(hash, result))
+        })
+        .buffer_unordered(state.batch_concurrency.max(1))
+        .collect()
+        .await;
+    for (index, item) in fetched {
+        results[index] = Some(item);
+    }
+    let results: Vec<BatchVerifyItem> = results.into_iter().flatten().collect();
+
+    hashes
+        .iter()
+        .zip(slots)
+        .map(|(hash, slot)| BatchVerifyItem {
+            hash: hash.clone(),
+            ..results[slot].clone()
+        })
+        .collect()
+}
+
+// Batch verify documents
+pub async fn batch_verify_documents(
+    State(state): State<AppState>,
+    Query(query): Query<VerifyQuery>,
+    Json(req): Json<BatchVerifyRequest>,
+) -> Result<Json<BatchVerifyResponse>, ApiError> {
+    validate_batch_size(&req.hashes, MAX_BATCH_VERIFY)?;
+
+    let results = verify_hashes(&state, &req.hashes, query.max_age_secs).await;
 
     let verified_count = results.iter().filter(|item| item.verified).count();
     let failed_count = results.len() - verified_count;
@@ -493,154 +2043,450 @@ pub async fn batch_verify_documents(
         failed_count,
     };
 
-    Json(response).into_response()
+    Ok(Json(response))
+}
+
+/// Longest NDJSON line `/verify/stream` buffers before giving up.
+const MAX_STREAM_LINE_BYTES: usize = 1024;
+
+/// Split a request body into trimmed, non-empty lines as chunks arrive,
+/// holding at most one partial line in memory.
+fn body_lines<S, E>(body: S) -> impl futures::Stream<Item = Result<String, String>>
+where
+    S: futures::Stream<Item = Result<axum::body::Bytes, E>> + Unpin,
+    E: std::fmt::Display,
+{
+    futures::stream::unfold(
+        (body, Vec::new(), false),
+        |(mut body, mut buf, mut done)| async move {
+            loop {
+                if let Some(pos) = buf.iter().position(|b| *b == b'\n') {
+                    let line: Vec<u8> = buf.drain(..=pos).collect();
+                    let line = String::from_utf8_lossy(&line).trim().to_string();
+                    if !line.is_empty() {
+                        return Some((Ok(line), (body, buf, done)));
+                    }
+                    continue;
+                }
+                if done {
+                    let line = String::from_utf8_lossy(&buf).trim().to_string();
+                    buf.clear();
+                    return (!line.is_empty()).then_some((Ok(line), (body, buf, done)));
+                }
+                if buf.len() > MAX_STREAM_LINE_BYTES {
+                    let error = format!("line exceeds {} bytes", MAX_STREAM_LINE_BYTES);
+                    return Some((Err(error), (body, Vec::new(), true)));
+                }
+                match body.next().await {
+                    Some(Ok(chunk)) => buf.extend_from_slice(&chunk),
+                    Some(Err(e)) => {
+                        let error = format!("failed to read request body: {}", e);
+                        return Some((Err(error), (body, Vec::new(), true)));
+                    }
+                    None => done = true,
+                }
+            }
+        },
+    )
+}
+
+/// A `/verify/stream` line is a bare hash, a JSON string or `{"hash": ...}`.
+fn stream_line_hash(line: &str) -> String {
+    match serde_json::from_str::<serde_json::Value>(line) {
+        Ok(serde_json::Value::String(hash)) => hash,
+        Ok(serde_json::Value::Object(fields)) => fields
+            .get("hash")
+            .and_then(|hash| hash.as_str())
+            .unwrap_or(line)
+            .to_string(),
+        _ => line.to_string(),
+    }
+}
+
+/// POST /verify/stream — verify an NDJSON stream of hashes of any length,
+/// streaming one `BatchVerifyItem` line back per input line, in order.
+pub async fn stream_verify_documents(
+    State(state): State<AppState>,
+    Query(query): Query<VerifyQuery>,
+    body: axum::body::Body,
+) -> Response {
+    state.metrics.increment_request_count();
+
+    let max_age_secs = query.max_age_secs;
+    let concurrency = state.batch_concurrency.max(1);
+    let results = body_lines(body.into_data_stream())
+        .map(move |line| {
+            let state = state.clone();
+            async move {
+                match line {
+                    Ok(line) => {
+                        let _inflight = state.metrics.batch_inflight_query();
+                        verify_single_hash(&state, stream_line_hash(&line), max_age_secs).await
+                    }
+                    Err(error) => BatchVerifyItem {
+                        hash: String::new(),
+                        verified: false,
+                        transaction_id: None,
+                        timestamp: None,
+                        error: Some(error),
+                    },
+                }
+            }
+        })
+        .buffered(concurrency)
+        .map(|item| {
+            serde_json::to_vec(&item).map(|mut line| {
+                line.push(b'\n');
+                line
+            })
+        });
+
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        axum::body::Body::from_stream(results),
+    )
+        .into_response()
+}
+
+/// POST /verify/batch/summary — batch verification returning counts only.
+pub async fn batch_verify_summary(
+    State(state): State<AppState>,
+    Query(query): Query<VerifyQuery>,
+    Json(req): Json<BatchVerifyRequest>,
+) -> Result<Json<BatchVerifySummary>, ApiError> {
+    validate_batch_size(&req.hashes, MAX_BATCH_VERIFY_SUMMARY)?;
+
+    let results = verify_hashes(&state, &req.hashes, query.max_age_secs).await;
+
+    let verified_count = results.iter().filter(|item| item.verified).count();
+    let error_count = results.iter().filter(|item| item.error.is_some()).count();
+
+    Ok(Json(BatchVerifySummary {
+        total: results.len(),
+        verified_count,
+        failed_count: results.len() - verified_count,
+        error_count,
+    }))
 }
 
 // Helper function to verify a single hash
-async fn verify_single_hash(state: &AppState, hash: String) -> BatchVerifyItem {
-    let normalized_hash = HashValidator::normalize(&hash);
+async fn verify_single_hash(
+    state: &AppState,
+    hash: String,
+    max_age_secs: Option<u64>,
+) -> BatchVerifyItem {
+    let result = match validate_batch_hash(state, &hash) {
+        Ok(normalized_hash) => verify_hash_cached(state, &normalized_hash, max_age_secs).await,
+        Err(e) => Err(e),
+    };
+    batch_item(hash, result)
+}
 
-    if let Err(err) = HashValidator::validate_sha256(&normalized_hash) {
-        let error_msg = match err {
-            HashValidationError::EmptyHash => "hash must not be empty".to_string(),
-            HashValidationError::WrongLength { expected, actual } => format!(
-                "hash has wrong length: expected {} characters, got {}",
-                expected, actual
-            ),
-            HashValidationError::InvalidCharacter {
-                position,
-                character,
-            } => format!(
-                "hash contains invalid character '{}' at position {}",
-                character, position
-            ),
-        };
+/// Normalize and validate one hash of a batch.
+fn validate_batch_hash(state: &AppState, hash: &str) -> Result<String, ApiError> {
+    let normalized_hash = HashValidator::normalize(hash);
+    validate_detected_hash(state, &normalized_hash).map_err(|err| {
+        state.metrics.increment_validation_failure(err.kind());
+        ApiError::Validation(validation_error_message(&err))
+    })?;
+    Ok(normalized_hash)
+}
+
+/// Validate against the algorithm the hash's length implies (SHA-256 when
+/// it matches none), as `/verify` does.
+fn validate_detected_hash(
+    state: &AppState,
+    normalized_hash: &str,
+) -> Result<(), HashValidationError> {
+    let algorithm =
+        HashValidator::detect_algorithm(normalized_hash).unwrap_or(HashAlgorithm::SHA256);
+    HashValidator::ensure_accepted(normalized_hash, Some(algorithm), &state.accepted_algorithms)
+        .and_then(|()| HashValidator::validate(normalized_hash, algorithm))
+}
+
+/// Anchors store the hex digest as a 64-byte `ManageData` value or a
+/// 32-byte hash memo, so only SHA-256 can be submitted.
+fn validate_anchorable_hash(
+    state: &AppState,
+    normalized_hash: &str,
+) -> Result<(), HashValidationError> {
+    HashValidator::ensure_accepted(normalized_hash, None, &state.accepted_algorithms)
+        .and_then(|()| HashValidator::validate_only(normalized_hash, HashAlgorithm::SHA256))
+}
 
-        return BatchVerifyItem {
+fn batch_item(hash: String, result: Result<VerifyResponse, ApiError>) -> BatchVerifyItem {
+    match result {
+        Ok(response) => BatchVerifyItem {
+            hash,
+            verified: response.verified,
+            transaction_id: response.transaction_id,
+            timestamp: response.timestamp,
+            error: None,
+        },
+        Err(e) => BatchVerifyItem {
             hash,
             verified: false,
             transaction_id: None,
             timestamp: None,
-            error: Some(error_msg),
-        };
+            error: Some(e.message().to_string()),
+        },
     }
+}
 
-    // Check cache first
-    if let Ok(Some(cached)) = state.cache.get::<VerifyResponse>(&normalized_hash).await {
-        info!("Cache hit for hash: {}", normalized_hash);
-        state.metrics.increment_cache_hits();
+/// Verify an already validated hash, answering from the cache when fresh
+/// and caching whatever Stellar returns otherwise.
+async fn verify_hash_cached(
+    state: &AppState,
+    normalized_hash: &str,
+    max_age_secs: Option<u64>,
+) -> Result<VerifyResponse, ApiError> {
+    let cached = state
+        .cache
+        .get::<VerifyResponse>(normalized_hash)
+        .await
+        .ok()
+        .flatten();
+    match use_cached(state, normalized_hash, cached, max_age_secs) {
+        Some(hit) => Ok(hit),
+        None => verify_uncached(state, normalized_hash).await,
+    }
+}
 
-        return BatchVerifyItem {
-            hash,
-            verified: cached.verified,
-            transaction_id: cached.transaction_id,
-            timestamp: cached.timestamp,
-            error: None,
-        };
+/// Returns the cached result if it is fresh enough, recording the hit.
+fn use_cached(
+    state: &AppState,
+    normalized_hash: &str,
+    cached: Option<VerifyResponse>,
+    max_age_secs: Option<u64>,
+) -> Option<VerifyResponse> {
+    let mut cached = cached?;
+    if !is_fresh(&cached, max_age_secs, Utc::now().timestamp()) {
+        info!(
+            "Cached result for {} is stale; re-checking",
+            normalized_hash
+        );
+        return None;
     }
+    info!("Cache hit for hash: {}", normalized_hash);
+    state.metrics.increment_cache_hits();
+    record_verification_age(&state.metrics, cached.verified, cached.timestamp);
+    // Entries are stored as fetched; the flag describes this answer.
+    cached.cached = true;
+    Some(cached)
+}
 
+/// Query Stellar for a hash the cache could not answer, sharing the lookup
+/// with concurrent requests for the same hash.
+async fn verify_uncached(
+    state: &AppState,
+    normalized_hash: &str,
+) -> Result<VerifyResponse, ApiError> {
     state.metrics.increment_cache_misses();
 
-    let anchor_account_id = match derive_account_id(&state.stellar_secret_key) {
-        Ok(id) => id,
-        Err(e) => {
-            warn!("Failed to derive anchor account id: {}", e);
-            state.metrics.increment_error_count();
+    let flight_state = state.clone();
+    let hash = normalized_hash.to_string();
+    state
+        .verify_flights
+        .run(normalized_hash, move || async move {
+            fetch_verification(&flight_state, &hash).await
+        })
+        .await
+}
 
-            return BatchVerifyItem {
-                hash,
-                verified: false,
-                transaction_id: None,
-                timestamp: None,
-                error: Some(format!("failed to derive anchor account id: {}", e)),
-            };
-        }
-    };
+/// Query Stellar for `normalized_hash` and cache the answer.
+async fn fetch_verification(
+    state: &AppState,
+    normalized_hash: &str,
+) -> Result<VerifyResponse, ApiError> {
+    let anchor_account_id = anchor_account_id(state)?;
 
-    // Query Stellar blockchain
-    let result = match state
+    let result = state
         .stellar
-        .verify_hash(&normalized_hash, &anchor_account_id)
+        .verify_hash(normalized_hash, &anchor_account_id)
         .await
-    {
-        Ok(verification) => verification,
-        Err(e) => {
+        .map_err(|e| {
             warn!("Stellar query failed for hash {}: {}", normalized_hash, e);
             state.metrics.increment_error_count();
+            ApiError::StellarUnavailable(format!("stellar query failed: {}", e))
+        })?;
 
-            return BatchVerifyItem {
-                hash,
-                verified: false,
-                transaction_id: None,
-                timestamp: None,
-                error: Some(format!("stellar query failed: {}", e)),
-            };
-        }
-    };
-
-    // Cache the result
-    let cache_response = VerifyResponse {
+    let response = VerifyResponse {
         verified: result.anchored,
-        transaction_id: result.transaction_id.clone(),
+        transaction_id: result.transaction_id,
         timestamp: result.timestamp,
         cached: false,
-        revoked: None,
-        revoked_at: None,
+        revoked: result.revoked,
+        revoked_at: result.revoked_at,
+        revoked_transaction_id: result.revoked_transaction_id,
+        last_checked_at: Utc::now().timestamp(),
+        evidence: result.evidence,
+        error: None,
     };
 
-    if let Err(e) = state
-        .cache
-        .set(&normalized_hash, &cache_response, 3600)
-        .await
-    {
-        warn!("Failed to cache result for hash {}: {}", normalized_hash, e);
-    }
+    cache_verification_result(state, normalized_hash, &response).await;
 
-    BatchVerifyItem {
-        hash,
-        verified: result.anchored,
-        transaction_id: result.transaction_id,
-        timestamp: result.timestamp,
-        error: None,
-    }
+    record_verification_age(&state.metrics, response.verified, response.timestamp);
+
+    Ok(response)
 }
 
 /// POST /submit — anchor a document hash to Stellar using a ManageData operation.
 ///
 /// Request body: `{ document_hash, document_id, submitter }`
 ///
-/// On success returns `{ success: true, transaction_id, anchored_at }`.
-/// Duplicate submissions return the cached result with `200 OK` (idempotent).
+/// On success returns `201 Created` with `{ success: true, transaction_id, anchored_at }`.
+/// Returns `503` when no signing key is configured and `409` with the
+/// existing transaction id when the hash is already anchored.
 pub async fn submit_document(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<SubmitRequest>,
-) -> Response {
+) -> Result<Response, ApiError> {
     let normalized_hash = HashValidator::normalize(&req.document_hash);
-    if let Err(err) = HashValidator::validate_sha256(&normalized_hash) {
-        let (status, body) = map_validation_error(err);
-        return (status, Json(body)).into_response();
+    validate_anchorable_hash(&state, &normalized_hash)
+        .map_err(|err| map_validation_error(&state.metrics, err))?;
+
+    if state.stellar_secret_key.is_empty() {
+        return Err(ApiError::NotConfigured(
+            "stellar signing key is not configured".to_string(),
+        ));
+    }
+
+    let anchor_account_id = anchor_account_id(&state)?;
+
+    info!(
+        "Submitting document hash {} for {}",
+        normalized_hash, req.submitter
+    );
+
+    let actor = request_actor(&headers);
+    match submit_single_hash(&state, &normalized_hash, &anchor_account_id, &actor).await {
+        SubmitOutcome::Anchored(response) => {
+            Ok((StatusCode::CREATED, Json(response)).into_response())
+        }
+        // The conflict body keeps the SubmitResponse shape so clients can
+        // read the existing transaction id.
+        SubmitOutcome::AlreadyAnchored {
+            transaction_id,
+            anchored_at,
+        } => Ok(submit_conflict(transaction_id, anchored_at)),
+        SubmitOutcome::AccountNotReady(error) => Err(ApiError::AccountNotReady(error)),
+        SubmitOutcome::Failed(error) => Err(ApiError::StellarUnavailable(error)),
+    }
+}
+
+/// Result of anchoring a single validated hash.
+enum SubmitOutcome {
+    Anchored(SubmitResponse),
+    AlreadyAnchored {
+        transaction_id: Option<String>,
+        anchored_at: Option<i64>,
+    },
+    /// The anchoring account cannot currently submit (balance or signers).
+    AccountNotReady(String),
+    Failed(String),
+}
+
+/// Check the anchoring account can pay for and sign a ManageData
+/// transaction before building one, so Horizon never sees a doomed envelope.
+async fn check_anchor_account(
+    state: &AppState,
+    anchor_account_id: &str,
+) -> Result<(), StellarError> {
+    let result = match state.stellar.account_info(anchor_account_id).await {
+        Ok(info) => info.check_can_anchor(anchor_account_id),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = &result {
+        warn!("Anchoring account check failed: {}", e);
+        state.metrics.increment_error_count();
     }
+    result
+}
+
+impl From<StellarError> for SubmitOutcome {
+    fn from(e: StellarError) -> Self {
+        match e {
+            StellarError::Horizon(_) => SubmitOutcome::Failed(e.to_string()),
+            _ => SubmitOutcome::AccountNotReady(e.to_string()),
+        }
+    }
+}
 
+/// Anchor `normalized_hash` unless our records or the chain show it is
+/// already anchored. Shared by `/submit` and `/submit/batch`.
+async fn submit_single_hash(
+    state: &AppState,
+    normalized_hash: &str,
+    anchor_account_id: &str,
+    actor: &str,
+) -> SubmitOutcome {
     let cache_key = format!("stellar:verify:{}", normalized_hash);
+    const ANCHOR_CACHE_TTL: u64 = 60 * 60 * 24 * 365; // 1 year
 
-    // Idempotency check — return cached anchor result if it exists.
-    if let Ok(Some(cached)) = state.cache.get::<SubmitResponse>(&cache_key).await {
+    // Duplicate check against our own records first, then the chain.
+    // Any record under the anchor key (including a revocation) means anchored.
+    if let Ok(Some(raw)) = state.cache.get_raw(&cache_key).await {
         info!(
-            "Cache hit for submit: returning existing anchor for {}",
+            "Cache hit for submit: {} is already anchored",
             normalized_hash
         );
-        return Json(cached).into_response();
+        let cached = serde_json::from_str::<SubmitResponse>(&raw).ok();
+        return SubmitOutcome::AlreadyAnchored {
+            transaction_id: cached.as_ref().and_then(|c| c.transaction_id.clone()),
+            anchored_at: cached.and_then(|c| c.anchored_at),
+        };
     }
 
-    info!(
-        "Anchoring document hash {} submitted by {}",
-        normalized_hash, req.submitter
-    );
+    match state
+        .stellar
+        .verify_hash(normalized_hash, anchor_account_id)
+        .await
+    {
+        Ok(existing) if existing.anchored => {
+            info!(
+                "Document hash {} is already anchored on-chain",
+                normalized_hash
+            );
+            let record = SubmitResponse {
+                success: true,
+                transaction_id: existing.transaction_id.clone(),
+                anchored_at: existing.timestamp,
+                error: None,
+            };
+            if let Err(e) = state.cache.set(&cache_key, &record, ANCHOR_CACHE_TTL).await {
+                warn!(
+                    "Failed to cache anchor result for {}: {}",
+                    normalized_hash, e
+                );
+            }
+            return SubmitOutcome::AlreadyAnchored {
+                transaction_id: existing.transaction_id,
+                anchored_at: existing.timestamp,
+            };
+        }
+        Ok(_) => {}
+        Err(e) => {
+            warn!("Stellar query failed for {}: {}", normalized_hash, e);
+            state.metrics.increment_error_count();
+            return SubmitOutcome::Failed(e.to_string());
+        }
+    }
+
+    if let Err(e) = check_anchor_account(state, anchor_account_id).await {
+        return e.into();
+    }
+
+    info!("Anchoring document hash {}", normalized_hash);
     state.metrics.increment_request_count();
 
     match state
         .stellar
-        .anchor_hash(&normalized_hash, &req.submitter, &state.stellar_secret_key)
+        .anchor_hash(
+            normalized_hash,
+            anchor_account_id,
+            &state.stellar_secret_key,
+        )
         .await
     {
         Ok(result) => {
@@ -651,8 +2497,7 @@ pub async fn submit_document(
                 error: None,
             };
 
-            // Cache the result so duplicate submissions get a fast 200.
-            const ANCHOR_CACHE_TTL: u64 = 60 * 60 * 24 * 365; // 1 year
+            // Cache the result so duplicate submissions are caught without Horizon.
             if let Err(e) = state
                 .cache
                 .set(&cache_key, &response, ANCHOR_CACHE_TTL)
@@ -664,27 +2509,145 @@ pub async fn submit_document(
                 );
             }
 
+            // A cached "not verified" result would now be wrong.
+            if let Err(e) = state.cache.delete(normalized_hash).await {
+                warn!(
+                    "Failed to invalidate verify cache for {}: {}",
+                    normalized_hash, e
+                );
+            }
+            invalidate_document_status(state, normalized_hash).await;
+
+            record_event(
+                state,
+                Event::new(
+                    normalized_hash.to_string(),
+                    ANCHORED_EVENT.to_string(),
+                    serde_json::json!({
+                        "transaction_id": result.tx_hash,
+                        "ledger": result.ledger,
+                        "anchored_at": result.anchored_at,
+                    }),
+                    actor.to_string(),
+                ),
+            )
+            .await;
+
             info!(
                 "Document hash {} anchored in ledger {} (tx: {})",
                 normalized_hash, result.ledger, result.tx_hash
             );
-            Json(response).into_response()
+            SubmitOutcome::Anchored(response)
         }
         Err(e) => {
             warn!("Stellar anchor failed for {}: {}", normalized_hash, e);
             state.metrics.increment_error_count();
-            (
-                StatusCode::BAD_GATEWAY,
-                Json(SubmitResponse {
-                    success: false,
-                    transaction_id: None,
-                    anchored_at: None,
-                    error: Some(e.to_string()),
-                }),
-            )
-                .into_response()
+            SubmitOutcome::Failed(e.to_string())
+        }
+    }
+}
+
+const MAX_BATCH_SUBMIT: usize = 25;
+
+/// POST /submit/batch — anchor up to 25 hashes.
+///
+/// Submissions run one after another because each transaction consumes the
+/// anchoring account's next sequence number. Hashes that are already
+/// anchored are reported with `skipped: true`.
+pub async fn batch_submit_documents(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<BatchSubmitRequest>,
+) -> Result<Json<BatchSubmitResponse>, ApiError> {
+    if req.hashes.is_empty() {
+        return Err(ApiError::Validation(
+            "hashes array cannot be empty".to_string(),
+        ));
+    }
+
+    if req.hashes.len() > MAX_BATCH_SUBMIT {
+        return Err(ApiError::Validation(format!(
+            "batch size exceeds maximum of {} hashes",
+            MAX_BATCH_SUBMIT
+        )));
+    }
+
+    if state.stellar_secret_key.is_empty() {
+        return Err(ApiError::NotConfigured(
+            "stellar signing key is not configured".to_string(),
+        ));
+    }
+
+    let anchor_account_id = anchor_account_id(&state)?;
+    let actor = request_actor(&headers);
+
+    // Fail the whole batch up front rather than item by item.
+    check_anchor_account(&state, &anchor_account_id).await?;
+
+    info!("Batch submitting {} document hashes", req.hashes.len());
+
+    let mut results = Vec::with_capacity(req.hashes.len());
+    for hash in req.hashes {
+        let normalized_hash = HashValidator::normalize(&hash);
+        if let Err(err) = validate_anchorable_hash(&state, &normalized_hash) {
+            state.metrics.increment_validation_failure(err.kind());
+            results.push(BatchSubmitItem::failed(
+                hash,
+                validation_error_message(&err),
+            ));
+            continue;
         }
+
+        let item =
+            match submit_single_hash(&state, &normalized_hash, &anchor_account_id, &actor).await {
+                SubmitOutcome::Anchored(response) => BatchSubmitItem {
+                    hash,
+                    transaction_id: response.transaction_id,
+                    anchored_at: response.anchored_at,
+                    skipped: false,
+                    error: None,
+                },
+                SubmitOutcome::AlreadyAnchored {
+                    transaction_id,
+                    anchored_at,
+                } => BatchSubmitItem {
+                    hash,
+                    transaction_id,
+                    anchored_at,
+                    skipped: true,
+                    error: None,
+                },
+                SubmitOutcome::AccountNotReady(error) | SubmitOutcome::Failed(error) => {
+                    BatchSubmitItem::failed(hash, error)
+                }
+            };
+        results.push(item);
     }
+
+    let total = results.len();
+    let skipped_count = results.iter().filter(|r| r.skipped).count();
+    let failed_count = results.iter().filter(|r| r.error.is_some()).count();
+
+    Ok(Json(BatchSubmitResponse {
+        results,
+        total,
+        submitted_count: total - skipped_count - failed_count,
+        skipped_count,
+        failed_count,
+    }))
+}
+
+fn submit_conflict(transaction_id: Option<String>, anchored_at: Option<i64>) -> Response {
+    (
+        StatusCode::CONFLICT,
+        Json(SubmitResponse {
+            success: false,
+            transaction_id,
+            anchored_at,
+            error: Some("document hash is already anchored".to_string()),
+        }),
+    )
+        .into_response()
 }
 
 /// POST /revoke — record a document revocation on Stellar.
@@ -694,37 +2657,51 @@ pub async fn submit_document(
 /// preserved so audit history remains intact.
 ///
 /// After a successful on-chain revocation the Redis cache entry for
-/// `stellar:verify:{hash}` is updated so that subsequent `GET /verify/:hash`
-/// calls return `{ verified: true, revoked: true, revokedAt }`.
+/// `stellar:verify:{hash}` is updated to record the revocation, the cached
+/// `/verify` answer is dropped, a `Revoked` event is recorded and the
+/// `hash_revoked` webhook fires.
 ///
-/// Returns `404` if the hash has no prior anchor record.
+/// Returns `404` if the hash is not anchored on-chain and `409` if it is
+/// already revoked.
 pub async fn revoke_document(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<RevokeRequest>,
-) -> Response {
+) -> Result<Json<RevokeResponse>, ApiError> {
     let normalized_hash = HashValidator::normalize(&req.document_hash);
-    if let Err(err) = HashValidator::validate_sha256(&normalized_hash) {
-        let (status, body) = map_validation_error(err);
-        return (status, Json(body)).into_response();
-    }
+    HashValidator::validate_sha256(&normalized_hash)
+        .map_err(|err| map_validation_error(&state.metrics, err))?;
 
     let anchor_key = format!("stellar:verify:{}", normalized_hash);
 
-    // Ensure the document was previously anchored before revoking.
-    let existing: Option<SubmitResponse> = state
-        .cache
-        .get::<SubmitResponse>(&anchor_key)
+    let anchor_account_id = anchor_account_id(&state)?;
+
+    // Ensure the document is anchored before revoking.
+    let existing = match state
+        .stellar
+        .verify_hash(&normalized_hash, &anchor_account_id)
         .await
-        .unwrap_or(None);
+    {
+        Ok(record) if record.anchored => record,
+        Ok(_) => {
+            return Err(ApiError::NotFound("Document hash not found".to_string()));
+        }
+        Err(e) => {
+            warn!("Stellar query failed for {}: {}", normalized_hash, e);
+            state.metrics.increment_error_count();
+            return Err(ApiError::StellarUnavailable(format!(
+                "Stellar query failed: {}",
+                e
+            )));
+        }
+    };
 
-    if existing.is_none() {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(ValidationErrorResponse {
-                error: "document hash has no prior anchor record; cannot revoke".to_string(),
-            }),
-        )
-            .into_response();
+    // A second revocation would submit another transaction and replay the
+    // event and webhooks for a document that is already revoked.
+    if existing.revoked {
+        return Err(ApiError::Conflict(
+            "Document hash is already revoked".to_string(),
+        ));
     }
 
     info!(
@@ -750,7 +2727,7 @@ pub async fn revoke_document(
         .anchor_revocation(
             &normalized_hash,
             &revocation_value,
-            &req.revoked_by,
+            &anchor_account_id,
             &state.stellar_secret_key,
         )
         .await
@@ -759,11 +2736,15 @@ pub async fn revoke_document(
             // Update the cached verify entry to reflect revocation.
             let updated_verify = VerifyResponse {
                 verified: true,
-                transaction_id: existing.and_then(|r| r.transaction_id),
-                timestamp: Some(revoked_at),
+                transaction_id: existing.transaction_id,
+                timestamp: existing.timestamp,
                 cached: false,
-                revoked: Some(true),
+                revoked: true,
                 revoked_at: Some(revoked_at),
+                revoked_transaction_id: Some(result.tx_hash.clone()),
+                last_checked_at: revoked_at,
+                evidence: None,
+                error: None,
             };
             const REVOKE_CACHE_TTL: u64 = 60 * 60 * 24 * 365;
             if let Err(e) = state
@@ -774,58 +2755,62 @@ pub async fn revoke_document(
                 warn!("Failed to update cache after revocation: {}", e);
             }
 
+            // Drop the cached /verify answer so readers re-check the chain.
+            if let Err(e) = state.cache.delete(&normalized_hash).await {
+                warn!("Failed to invalidate verify cache after revocation: {}", e);
+            }
+            invalidate_document_status(&state, &normalized_hash).await;
+
+            record_event(
+                &state,
+                Event::new(
+                    normalized_hash.clone(),
+                    REVOKED_EVENT.to_string(),
+                    serde_json::json!({
+                        "reason": req.reason,
+                        "revoked_by": req.revoked_by,
+                        "transaction_id": result.tx_hash,
+                        "revoked_at": revoked_at,
+                    }),
+                    request_actor(&headers),
+                ),
+            )
+            .await;
+
+            let webhook_data = serde_json::json!({
+                "hash": normalized_hash,
+                "reason": req.reason,
+                "revoked_by": req.revoked_by,
+                "transaction_id": result.tx_hash,
+                "revoked_at": revoked_at,
+            });
+            let webhooks = state.webhooks.clone();
+            tokio::spawn(async move {
+                webhooks.dispatch("hash_revoked", webhook_data).await;
+            });
+
             info!(
                 "Document {} revoked in ledger {} (tx: {})",
                 normalized_hash, result.ledger, result.tx_hash
             );
 
-            Json(RevokeResponse {
+            Ok(Json(RevokeResponse {
                 transaction_id: result.tx_hash,
                 revoked_at,
                 revoked: true,
-            })
-            .into_response()
+            }))
         }
         Err(e) => {
             warn!("Revocation failed for {}: {}", normalized_hash, e);
             state.metrics.increment_error_count();
-            (
-                StatusCode::BAD_GATEWAY,
-                Json(ValidationErrorResponse {
-                    error: format!("Stellar revocation failed: {}", e),
-                }),
-            )
-                .into_response()
+            Err(ApiError::StellarUnavailable(format!(
+                "Stellar revocation failed: {}",
+                e
+            )))
         }
     }
 }
 
-pub async fn transfer_document(Json(req): Json<TransferRequest>) -> impl IntoResponse {
-    let normalized_hash = HashValidator::normalize(&req.document_hash);
-    if let Err(err) = HashValidator::validate_sha256(&normalized_hash) {
-        let (status, body) = map_validation_error(err);
-        return (status, Json(body));
-    }
-
-    // Basic date validation: expect YYYY-MM-DD
-    if chrono::NaiveDate::parse_from_str(&req.transfer_date, "%Y-%m-%d").is_err() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ValidationErrorResponse {
-                error: "invalid date format, expected YYYY-MM-DD".to_string(),
-            }),
-        );
-    }
-
-    // Endpoint behavior not yet implemented; for now respond with BAD_REQUEST.
-    (
-        StatusCode::BAD_REQUEST,
-        Json(ValidationErrorResponse {
-            error: "transfer endpoint not yet implemented".to_string(),
-        }),
-    )
-}
-
 /// Calculates Levenshtein distance between two strings
 pub fn levenshtein_distance(s1: &str, s2: &str) -> usize {
     let len1 = s1.len();
@@ -919,12 +2904,54 @@ pub struct SimilarityResult {
     pub cosine: f64,
     pub levenshtein: f64,
     pub combined: f64,
+    /// True when `levenshtein` and `combined` are upper bounds rather than
+    /// exact scores because the pair could not reach `min_interesting`.
+    pub early_exit: bool,
+}
+
+/// Tuning for document comparisons.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimilarityConfig {
+    /// Combined score below which a pair is not interesting. Pairs that
+    /// provably cannot reach it skip the Levenshtein matrix. `0.0` always
+    /// computes exact scores.
+    pub min_interesting: f64,
+}
+
+/// Best Levenshtein similarity achievable given the length difference alone.
+fn levenshtein_similarity_upper_bound(s1: &str, s2: &str) -> f64 {
+    let max_len = s1.len().max(s2.len()) as f64;
+    if max_len == 0.0 {
+        return 1.0;
+    }
+    let char_diff = s1.chars().count().abs_diff(s2.chars().count()) as f64;
+    1.0 - (char_diff / max_len)
 }
 
 /// Compares two documents and returns similarity scores
 pub fn compare_documents(doc1: &str, doc2: &str) -> SimilarityResult {
+    compare_documents_with(doc1, doc2, &SimilarityConfig::default())
+}
+
+/// Compares two documents, short-circuiting pairs whose best possible
+/// combined score is below `config.min_interesting`.
+pub fn compare_documents_with(
+    doc1: &str,
+    doc2: &str,
+    config: &SimilarityConfig,
+) -> SimilarityResult {
+    // Disjoint token sets already yield 0.0 here without touching magnitudes.
     let cosine = cosine_similarity(doc1, doc2);
-    let levenshtein = levenshtein_similarity(doc1, doc2);
+
+    let levenshtein_bound = levenshtein_similarity_upper_bound(doc1, doc2);
+    let combined_bound = (cosine + levenshtein_bound) / 2.0;
+    let early_exit = combined_bound < config.min_interesting;
+
+    let levenshtein = if early_exit {
+        levenshtein_bound
+    } else {
+        levenshtein_similarity(doc1, doc2)
+    };
     let combined = (cosine + levenshtein) / 2.0;
 
     SimilarityResult {
@@ -933,23 +2960,36 @@ pub fn compare_documents(doc1: &str, doc2: &str) -> SimilarityResult {
         cosine,
         levenshtein,
         combined,
+        early_exit,
     }
 }
 
 /// Batch comparison of documents against a reference
 pub fn batch_compare(reference: &str, documents: &[&str]) -> Vec<SimilarityResult> {
+    batch_compare_with(reference, documents, &SimilarityConfig::default())
+}
+
+/// Batch comparison using the early-exit guardrails in `config`.
+pub fn batch_compare_with(
+    reference: &str,
+    documents: &[&str],
+    config: &SimilarityConfig,
+) -> Vec<SimilarityResult> {
     documents
         .iter()
-        .map(|doc| compare_documents(reference, doc))
+        .map(|doc| compare_documents_with(reference, doc, config))
         .collect()
 }
 
 /// Finds duplicate documents above threshold
 pub fn find_duplicates(documents: &[&str], threshold: f64) -> Vec<(usize, usize, f64)> {
+    let config = SimilarityConfig {
+        min_interesting: threshold,
+    };
     let mut duplicates = Vec::new();
     for i in 0..documents.len() {
         for j in (i + 1)..documents.len() {
-            let similarity = compare_documents(documents[i], documents[j]).combined;
+            let similarity = compare_documents_with(documents[i], documents[j], &config).combined;
             if similarity >= threshold {
                 duplicates.push((i, j, similarity));
             }
@@ -959,10 +2999,122 @@ pub fn find_duplicates(documents: &[&str], threshold: f64) -> Vec<(usize, usize,
     duplicates
 }
 
+/// Parallel variant of [`find_duplicates`] using rayon for the pairwise
+/// scoring. Returns the same pairs in the same order as the serial version.
+pub fn find_duplicates_parallel(documents: &[&str], threshold: f64) -> Vec<(usize, usize, f64)> {
+    find_duplicates_parallel_with_progress(documents, threshold, 0, |_, _| {})
+}
+
+/// Like [`find_duplicates_parallel`], but calls `on_progress(completed, total)`
+/// every `progress_every` comparisons (and once when finished). A
+/// `progress_every` of 0 only reports completion.
+pub fn find_duplicates_parallel_with_progress<F>(
+    documents: &[&str],
+    threshold: f64,
+    progress_every: usize,
+    on_progress: F,
+) -> Vec<(usize, usize, f64)>
+where
+    F: Fn(usize, usize) + Sync,
+{
+    use rayon::prelude::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let pairs: Vec<(usize, usize)> = (0..documents.len())
+        .flat_map(|i| ((i + 1)..documents.len()).map(move |j| (i, j)))
+        .collect();
+    let total = pairs.len();
+    let completed = AtomicUsize::new(0);
+    let config = SimilarityConfig {
+        min_interesting: threshold,
+    };
+
+    let mut duplicates: Vec<(usize, usize, f64)> = pairs
+        .par_iter()
+        .filter_map(|&(i, j)| {
+            let similarity = compare_documents_with(documents[i], documents[j], &config).combined;
+            let done = completed.fetch_add(1, Ordering::Relaxed) + 1;
+            if progress_every > 0 && done.is_multiple_of(progress_every) && done != total {
+                on_progress(done, total);
+            }
+            (similarity >= threshold).then_some((i, j, similarity))
+        })
+        .collect();
+
+    on_progress(total, total);
+    duplicates.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
+    duplicates
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn wants_xml_follows_accept_weights() {
+        let wants = |accept: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::ACCEPT, axum::http::HeaderValue::from_static(accept));
+            wants_xml(&headers)
+        };
+        assert!(wants("application/xml"));
+        assert!(wants("application/json;q=0, application/xml"));
+        assert!(wants("application/json;q=0.5, text/xml;q=0.8"));
+        assert!(!wants("application/xml, application/json"));
+        assert!(!wants("application/xml;q=0.4, application/json;q=0.9"));
+        assert!(!wants("application/xml;q=0"));
+        assert!(!wants("*/*"));
+        assert!(!wants_xml(&HeaderMap::new()));
+    }
+
+    #[test]
+    fn purge_prefix_must_name_a_hash_namespace() {
+        assert!(validate_purge_prefix("status:").is_ok());
+        assert!(validate_purge_prefix("history:e3b0").is_ok());
+        assert!(validate_purge_prefix("e3b0").is_ok());
+        assert!(validate_purge_prefix("").is_err());
+        assert!(validate_purge_prefix("events:").is_err());
+        assert!(validate_purge_prefix("status:E3").is_err());
+        assert!(validate_purge_prefix("status:*").is_err());
+    }
+
+    #[test]
+    fn tokens_match_only_identical_tokens() {
+        assert!(tokens_match("s3cret", "s3cret"));
+        assert!(!tokens_match("s3cre", "s3cret"));
+        assert!(!tokens_match("", "s3cret"));
+    }
+
+    #[tokio::test]
+    async fn body_lines_reassembles_lines_split_across_chunks() {
+        let chunks: Vec<Result<axum::body::Bytes, std::convert::Infallible>> = vec![
+            Ok("aa\n b".into()),
+            Ok("b \n\n".into()),
+            Ok("\"cc\"".into()),
+        ];
+        let lines: Vec<_> = body_lines(futures::stream::iter(chunks)).collect().await;
+        assert_eq!(
+            lines,
+            vec![
+                Ok("aa".to_string()),
+                Ok("bb".to_string()),
+                Ok("\"cc\"".to_string())
+            ]
+        );
+
+        let long: Vec<Result<axum::body::Bytes, std::convert::Infallible>> =
+            vec![Ok(vec![b'a'; MAX_STREAM_LINE_BYTES + 1].into())];
+        let lines: Vec<_> = body_lines(futures::stream::iter(long)).collect().await;
+        assert!(matches!(&lines[..], [Err(_)]));
+    }
+
+    #[test]
+    fn stream_lines_accept_bare_string_and_object_hashes() {
+        assert_eq!(stream_line_hash("abc"), "abc");
+        assert_eq!(stream_line_hash("\"abc\""), "abc");
+        assert_eq!(stream_line_hash(r#"{"hash":"abc"}"#), "abc");
+    }
+
     #[test]
     fn test_levenshtein_identical() {
         assert_eq!(levenshtein_distance("hello", "hello"), 0);
@@ -1019,6 +3171,65 @@ mod tests {
         assert_eq!(duplicates[0].1, 1);
     }
 
+    #[test]
+    fn test_early_exit_preserves_threshold_decisions() {
+        let docs = [
+            "deed",
+            "title deed",
+            "title deed for plot 12 in the northern district",
+            "title deed for plot 12 in the northern district, registered 2019",
+            "survey",
+            "",
+        ];
+        for threshold in [0.0, 0.25, 0.4, 0.5, 0.6, 0.75, 0.9, 1.0] {
+            let config = SimilarityConfig {
+                min_interesting: threshold,
+            };
+            for a in &docs {
+                for b in &docs {
+                    let exact = compare_documents(a, b);
+                    let fast = compare_documents_with(a, b, &config);
+                    assert_eq!(exact.combined >= threshold, fast.combined >= threshold);
+                    assert!(fast.combined >= exact.combined - 1e-12);
+                    if !fast.early_exit {
+                        assert_eq!(exact.combined, fast.combined);
+                    }
+                }
+            }
+        }
+
+        let skipped = compare_documents_with(
+            "deed",
+            "title deed for plot 12 in the northern district",
+            &SimilarityConfig {
+                min_interesting: 0.8,
+            },
+        );
+        assert!(skipped.early_exit);
+    }
+
+    #[test]
+    fn test_find_duplicates_parallel_matches_serial() {
+        let docs = vec![
+            "land title deed for plot 12",
+            "land title deed for plot 12",
+            "land title deed for plot 13",
+            "survey report for the northern parcel",
+            "survey report for the northern parcels",
+            "completely unrelated text",
+        ];
+        let calls = std::sync::Mutex::new(Vec::new());
+
+        let parallel = find_duplicates_parallel_with_progress(&docs, 0.5, 4, |done, total| {
+            calls.lock().unwrap().push((done, total));
+        });
+
+        assert_eq!(parallel, find_duplicates(&docs, 0.5));
+        let calls = calls.into_inner().unwrap();
+        assert_eq!(calls.len(), 15 / 4 + 1);
+        assert_eq!(calls.last(), Some(&(15, 15)));
+    }
+
     #[test]
     fn test_transfer_hash_deterministic() {
         let req = TransferRequest {
@@ -1027,6 +3238,7 @@ mod tests {
             to_owner: "Bob".to_string(),
             transfer_date: "2025-01-01".to_string(),
             transfer_reference: "REF-1".to_string(),
+            expected_sequence: None,
         };
 
         let h1 = compute_transfer_hash(&req);
@@ -1043,6 +3255,7 @@ mod tests {
             to_owner: "Bob".to_string(),
             transfer_date: "2025-01-01".to_string(),
             transfer_reference: "REF-1".to_string(),
+            expected_sequence: None,
         };
 
         let mut modified = base.clone();
@@ -1054,6 +3267,58 @@ mod tests {
         assert_ne!(h1, h2);
     }
 
+    #[test]
+    fn test_transfer_hash_field_boundaries_are_unambiguous() {
+        let a = TransferRequest {
+            document_hash: "doc123".to_string(),
+            from_owner: "AB".to_string(),
+            to_owner: "C".to_string(),
+            transfer_date: "2025-01-01".to_string(),
+            transfer_reference: "REF-1".to_string(),
+            expected_sequence: None,
+        };
+        let mut b = a.clone();
+        b.from_owner = "A".to_string();
+        b.to_owner = "BC".to_string();
+
+        // The legacy scheme collides on shifted field boundaries.
+        assert_eq!(
+            compute_legacy_transfer_hash(&a),
+            compute_legacy_transfer_hash(&b)
+        );
+        assert_ne!(compute_transfer_hash(&a), compute_transfer_hash(&b));
+    }
+
+    #[test]
+    fn test_verify_transfer_record_uses_stored_scheme() {
+        let req = TransferRequest {
+            document_hash: "doc123".to_string(),
+            from_owner: "Alice".to_string(),
+            to_owner: "Bob".to_string(),
+            transfer_date: "2025-01-01".to_string(),
+            transfer_reference: "REF-1".to_string(),
+            expected_sequence: None,
+        };
+        let legacy_json = serde_json::json!({
+            "document_hash": req.document_hash,
+            "from_owner": req.from_owner,
+            "to_owner": req.to_owner,
+            "transfer_date": req.transfer_date,
+            "transfer_reference": req.transfer_reference,
+            "transfer_hash": compute_legacy_transfer_hash(&req),
+            "memo": "TRANSFER:x",
+            "anchored_at": "2025-01-01T00:00:00Z"
+        });
+        let mut record: TransferRecord = serde_json::from_value(legacy_json).unwrap();
+        assert_eq!(record.hash_version, 1);
+        assert!(verify_transfer_record(&record));
+
+        record.hash_version = TRANSFER_HASH_VERSION;
+        assert!(!verify_transfer_record(&record));
+        record.transfer_hash = compute_transfer_hash(&req);
+        assert!(verify_transfer_record(&record));
+    }
+
     #[test]
     fn test_iso8601_date_validation() {
         assert!(is_valid_iso8601_date("2025-12-31"));
@@ -1061,6 +3326,26 @@ mod tests {
         assert!(!is_valid_iso8601_date("not-a-date"));
     }
 
+    #[test]
+    fn test_is_fresh_respects_max_age() {
+        let cached = VerifyResponse {
+            verified: true,
+            transaction_id: None,
+            timestamp: None,
+            cached: false,
+            revoked: false,
+            revoked_at: None,
+            revoked_transaction_id: None,
+            last_checked_at: 1_000,
+            evidence: None,
+            error: None,
+        };
+
+        assert!(is_fresh(&cached, None, 50_000));
+        assert!(is_fresh(&cached, Some(60), 1_060));
+        assert!(!is_fresh(&cached, Some(60), 1_061));
+    }
+
     #[test]
     fn test_batch_verify_request_validation() {
         // Test empty batch
@@ -1,18 +1,39 @@
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use std::time::Duration;
 use stellar_doc_verifier::app;
-use stellar_doc_verifier::cache::{CacheBackend, RedisCache};
-use stellar_doc_verifier::config::AppConfig;
+use stellar_doc_verifier::cache::{CacheBackend, FallbackCache, InMemoryCache};
+use stellar_doc_verifier::config::{format_issue_table, AppConfig};
+use stellar_doc_verifier::event::EventStore;
 use stellar_doc_verifier::metrics::MetricsRegistry;
+use stellar_doc_verifier::public::PublicRecentFeed;
+use stellar_doc_verifier::secrets::Secret;
+use stellar_doc_verifier::self_check::run_self_check;
+use stellar_doc_verifier::server::{self, BoundListener};
+use stellar_doc_verifier::singleflight::SingleFlight;
 use stellar_doc_verifier::stellar::StellarClient;
+use stellar_doc_verifier::webhook::WebhookDispatcher;
+use stellar_doc_verifier::write_behind::WriteBehindQueue;
 use stellar_doc_verifier::*;
-use tokio::net::TcpListener;
-use tracing::info;
+use tokio::sync::watch;
+use tracing::{info, warn};
 use tracing_subscriber::EnvFilter;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Load configuration
-    let config = AppConfig::from_env()?;
+    // Load configuration; warnings are printed but only errors are fatal.
+    let config = match AppConfig::from_env() {
+        Ok(config) => {
+            if !config.warnings.is_empty() {
+                eprintln!("{}", format_issue_table(&config.warnings));
+            }
+            config
+        }
+        Err(e) => {
+            eprintln!("{}", format_issue_table(e.issues()));
+            return Err(e.into());
+        }
+    };
 
     // Initialize tracing
     let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| {
@@ -28,7 +49,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Startup configuration summary (redacting secrets)
     info!(
-        "Configuration: port={}, stellar_horizon_url={}, redis_url={}, rate_limit_per_second={}, rate_limit_burst={}, stellar_max_retries={}, log_level={}, webhook_urls={:?}, stellar_secret_key=[REDACTED], webhook_secret=[REDACTED], cache_verification_ttl={}",
+        "Configuration: port={}, stellar_horizon_url={}, redis_url={}, rate_limit_per_second={}, rate_limit_burst={}, stellar_max_retries={}, log_level={}, webhook_urls={:?}, stellar_secret_key=[REDACTED], webhook_secret=[REDACTED], admin_token=[REDACTED], cache_verification_ttl={}, cache_negative_ttl={}, cache_max_entries={}, cache_fallback_cooldown_secs={}, request_timeout_secs={}, shutdown_drain_secs={}, shutdown_prestop_secs={}, anchor_method={:?}, stellar_adaptive_timeout_multiplier={:?}, batch_concurrency={}",
         config.port,
         config.stellar_horizon_url,
         config.redis_url,
@@ -38,30 +59,155 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         config.log_level,
         config.webhook_urls,
         config.cache_verification_ttl,
+        config.cache_negative_ttl,
+        config.cache_max_entries,
+        config.cache_fallback_cooldown_secs,
+        config.request_timeout_secs,
+        config.shutdown_drain_secs,
+        config.shutdown_prestop_secs,
+        config.anchor_method,
+        config.stellar_adaptive_timeout_multiplier,
+        config.batch_concurrency,
     );
 
     // Initialize components
     let stellar_url = config.stellar_horizon_url.clone();
     let redis_url = config.redis_url.clone();
 
-    let stellar = Arc::new(StellarClient::new(&stellar_url));
-    let cache = Arc::new(CacheBackend::Redis(RedisCache::new(&redis_url).await?));
+    let mut stellar = StellarClient::new(&stellar_url).with_anchor_method(config.anchor_method);
+    if let Some(multiplier) = config.stellar_adaptive_timeout_multiplier {
+        stellar = stellar.with_adaptive_timeout(multiplier);
+    }
+    let stellar = Arc::new(stellar);
+    let cache = Arc::new(CacheBackend::Layered(
+        FallbackCache::connect(
+            &redis_url,
+            InMemoryCache::with_capacity(config.cache_max_entries),
+            Duration::from_secs(config.cache_fallback_cooldown_secs),
+        )
+        .await,
+    ));
     let metrics = Arc::new(MetricsRegistry::new());
+    let write_behind = Arc::new(WriteBehindQueue::new(
+        config.cache_write_behind_capacity,
+        metrics.clone(),
+    ));
+
+    // Refuse to start on a misconfigured deployment
+    if config.skip_self_check {
+        info!("Startup self-check skipped (SKIP_SELF_CHECK=true)");
+    } else {
+        let report = run_self_check(
+            &stellar,
+            &cache,
+            config.stellar_secret_key.as_ref().map(Secret::expose),
+            config.webhook_secret.as_ref().map(Secret::expose),
+            config.self_check_min_balance_xlm,
+        )
+        .await;
+        if !report.passed() {
+            eprintln!("{}", report);
+            return Err("startup self-check failed".into());
+        }
+        info!("{}", report);
+    }
+
+    // Bind every listener before serving so a bad address fails fast
+    #[allow(unused_mut)]
+    let mut listeners = server::bind_tcp(&config.listen_addrs).await?;
+    #[cfg(unix)]
+    if let Some(path) = &config.listen_uds {
+        listeners.push(server::bind_unix(path, config.listen_uds_mode)?);
+    }
+    let listener_names = listeners.iter().map(BoundListener::describe).collect();
+    let webhook_secret = config
+        .webhook_secret
+        .as_ref()
+        .map(|secret| secret.expose().to_string());
 
+    let draining = Arc::new(AtomicBool::new(false));
     let state = AppState {
         stellar,
-        cache,
+        cache: cache.clone(),
         metrics,
-        stellar_secret_key: config.stellar_secret_key.clone().unwrap_or_default(),
+        stellar_secret_key: config
+            .stellar_secret_key
+            .as_ref()
+            .map(|key| key.expose().to_string())
+            .unwrap_or_default(),
+        webhook_secret: webhook_secret.clone(),
+        webhooks: Arc::new(WebhookDispatcher::new(
+            config.webhook_urls.clone(),
+            webhook_secret,
+        )),
+        listeners: Arc::new(listener_names),
+        write_behind: write_behind.clone(),
+        events: Arc::new(EventStore::new(cache.clone())),
+        accepted_algorithms: Arc::new(config.accepted_hash_algorithms.clone()),
+        request_timeout: Duration::from_secs(config.request_timeout_secs),
+        draining: draining.clone(),
+        public_recent: config.public_recent_enabled.then(|| {
+            Arc::new(PublicRecentFeed::new(
+                config.public_recent_rate_limit_per_second,
+            ))
+        }),
+        batch_concurrency: config.batch_concurrency,
+        verify_flights: Arc::new(SingleFlight::new()),
+        cache_ttl: config.cache_verification_ttl,
+        cache_negative_ttl: config.cache_negative_ttl,
+        admin_token: config
+            .admin_token
+            .as_ref()
+            .map(|token| token.expose().to_string()),
+        time: config.time,
     };
 
     let app = app(state);
 
     // Start server
-    let addr = format!("0.0.0.0:{}", config.port);
-    info!("Listening on {}", addr);
-    let listener = TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let prestop = Duration::from_secs(config.shutdown_prestop_secs);
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        info!(
+            "Shutdown signal received; draining after {}s pre-stop",
+            prestop.as_secs()
+        );
+        server::begin_shutdown(&draining, prestop, &shutdown_tx).await;
+    });
+    let write_behind_task = tokio::spawn(write_behind.run(cache, shutdown_rx.clone()));
+    let drain = Duration::from_secs(config.shutdown_drain_secs);
+    if !server::serve_all_with_drain(app, listeners, shutdown_rx, drain).await? {
+        warn!(
+            "Requests still in flight after {}s drain period; exiting anyway",
+            config.shutdown_drain_secs
+        );
+    }
+    // Listeners have drained; flush deferred cache writes before exiting.
+    write_behind_task.await?;
 
     Ok(())
 }
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
@@ -1,5 +1,21 @@
 use axum::response::IntoResponse;
-use prometheus::{Counter, Encoder, Registry, TextEncoder};
+use prometheus::{
+    Counter, Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts,
+    Registry, TextEncoder,
+};
+
+const DAY_SECS: i64 = 60 * 60 * 24;
+
+/// Bucket label for the age of a verified document's anchor.
+pub fn age_bucket(age_secs: i64) -> &'static str {
+    match age_secs {
+        a if a < DAY_SECS => "lt_1d",
+        a if a < 7 * DAY_SECS => "1d_7d",
+        a if a < 30 * DAY_SECS => "7d_30d",
+        a if a < 365 * DAY_SECS => "30d_365d",
+        _ => "gt_365d",
+    }
+}
 
 pub struct MetricsRegistry {
     registry: Registry,
@@ -7,6 +23,22 @@ pub struct MetricsRegistry {
     cache_hits: Counter,
     cache_misses: Counter,
     error_count: Counter,
+    verifications_by_age: IntCounterVec,
+    document_age_days: Histogram,
+    write_behind_dropped: IntCounterVec,
+    validation_failures: IntCounterVec,
+    batch_inflight: IntGauge,
+    in_memory_cache_entries: IntGauge,
+    in_memory_cache_evictions: IntCounter,
+}
+
+/// Holds one `batch_inflight_stellar_queries` slot until dropped.
+pub struct InflightGuard(IntGauge);
+
+impl Drop for InflightGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
 }
 
 impl Default for MetricsRegistry {
@@ -22,11 +54,79 @@ impl MetricsRegistry {
         let cache_hits = Counter::new("cache_hits_total", "Total cache hits").unwrap();
         let cache_misses = Counter::new("cache_misses_total", "Total cache misses").unwrap();
         let error_count = Counter::new("errors_total", "Total errors").unwrap();
+        let verifications_by_age = IntCounterVec::new(
+            Opts::new(
+                "verifications_by_age_total",
+                "Successful verifications by anchored document age",
+            ),
+            &["bucket"],
+        )
+        .unwrap();
+        let document_age_days = Histogram::with_opts(
+            HistogramOpts::new(
+                "verification_document_age_days",
+                "Age in days of successfully verified documents",
+            )
+            .buckets(vec![1.0, 7.0, 30.0, 90.0, 365.0, 730.0, 1825.0]),
+        )
+        .unwrap();
+        let write_behind_dropped = IntCounterVec::new(
+            Opts::new(
+                "cache_write_behind_dropped_total",
+                "Deferred cache writes dropped before reaching the cache",
+            ),
+            &["reason"],
+        )
+        .unwrap();
+        let validation_failures = IntCounterVec::new(
+            Opts::new(
+                "hash_validation_failures_total",
+                "Submitted hashes rejected by validation",
+            ),
+            &["kind"],
+        )
+        .unwrap();
+
+        let batch_inflight = IntGauge::new(
+            "batch_inflight_stellar_queries",
+            "Batch verification lookups currently in flight",
+        )
+        .unwrap();
 
         registry.register(Box::new(request_count.clone())).unwrap();
         registry.register(Box::new(cache_hits.clone())).unwrap();
         registry.register(Box::new(cache_misses.clone())).unwrap();
         registry.register(Box::new(error_count.clone())).unwrap();
+        registry
+            .register(Box::new(verifications_by_age.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(document_age_days.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(write_behind_dropped.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(validation_failures.clone()))
+            .unwrap();
+        let in_memory_cache_entries = IntGauge::new(
+            "in_memory_cache_entries",
+            "Entries held by the in-memory cache",
+        )
+        .unwrap();
+        let in_memory_cache_evictions = IntCounter::new(
+            "in_memory_cache_evictions_total",
+            "In-memory cache entries evicted to stay within CACHE_MAX_ENTRIES",
+        )
+        .unwrap();
+
+        registry.register(Box::new(batch_inflight.clone())).unwrap();
+        registry
+            .register(Box::new(in_memory_cache_entries.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(in_memory_cache_evictions.clone()))
+            .unwrap();
 
         Self {
             registry,
@@ -34,6 +134,13 @@ impl MetricsRegistry {
             cache_hits,
             cache_misses,
             error_count,
+            verifications_by_age,
+            document_age_days,
+            write_behind_dropped,
+            validation_failures,
+            batch_inflight,
+            in_memory_cache_entries,
+            in_memory_cache_evictions,
         }
     }
 
@@ -53,6 +160,53 @@ impl MetricsRegistry {
         self.error_count.inc();
     }
 
+    /// Record the age of a successfully verified document's anchor.
+    pub fn observe_document_age(&self, age_secs: i64) {
+        let age_secs = age_secs.max(0);
+        self.verifications_by_age
+            .with_label_values(&[age_bucket(age_secs)])
+            .inc();
+        self.document_age_days
+            .observe(age_secs as f64 / DAY_SECS as f64);
+    }
+
+    /// Record a deferred cache write dropped for `reason` (`overflow` or `expired`).
+    pub fn increment_write_behind_dropped(&self, reason: &str) {
+        self.write_behind_dropped.with_label_values(&[reason]).inc();
+    }
+
+    pub fn write_behind_dropped(&self, reason: &str) -> u64 {
+        self.write_behind_dropped.with_label_values(&[reason]).get()
+    }
+
+    /// Record a hash rejected by validation, labeled by failure kind.
+    pub fn increment_validation_failure(&self, kind: &str) {
+        self.validation_failures.with_label_values(&[kind]).inc();
+    }
+
+    pub fn validation_failures(&self, kind: &str) -> u64 {
+        self.validation_failures.with_label_values(&[kind]).get()
+    }
+
+    /// Count one batch lookup as in flight until the guard is dropped.
+    pub fn batch_inflight_query(&self) -> InflightGuard {
+        self.batch_inflight.inc();
+        InflightGuard(self.batch_inflight.clone())
+    }
+
+    pub fn batch_inflight_queries(&self) -> i64 {
+        self.batch_inflight.get()
+    }
+
+    /// Publish the in-memory cache's size and its eviction total so far.
+    pub fn observe_in_memory_cache(&self, entries: usize, evictions: u64) {
+        self.in_memory_cache_entries.set(entries as i64);
+        let recorded = self.in_memory_cache_evictions.get();
+        if evictions > recorded {
+            self.in_memory_cache_evictions.inc_by(evictions - recorded);
+        }
+    }
+
     pub fn render(&self) -> impl IntoResponse {
         let encoder = TextEncoder::new();
         let metric_families = self.registry.gather();
@@ -63,3 +217,54 @@ impl MetricsRegistry {
         String::from_utf8(buffer).unwrap_or_default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn age_bucket_boundaries() {
+        assert_eq!(age_bucket(0), "lt_1d");
+        assert_eq!(age_bucket(DAY_SECS - 1), "lt_1d");
+        assert_eq!(age_bucket(DAY_SECS), "1d_7d");
+        assert_eq!(age_bucket(7 * DAY_SECS - 1), "1d_7d");
+        assert_eq!(age_bucket(7 * DAY_SECS), "7d_30d");
+        assert_eq!(age_bucket(30 * DAY_SECS - 1), "7d_30d");
+        assert_eq!(age_bucket(30 * DAY_SECS), "30d_365d");
+        assert_eq!(age_bucket(365 * DAY_SECS - 1), "30d_365d");
+        assert_eq!(age_bucket(365 * DAY_SECS), "gt_365d");
+    }
+
+    #[test]
+    fn observe_document_age_increments_labeled_counter() {
+        let metrics = MetricsRegistry::new();
+        metrics.observe_document_age(3 * DAY_SECS);
+        metrics.observe_document_age(-5);
+
+        assert_eq!(
+            metrics
+                .verifications_by_age
+                .with_label_values(&["1d_7d"])
+                .get(),
+            1
+        );
+        assert_eq!(
+            metrics
+                .verifications_by_age
+                .with_label_values(&["lt_1d"])
+                .get(),
+            1
+        );
+        assert_eq!(metrics.document_age_days.get_sample_count(), 2);
+    }
+
+    #[test]
+    fn observe_in_memory_cache_tracks_eviction_total() {
+        let metrics = MetricsRegistry::new();
+        metrics.observe_in_memory_cache(20, 3);
+        metrics.observe_in_memory_cache(20, 7);
+
+        assert_eq!(metrics.in_memory_cache_entries.get(), 20);
+        assert_eq!(metrics.in_memory_cache_evictions.get(), 7);
+    }
+}
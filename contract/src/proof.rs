@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+use crate::canonical::canonical_json;
+use crate::config::TimeConfig;
+use crate::webhook::{sign_payload, verify_signature};
+
+/// Self-contained attestation that a document hash was anchored, signed by
+/// this service so a third party can check it offline.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VerificationProof {
+    pub hash: String,
+    pub transaction_id: Option<String>,
+    pub ledger: Option<u32>,
+    pub timestamp: Option<i64>,
+    pub horizon_url: String,
+    pub network: String,
+    pub service_signature: String,
+}
+
+/// The signed portion of a proof, encoded as canonical JSON.
+#[derive(Serialize)]
+struct ProofFields<'a> {
+    hash: &'a str,
+    transaction_id: Option<&'a str>,
+    ledger: Option<u32>,
+    timestamp: Option<i64>,
+    horizon_url: &'a str,
+    network: &'a str,
+}
+
+impl VerificationProof {
+    /// Build a proof and sign it with `secret`.
+    pub fn new_signed(
+        hash: String,
+        transaction_id: Option<String>,
+        ledger: Option<u32>,
+        timestamp: Option<i64>,
+        horizon_url: String,
+        network: String,
+        secret: &str,
+    ) -> Self {
+        let mut proof = Self {
+            hash,
+            transaction_id,
+            ledger,
+            timestamp,
+            horizon_url,
+            network,
+            service_signature: String::new(),
+        };
+        proof.service_signature = sign_payload(secret, &proof.signing_payload());
+        proof
+    }
+
+    /// Bytes covered by `service_signature`.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        let fields = ProofFields {
+            hash: &self.hash,
+            transaction_id: self.transaction_id.as_deref(),
+            ledger: self.ledger,
+            timestamp: self.timestamp,
+            horizon_url: &self.horizon_url,
+            network: &self.network,
+        };
+        canonical_json(&fields).into_bytes()
+    }
+
+    /// Returns `true` if the signature matches the proof fields.
+    pub fn verify(&self, secret: &str) -> bool {
+        verify_signature(secret, &self.signing_payload(), &self.service_signature)
+    }
+
+    /// Checks the signature and that the anchor timestamp is not in the
+    /// future beyond the configured clock skew.
+    pub fn validate(&self, secret: &str, time: &TimeConfig, now: i64) -> bool {
+        let timestamp_ok = self
+            .timestamp
+            .is_none_or(|timestamp| time.not_in_future(timestamp, now));
+        timestamp_ok && self.verify(secret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_proof() -> VerificationProof {
+        VerificationProof::new_signed(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855".to_string(),
+            Some("tx123".to_string()),
+            Some(42),
+            Some(1_700_000_000),
+            "https://horizon-testnet.stellar.org".to_string(),
+            "testnet".to_string(),
+            "secret",
+        )
+    }
+
+    #[test]
+    fn signed_proof_verifies() {
+        let proof = sample_proof();
+        assert!(proof.verify("secret"));
+        assert!(!proof.verify("wrong-secret"));
+    }
+
+    #[test]
+    fn tampered_proof_fails_verification() {
+        let mut proof = sample_proof();
+        proof.transaction_id = Some("tx999".to_string());
+        assert!(!proof.verify("secret"));
+
+        let mut proof = sample_proof();
+        proof.timestamp = Some(1_700_000_001);
+        assert!(!proof.verify("secret"));
+    }
+
+    #[test]
+    fn proof_survives_json_round_trip() {
+        let proof = sample_proof();
+        let json = serde_json::to_string(&proof).unwrap();
+        let parsed: VerificationProof = serde_json::from_str(&json).unwrap();
+        assert!(parsed.verify("secret"));
+    }
+
+    #[test]
+    fn validate_tolerates_clock_skew_on_timestamp() {
+        let proof = sample_proof();
+        let time = TimeConfig::default();
+        let anchored_at = proof.timestamp.unwrap();
+
+        assert!(proof.validate("secret", &time, anchored_at - 300));
+        assert!(!proof.validate("secret", &time, anchored_at - 301));
+        assert!(!proof.validate("wrong-secret", &time, anchored_at));
+    }
+}
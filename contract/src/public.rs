@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+use crate::event::Event;
+use crate::rate_limit::{build_rate_limiter, DefaultRateLimiter};
+
+/// Event type recorded when a hash is anchored; feeds `/public/recent`.
+pub const ANCHORED_EVENT: &str = "Anchored";
+
+/// Settings and rate limiter for `GET /public/recent`.
+///
+/// The public portal is unauthenticated, so it gets its own limiter rather
+/// than sharing the API budget.
+pub struct PublicRecentFeed {
+    limiter: DefaultRateLimiter,
+}
+
+impl PublicRecentFeed {
+    pub fn new(per_second: u32) -> Self {
+        Self {
+            limiter: build_rate_limiter(per_second, per_second),
+        }
+    }
+
+    /// Returns `false` when the caller should be told to back off.
+    pub fn try_acquire(&self) -> bool {
+        self.limiter.check().is_ok()
+    }
+}
+
+/// Keep only the first 8 and last 4 hex characters, e.g. `e3b0c442...b855`,
+/// so the portal can show anchors without listing enumerable hashes.
+pub fn truncate_hash(hash: &str) -> String {
+    if hash.len() <= 12 || !hash.is_ascii() {
+        return "...".to_string();
+    }
+    format!("{}...{}", &hash[..8], &hash[hash.len() - 4..])
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RecentAnchor {
+    /// Truncated document hash, see [`truncate_hash`].
+    pub hash: String,
+    pub anchored_at: Option<i64>,
+    pub ledger: Option<u32>,
+}
+
+impl RecentAnchor {
+    pub fn from_event(event: &Event) -> Self {
+        Self {
+            hash: truncate_hash(&event.aggregate_id),
+            anchored_at: event.data.get("anchored_at").and_then(|v| v.as_i64()),
+            ledger: event
+                .data
+                .get("ledger")
+                .and_then(|v| v.as_u64())
+                .and_then(|v| u32::try_from(v).ok()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecentAnchorsResponse {
+    /// Newest first.
+    pub anchors: Vec<RecentAnchor>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_hash_keeps_prefix_and_suffix() {
+        let hash = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        assert_eq!(truncate_hash(hash), "e3b0c442...b855");
+        assert_eq!(truncate_hash("abc"), "...");
+    }
+}
@@ -0,0 +1,121 @@
+use std::env;
+use std::fmt;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// A secret value that never prints its contents.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[REDACTED]")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[REDACTED]")
+    }
+}
+
+/// Errors never include the secret value itself.
+#[derive(Debug, Error)]
+pub enum SecretError {
+    #[error("{name} and {name}_FILE are both set; use only one")]
+    Conflict { name: String },
+    #[error("{name}_FILE points to {path:?}, which could not be read: {source}")]
+    Unreadable {
+        name: String,
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{name}_FILE points to {path:?}, which is empty")]
+    EmptyFile { name: String, path: PathBuf },
+    #[error("{name} could not be loaded from {provider}: {message}")]
+    Provider {
+        name: String,
+        provider: String,
+        message: String,
+    },
+}
+
+/// Source of named secrets such as `STELLAR_SECRET_KEY`.
+///
+/// Config parsing only talks to this trait, so backends like Vault can be
+/// plugged in without changing `AppConfig`.
+pub trait SecretProvider: Send + Sync {
+    /// Human-readable provider name for error messages.
+    fn name(&self) -> &str;
+
+    /// Returns `Ok(None)` when the secret is not configured.
+    fn load(&self, name: &str) -> Result<Option<Secret>, SecretError>;
+}
+
+/// Reads `NAME` from the environment, or the trimmed contents of the file
+/// named by `NAME_FILE` (as mounted by Kubernetes secrets).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EnvSecretProvider;
+
+impl SecretProvider for EnvSecretProvider {
+    fn name(&self) -> &str {
+        "environment"
+    }
+
+    fn load(&self, name: &str) -> Result<Option<Secret>, SecretError> {
+        let direct = env::var(name).ok();
+        let file = env::var(format!("{}_FILE", name))
+            .ok()
+            .filter(|p| !p.trim().is_empty())
+            .map(PathBuf::from);
+
+        match (direct, file) {
+            (Some(_), Some(_)) => Err(SecretError::Conflict {
+                name: name.to_string(),
+            }),
+            (Some(value), None) => Ok(Some(Secret::new(value))),
+            (None, Some(path)) => {
+                let contents =
+                    std::fs::read_to_string(&path).map_err(|source| SecretError::Unreadable {
+                        name: name.to_string(),
+                        path: path.clone(),
+                        source,
+                    })?;
+                let value = contents.trim();
+                if value.is_empty() {
+                    return Err(SecretError::EmptyFile {
+                        name: name.to_string(),
+                        path,
+                    });
+                }
+                Ok(Some(Secret::new(value)))
+            }
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_formatting_is_redacted() {
+        let secret = Secret::new("SUPERSECRETVALUE");
+        assert_eq!(format!("{:?}", secret), "[REDACTED]");
+        assert_eq!(secret.to_string(), "[REDACTED]");
+        assert_eq!(secret.expose(), "SUPERSECRETVALUE");
+    }
+}
@@ -0,0 +1,220 @@
+use crate::cache::CacheBackend;
+use crate::stellar::{derive_account_id, StellarClient, StellarError};
+use crate::webhook::{sign_payload, verify_signature};
+use chrono::Utc;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => write!(f, "PASS"),
+            Self::Warn => write!(f, "WARN"),
+            Self::Fail => write!(f, "FAIL"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl CheckResult {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Outcome of the startup self-check. Warnings are reported but only
+/// failures prevent the service from starting.
+#[derive(Debug, Clone, Default)]
+pub struct SelfCheckReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl SelfCheckReport {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.status != CheckStatus::Fail)
+    }
+
+    pub fn check(&self, name: &str) -> Option<&CheckResult> {
+        self.checks.iter().find(|c| c.name == name)
+    }
+}
+
+impl fmt::Display for SelfCheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "startup self-check:")?;
+        for check in &self.checks {
+            writeln!(
+                f,
+                "  [{}] {:<16} {}",
+                check.status, check.name, check.detail
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Verify signing, Horizon account, cache and webhook configuration before
+/// the service accepts traffic.
+pub async fn run_self_check(
+    stellar: &StellarClient,
+    cache: &CacheBackend,
+    secret_key: Option<&str>,
+    webhook_secret: Option<&str>,
+    min_balance_xlm: f64,
+) -> SelfCheckReport {
+    let mut report = SelfCheckReport::default();
+
+    let account_id = match secret_key.map(derive_account_id) {
+        Some(Ok(id)) => {
+            report.checks.push(CheckResult::new(
+                "signing_key",
+                CheckStatus::Pass,
+                format!("anchoring account {}", id),
+            ));
+            Some(id)
+        }
+        Some(Err(e)) => {
+            report.checks.push(CheckResult::new(
+                "signing_key",
+                CheckStatus::Fail,
+                e.to_string(),
+            ));
+            None
+        }
+        None => {
+            report.checks.push(CheckResult::new(
+                "signing_key",
+                CheckStatus::Fail,
+                "STELLAR_SECRET_KEY is not set",
+            ));
+            None
+        }
+    };
+
+    if let Some(account_id) = account_id {
+        report
+            .checks
+            .push(check_account(stellar, &account_id, min_balance_xlm).await);
+    }
+
+    report.checks.push(check_cache(cache).await);
+    report.checks.push(check_webhook_signing(webhook_secret));
+    report
+}
+
+async fn check_account(
+    stellar: &StellarClient,
+    account_id: &str,
+    min_balance_xlm: f64,
+) -> CheckResult {
+    let info = match stellar.account_info(account_id).await {
+        Ok(info) => info,
+        Err(StellarError::AccountNotFound(_)) => {
+            return CheckResult::new(
+                "horizon_account",
+                CheckStatus::Fail,
+                format!(
+                    "account {} does not exist on {}",
+                    account_id,
+                    stellar.horizon_url()
+                ),
+            )
+        }
+        Err(e) => return CheckResult::new("horizon_account", CheckStatus::Fail, e.to_string()),
+    };
+
+    match info.check_can_anchor(account_id) {
+        Err(e @ StellarError::SignerMismatch { .. }) => {
+            CheckResult::new("horizon_account", CheckStatus::Fail, e.to_string())
+        }
+        Err(e) => CheckResult::new("horizon_account", CheckStatus::Warn, e.to_string()),
+        Ok(()) if info.balance_xlm < min_balance_xlm => CheckResult::new(
+            "horizon_account",
+            CheckStatus::Warn,
+            format!(
+                "balance {} XLM is below the {} XLM minimum",
+                info.balance_xlm, min_balance_xlm
+            ),
+        ),
+        Ok(()) => CheckResult::new(
+            "horizon_account",
+            CheckStatus::Pass,
+            format!("balance {} XLM", info.balance_xlm),
+        ),
+    }
+}
+
+async fn check_cache(cache: &CacheBackend) -> CheckResult {
+    // The layered cache accepts writes into memory with Redis down, so the
+    // round-trip below would not notice a bad REDIS_URL.
+    if matches!(cache, CacheBackend::Layered(_)) && !cache.check_connection().await {
+        return CheckResult::new(
+            "cache",
+            CheckStatus::Warn,
+            "Redis is unreachable; serving the cache from memory",
+        );
+    }
+    let key = format!(
+        "selfcheck:probe:{}",
+        Utc::now().timestamp_nanos_opt().unwrap_or(0)
+    );
+    let value = "ok";
+
+    if let Err(e) = cache.set_raw(&key, value, 60).await {
+        return CheckResult::new("cache", CheckStatus::Fail, format!("set failed: {}", e));
+    }
+    let read_back = match cache.get_raw(&key).await {
+        Ok(v) => v,
+        Err(e) => {
+            return CheckResult::new("cache", CheckStatus::Fail, format!("get failed: {}", e))
+        }
+    };
+    if let Err(e) = cache.delete(&key).await {
+        return CheckResult::new("cache", CheckStatus::Fail, format!("delete failed: {}", e));
+    }
+    if read_back.as_deref() != Some(value) {
+        return CheckResult::new("cache", CheckStatus::Fail, "probe value did not round-trip");
+    }
+    CheckResult::new("cache", CheckStatus::Pass, "set/get/delete round-trip ok")
+}
+
+fn check_webhook_signing(webhook_secret: Option<&str>) -> CheckResult {
+    let Some(secret) = webhook_secret else {
+        return CheckResult::new(
+            "webhook_signing",
+            CheckStatus::Warn,
+            "WEBHOOK_SECRET is not set; webhooks are unsigned and proofs are disabled",
+        );
+    };
+    let payload = b"self-check";
+    let signature = sign_payload(secret, payload);
+    if verify_signature(secret, payload, &signature) {
+        CheckResult::new(
+            "webhook_signing",
+            CheckStatus::Pass,
+            "test payload verified",
+        )
+    } else {
+        CheckResult::new(
+            "webhook_signing",
+            CheckStatus::Fail,
+            "test payload signature did not verify",
+        )
+    }
+}
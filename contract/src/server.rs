@@ -0,0 +1,229 @@
+use axum::Router;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto::Builder;
+use hyper_util::service::TowerToHyperService;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+#[cfg(unix)]
+use std::path::PathBuf;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+#[cfg(unix)]
+use tokio::task::JoinSet;
+
+/// A bound socket the router is served on.
+pub enum BoundListener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener, PathBuf),
+}
+
+impl BoundListener {
+    /// Human-readable address, e.g. `tcp://[::1]:8080` or `unix:///run/app.sock`.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Tcp(listener) => match listener.local_addr() {
+                Ok(addr) => format!("tcp://{}", addr),
+                Err(_) => "tcp://unknown".to_string(),
+            },
+            #[cfg(unix)]
+            Self::Unix(_, path) => format!("unix://{}", path.display()),
+        }
+    }
+}
+
+/// Bind one TCP listener per configured address (IPv4 or IPv6).
+pub async fn bind_tcp(addrs: &[SocketAddr]) -> io::Result<Vec<BoundListener>> {
+    let mut listeners = Vec::with_capacity(addrs.len());
+    for addr in addrs {
+        listeners.push(BoundListener::Tcp(TcpListener::bind(addr).await?));
+    }
+    Ok(listeners)
+}
+
+/// Bind a Unix domain socket at `path` with the given permission bits,
+/// replacing a stale socket file left by a previous run. Anything else at
+/// `path` is left alone and reported as an error.
+///
+/// The mode is applied through the umask while binding, so the socket is
+/// never reachable with looser permissions. The umask is process-wide; bind
+/// before starting anything else that creates files.
+#[cfg(unix)]
+pub fn bind_unix(path: &std::path::Path, mode: u32) -> io::Result<BoundListener> {
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_socket() => std::fs::remove_file(path)?,
+        Ok(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} exists and is not a socket", path.display()),
+            ))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+
+    let mask = !(mode as libc::mode_t) & 0o777;
+    // SAFETY: umask only swaps the process file-creation mask.
+    let previous = unsafe { libc::umask(mask) };
+    let bound = UnixListener::bind(path);
+    // SAFETY: as above; restores the mask saved before binding.
+    unsafe { libc::umask(previous) };
+    Ok(BoundListener::Unix(bound?, path.to_path_buf()))
+}
+
+/// Serve `app` on every listener until `shutdown` flips to `true`, then
+/// drain and remove any Unix socket files.
+pub async fn serve_all(
+    app: Router,
+    listeners: Vec<BoundListener>,
+    shutdown: watch::Receiver<bool>,
+) -> io::Result<()> {
+    let tasks: Vec<_> = listeners
+        .into_iter()
+        .map(|listener| {
+            let app = app.clone();
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move { serve_one(app, listener, shutdown).await })
+        })
+        .collect();
+
+    for task in tasks {
+        task.await.map_err(io::Error::other)??;
+    }
+    Ok(())
+}
+
+/// Like [`serve_all`], but once `shutdown` flips gives in-flight requests at
+/// most `drain` to finish. Returns `false` if the drain period ran out.
+pub async fn serve_all_with_drain(
+    app: Router,
+    listeners: Vec<BoundListener>,
+    shutdown: watch::Receiver<bool>,
+    drain: Duration,
+) -> io::Result<bool> {
+    let serving = serve_all(app, listeners, shutdown.clone());
+    tokio::pin!(serving);
+
+    tokio::select! {
+        result = &mut serving => return result.map(|()| true),
+        _ = wait_for_shutdown(shutdown) => {}
+    }
+    match tokio::time::timeout(drain, serving).await {
+        Ok(result) => result.map(|()| true),
+        Err(_) => Ok(false),
+    }
+}
+
+/// Begin a graceful shutdown: mark the service `draining` so `/health`
+/// fails readiness checks, keep accepting for `prestop` while load balancers
+/// notice, then tell the listeners to stop accepting and drain.
+pub async fn begin_shutdown(
+    draining: &AtomicBool,
+    prestop: Duration,
+    shutdown: &watch::Sender<bool>,
+) {
+    draining.store(true, Ordering::SeqCst);
+    tokio::time::sleep(prestop).await;
+    let _ = shutdown.send(true);
+}
+
+async fn wait_for_shutdown(mut shutdown: watch::Receiver<bool>) {
+    while !*shutdown.borrow() {
+        if shutdown.changed().await.is_err() {
+            return;
+        }
+    }
+}
+
+async fn serve_one(
+    app: Router,
+    listener: BoundListener,
+    shutdown: watch::Receiver<bool>,
+) -> io::Result<()> {
+    info!("Listening on {}", listener.describe());
+    match listener {
+        BoundListener::Tcp(listener) => {
+            axum::serve(listener, app)
+                .with_graceful_shutdown(wait_for_shutdown(shutdown))
+                .await
+        }
+        #[cfg(unix)]
+        BoundListener::Unix(listener, path) => {
+            // Removed once every connection is done, or when the drain
+            // period runs out and this future is dropped.
+            let _socket = SocketFile(path);
+            serve_unix(app, &listener, shutdown).await
+        }
+    }
+}
+
+/// Removes the socket file when dropped.
+#[cfg(unix)]
+struct SocketFile(PathBuf);
+
+#[cfg(unix)]
+impl Drop for SocketFile {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.0) {
+            warn!("Failed to remove socket {}: {}", self.0.display(), e);
+        }
+    }
+}
+
+/// Accept connections until `shutdown`, then stop accepting, ask open
+/// connections to finish their current request and wait for them.
+/// Dropping the future aborts the remaining connections.
+#[cfg(unix)]
+async fn serve_unix(
+    app: Router,
+    listener: &UnixListener,
+    shutdown: watch::Receiver<bool>,
+) -> io::Result<()> {
+    let stop = wait_for_shutdown(shutdown.clone());
+    tokio::pin!(stop);
+    let mut connections = JoinSet::new();
+
+    loop {
+        tokio::select! {
+            _ = &mut stop => break,
+            Some(_) = connections.join_next(), if !connections.is_empty() => {}
+            accepted = listener.accept() => {
+                let (stream, _) = match accepted {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        warn!("Unix socket accept failed: {}", e);
+                        continue;
+                    }
+                };
+                let service = TowerToHyperService::new(app.clone());
+                let shutdown = shutdown.clone();
+                connections.spawn(async move {
+                    let builder = Builder::new(TokioExecutor::new());
+                    let conn = builder.serve_connection(TokioIo::new(stream), service);
+                    tokio::pin!(conn);
+                    let result = tokio::select! {
+                        result = conn.as_mut() => result,
+                        _ = wait_for_shutdown(shutdown) => {
+                            conn.as_mut().graceful_shutdown();
+                            conn.await
+                        }
+                    };
+                    if let Err(e) = result {
+                        warn!("Unix socket connection error: {}", e);
+                    }
+                });
+            }
+        }
+    }
+
+    while connections.join_next().await.is_some() {}
+    Ok(())
+}
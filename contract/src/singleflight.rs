@@ -0,0 +1,102 @@
+use futures::future::{BoxFuture, FutureExt, Shared};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+type Flights<T> = Arc<Mutex<HashMap<String, Shared<BoxFuture<'static, T>>>>>;
+
+/// Coalesces concurrent calls for the same key into one execution.
+///
+/// The first caller for a key starts the work; callers arriving while it
+/// runs wait on the same future and receive a clone of its output. The key
+/// is released as soon as the work finishes, whatever the outcome, so a
+/// failure is never handed to later callers.
+pub struct SingleFlight<T: Clone> {
+    flights: Flights<T>,
+}
+
+impl<T: Clone> Default for SingleFlight<T> {
+    fn default() -> Self {
+        Self {
+            flights: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> SingleFlight<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `work` for `key` unless a run for it is already in flight, in
+    /// which case its result is awaited instead.
+    pub async fn run<F, Fut>(&self, key: &str, work: F) -> T
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        let flight = {
+            let mut flights = self.flights.lock().unwrap();
+            match flights.get(key) {
+                Some(flight) => flight.clone(),
+                None => {
+                    let release = self.flights.clone();
+                    let owned_key = key.to_string();
+                    let fut = work();
+                    let flight = async move {
+                        let output = fut.await;
+                        release.lock().unwrap().remove(&owned_key);
+                        output
+                    }
+                    .boxed()
+                    .shared();
+                    flights.insert(key.to_string(), flight.clone());
+                    flight
+                }
+            }
+        };
+        flight.await
+    }
+
+    /// Number of keys currently in flight.
+    pub fn in_flight(&self) -> usize {
+        self.flights.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn concurrent_calls_share_one_run_and_release_the_key() {
+        let flights = Arc::new(SingleFlight::<Result<u32, String>>::new());
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let calls: Vec<_> = (0..10)
+            .map(|_| {
+                let flights = flights.clone();
+                let runs = runs.clone();
+                tokio::spawn(async move {
+                    flights
+                        .run("key", || async move {
+                            runs.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            Err("horizon down".to_string())
+                        })
+                        .await
+                })
+            })
+            .collect();
+        for call in calls {
+            assert_eq!(call.await.unwrap(), Err("horizon down".to_string()));
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(flights.in_flight(), 0);
+
+        // The failure is not replayed to the next caller.
+        assert_eq!(flights.run("key", || async { Ok(7) }).await, Ok(7));
+    }
+}
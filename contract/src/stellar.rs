@@ -3,20 +3,163 @@ use base64::Engine as _;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use stellar_base::{
     account::DataValue,
     crypto::KeyPair,
+    memo::Memo,
     network::Network,
     operations::Operation,
     transaction::{Transaction, TransactionEnvelope, MIN_BASE_FEE},
     xdr::XDRSerialize,
 };
-use tracing::info;
+use thiserror::Error;
+use tracing::{info, warn};
+
+/// How long `account_info` results are reused before Horizon is asked again.
+const ACCOUNT_INFO_TTL: Duration = Duration::from_secs(10);
+
+/// Base reserve per ledger entry, in XLM.
+const BASE_RESERVE_XLM: f64 = 0.5;
+
+/// Weight of the newest sample in [`LatencyEma`].
+const LATENCY_EMA_ALPHA: f64 = 0.2;
+
+/// Adaptive Horizon timeouts never drop below this.
+const MIN_ADAPTIVE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Records per page when scanning account history (Horizon's maximum).
+const HISTORY_PAGE_SIZE: usize = 200;
+
+/// Exponential moving average of observed Horizon request durations.
+#[derive(Debug, Default)]
+pub struct LatencyEma {
+    avg_ms: Mutex<Option<f64>>,
+}
+
+impl LatencyEma {
+    pub fn record(&self, elapsed: Duration) {
+        let sample = elapsed.as_secs_f64() * 1000.0;
+        let mut avg = self.avg_ms.lock().unwrap();
+        *avg = Some(match *avg {
+            Some(prev) => prev + LATENCY_EMA_ALPHA * (sample - prev),
+            None => sample,
+        });
+    }
+
+    /// Current average in milliseconds; `0.0` before the first sample.
+    pub fn avg_ms(&self) -> f64 {
+        self.avg_ms.lock().unwrap().unwrap_or(0.0)
+    }
+}
+
+/// How `anchor_hash` records a document hash on-chain (`ANCHOR_METHOD`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnchorMethod {
+    /// A `ManageData` entry `doc_{hash}` on the anchoring account.
+    #[default]
+    ManageData,
+    /// A `MEMO_HASH` carrying the 32-byte SHA-256 digest.
+    Memo,
+}
+
+impl AnchorMethod {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "manage_data" => Some(Self::ManageData),
+            "memo" => Some(Self::Memo),
+            _ => None,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct StellarClient {
     horizon_url: String,
     http_client: reqwest::Client,
+    account_cache: Arc<Mutex<HashMap<String, (Instant, AccountInfo)>>>,
+    anchor_method: AnchorMethod,
+    latency: Arc<LatencyEma>,
+    adaptive_timeout_multiplier: Option<f64>,
+}
+
+/// Typed failures for account lookups and pre-submission checks.
+#[derive(Debug, Error)]
+pub enum StellarError {
+    #[error("account {0} does not exist")]
+    AccountNotFound(String),
+    #[error("balance {balance_xlm} XLM is below the {required_xlm} XLM needed to anchor")]
+    InsufficientBalance { balance_xlm: f64, required_xlm: f64 },
+    #[error("signer {signer} has weight {weight} but ManageData requires {required}")]
+    SignerMismatch {
+        signer: String,
+        weight: u32,
+        required: u32,
+    },
+    #[error("Horizon request failed: {0}")]
+    Horizon(String),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountThresholds {
+    pub low_threshold: u32,
+    pub med_threshold: u32,
+    pub high_threshold: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountSigner {
+    pub key: String,
+    pub weight: u32,
+    #[serde(rename = "type")]
+    pub signer_type: String,
+}
+
+/// Anchoring-relevant view of a Horizon account.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountInfo {
+    pub account_id: String,
+    pub balance_xlm: f64,
+    pub sequence: i64,
+    pub subentry_count: u32,
+    pub thresholds: AccountThresholds,
+    pub signers: Vec<AccountSigner>,
+}
+
+impl AccountInfo {
+    /// Minimum balance the account must hold after adding one more data entry.
+    pub fn required_balance_xlm(&self) -> f64 {
+        (2 + self.subentry_count + 1) as f64 * BASE_RESERVE_XLM
+    }
+
+    /// Ensure `signer` can sign a ManageData transaction (medium threshold)
+    /// and the balance covers the reserve for a new data entry.
+    pub fn check_can_anchor(&self, signer: &str) -> Result<(), StellarError> {
+        let required = self.thresholds.med_threshold.max(1);
+        let weight = self
+            .signers
+            .iter()
+            .find(|s| s.key == signer)
+            .map(|s| s.weight)
+            .unwrap_or(0);
+        if weight < required {
+            return Err(StellarError::SignerMismatch {
+                signer: signer.to_string(),
+                weight,
+                required,
+            });
+        }
+
+        let required_xlm = self.required_balance_xlm();
+        if self.balance_xlm < required_xlm {
+            return Err(StellarError::InsufficientBalance {
+                balance_xlm: self.balance_xlm,
+                required_xlm,
+            });
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -46,6 +189,65 @@ pub struct VerificationRecord {
     pub timestamp: Option<i64>,
     pub raw_value_base64: Option<String>,
     pub decoded_value: Option<String>,
+    /// Horizon transaction that wrote the data entry, when it could be found.
+    pub evidence: Option<TransactionEvidence>,
+    /// Whether a `revoked_` entry exists for this hash.
+    pub revoked: bool,
+    pub revoked_transaction_id: Option<String>,
+    pub revoked_at: Option<i64>,
+}
+
+/// Revocation state of a hash, as read from the anchoring account.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RevocationStatus {
+    pub revoked: bool,
+    pub transaction_id: Option<String>,
+    pub revoked_at: Option<i64>,
+}
+
+/// Snapshot of the Horizon transaction record matched during verification.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TransactionEvidence {
+    pub transaction_id: String,
+    pub memo: Option<String>,
+    pub memo_type: String,
+    pub ledger: u32,
+    pub created_at: String,
+    pub source_account: String,
+    pub successful: bool,
+}
+
+impl TransactionEvidence {
+    /// Returns `true` when the memo carries `hash`: either a `hash`/`return`
+    /// memo whose 32 bytes equal the digest, or a text memo equal to it.
+    pub fn memo_matches(&self, hash: &str) -> bool {
+        let memo = match self.memo.as_deref() {
+            Some(memo) => memo,
+            None => return false,
+        };
+        match self.memo_type.as_str() {
+            "hash" | "return" => base64::engine::general_purpose::STANDARD
+                .decode(memo)
+                .map(|bytes| hex::encode(bytes).eq_ignore_ascii_case(hash))
+                .unwrap_or(false),
+            "text" => memo.trim().eq_ignore_ascii_case(hash),
+            _ => false,
+        }
+    }
+}
+
+/// Outcome of [`StellarClient::verify_hash_in_transaction`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactionVerification {
+    /// The transaction anchors the hash.
+    Matched(TransactionEvidence),
+    /// The transaction exists but neither its memo nor its operations carry the hash.
+    MemoMismatch(TransactionEvidence),
+    /// The transaction carries the hash but was not submitted by the
+    /// anchoring account, so it proves nothing.
+    ForeignSource(TransactionEvidence),
+    /// Horizon has no transaction with this id.
+    NotFound,
 }
 
 /// History entry for GET /verify/:hash/history (CT-03 / CT-04 compatibility).
@@ -76,6 +278,20 @@ struct HorizonAccount {
     sequence: String,
     #[serde(default)]
     data: HashMap<String, String>,
+    #[serde(default)]
+    balances: Vec<HorizonBalance>,
+    #[serde(default)]
+    subentry_count: u32,
+    #[serde(default)]
+    thresholds: AccountThresholds,
+    #[serde(default)]
+    signers: Vec<AccountSigner>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HorizonBalance {
+    asset_type: String,
+    balance: String,
 }
 
 /// Horizon transaction submission response (subset of fields).
@@ -86,6 +302,35 @@ struct HorizonTxResponse {
     created_at: Option<String>,
 }
 
+/// Horizon transaction object (subset of fields).
+#[derive(Debug, Deserialize)]
+struct HorizonTransaction {
+    hash: String,
+    ledger: u32,
+    created_at: String,
+    source_account: String,
+    memo_type: String,
+    #[serde(default)]
+    memo: Option<String>,
+    successful: bool,
+    #[serde(default)]
+    paging_token: Option<String>,
+}
+
+impl From<HorizonTransaction> for TransactionEvidence {
+    fn from(tx: HorizonTransaction) -> Self {
+        Self {
+            transaction_id: tx.hash,
+            memo: tx.memo,
+            memo_type: tx.memo_type,
+            ledger: tx.ledger,
+            created_at: tx.created_at,
+            source_account: tx.source_account,
+            successful: tx.successful,
+        }
+    }
+}
+
 /// Horizon error envelope returned on failure.
 #[derive(Debug, Deserialize)]
 struct HorizonError {
@@ -93,24 +338,50 @@ struct HorizonError {
     title: Option<String>,
 }
 
-/// Horizon operation list response.
+/// Account history records carry the cursor for the page after them.
+trait PagedRecord: serde::de::DeserializeOwned {
+    fn paging_token(&self) -> Option<&str>;
+}
+
+impl PagedRecord for HorizonTransaction {
+    fn paging_token(&self) -> Option<&str> {
+        self.paging_token.as_deref()
+    }
+}
+
+impl PagedRecord for OperationRecord {
+    fn paging_token(&self) -> Option<&str> {
+        self.paging_token.as_deref()
+    }
+}
+
+/// Horizon list response (`_embedded.records`).
 #[derive(Debug, Deserialize)]
-struct OperationsResponse {
-    _embedded: OperationsEmbedded,
+#[serde(bound = "T: PagedRecord")]
+struct HistoryPage<T> {
+    _embedded: HistoryEmbedded<T>,
 }
 
 #[derive(Debug, Deserialize)]
-struct OperationsEmbedded {
-    records: Vec<OperationRecord>,
+#[serde(bound = "T: PagedRecord")]
+struct HistoryEmbedded<T> {
+    records: Vec<T>,
 }
 
+/// Horizon operation list response.
+type OperationsResponse = HistoryPage<OperationRecord>;
+
 #[derive(Debug, Deserialize)]
 struct OperationRecord {
     id: String,
+    #[serde(default)]
+    paging_token: Option<String>,
     transaction_hash: String,
     created_at: String,
     #[serde(rename = "type")]
     op_type: String,
+    #[serde(default)]
+    source_account: Option<String>,
     name: Option<String>,
     value: Option<String>,
 }
@@ -120,59 +391,193 @@ impl StellarClient {
         Self {
             horizon_url: horizon_url.to_string(),
             http_client: reqwest::Client::new(),
+            account_cache: Arc::new(Mutex::new(HashMap::new())),
+            anchor_method: AnchorMethod::default(),
+            latency: Arc::new(LatencyEma::default()),
+            adaptive_timeout_multiplier: None,
+        }
+    }
+
+    /// Times out each Horizon request after `multiplier` times the average
+    /// latency (at least two seconds) once a sample has been observed.
+    pub fn with_adaptive_timeout(mut self, multiplier: f64) -> Self {
+        self.adaptive_timeout_multiplier = Some(multiplier);
+        self
+    }
+
+    pub fn with_anchor_method(mut self, anchor_method: AnchorMethod) -> Self {
+        self.anchor_method = anchor_method;
+        self
+    }
+
+    pub fn anchor_method(&self) -> AnchorMethod {
+        self.anchor_method
+    }
+
+    /// Moving average of Horizon request durations, in milliseconds.
+    pub fn avg_latency_ms(&self) -> f64 {
+        self.latency.avg_ms()
+    }
+
+    /// Timeout applied to the next Horizon request, if adaptive timeouts
+    /// are enabled and a latency sample exists.
+    pub fn adaptive_timeout(&self) -> Option<Duration> {
+        let multiplier = self.adaptive_timeout_multiplier?;
+        let avg_ms = self.latency.avg_ms();
+        if avg_ms <= 0.0 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(avg_ms * multiplier / 1000.0).max(MIN_ADAPTIVE_TIMEOUT))
+    }
+
+    /// Sends a Horizon request, recording its duration in the latency EMA.
+    async fn send(&self, request: reqwest::RequestBuilder) -> reqwest::Result<reqwest::Response> {
+        let request = match self.adaptive_timeout() {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        };
+        let started = Instant::now();
+        let result = request.send().await;
+        self.latency.record(started.elapsed());
+        result
+    }
+
+    pub fn horizon_url(&self) -> &str {
+        &self.horizon_url
+    }
+
+    /// Network name implied by the Horizon URL (`testnet` or `public`).
+    pub fn network_name(&self) -> &'static str {
+        if self.horizon_url.contains("testnet") {
+            "testnet"
+        } else {
+            "public"
         }
     }
 
     pub async fn check_connection(&self) -> bool {
-        self.http_client
-            .get(&self.horizon_url)
-            .send()
+        self.send(self.http_client.get(&self.horizon_url))
             .await
             .map(|r| r.status().is_success())
             .unwrap_or(false)
     }
 
-    /// Verifies a document hash against Horizon using the `ManageData` approach.
+    /// Balance, sequence, thresholds and signers of `public_key`.
     ///
-    /// Reads `account.data_attr` for key `"doc_" + &hash[..58]`.
-    pub async fn verify_hash(
-        &self,
-        hash: &str,
-        anchor_account_id: &str,
-    ) -> Result<VerificationRecord> {
-        let account_url = format!("{}/accounts/{}", self.horizon_url, anchor_account_id);
+    /// Results are reused for a few seconds so checks before each
+    /// submission don't cost a Horizon round-trip every time.
+    pub async fn account_info(&self, public_key: &str) -> Result<AccountInfo, StellarError> {
+        if let Some((fetched_at, info)) = self.account_cache.lock().unwrap().get(public_key) {
+            if fetched_at.elapsed() < ACCOUNT_INFO_TTL {
+                return Ok(info.clone());
+            }
+        }
+
+        let account_url = format!("{}/accounts/{}", self.horizon_url, public_key);
         let resp = self
-            .http_client
-            .get(&account_url)
-            .send()
+            .send(self.http_client.get(&account_url))
             .await
-            .map_err(|e| anyhow!("Failed to fetch account info from Horizon: {}", e))?;
+            .map_err(|e| StellarError::Horizon(e.to_string()))?;
 
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(StellarError::AccountNotFound(public_key.to_string()));
+        }
         if !resp.status().is_success() {
-            let status = resp.status().as_u16();
-            return Err(anyhow!(
-                "Horizon account fetch failed with status {}",
-                status
-            ));
+            return Err(StellarError::Horizon(format!(
+                "account fetch failed with status {}",
+                resp.status().as_u16()
+            )));
         }
 
-        let account: HorizonAccount = resp.json().await?;
+        let account: HorizonAccount = resp
+            .json()
+            .await
+            .map_err(|e| StellarError::Horizon(e.to_string()))?;
+        let info = AccountInfo {
+            account_id: public_key.to_string(),
+            balance_xlm: account
+                .balances
+                .iter()
+                .find(|b| b.asset_type == "native")
+                .and_then(|b| b.balance.parse().ok())
+                .unwrap_or(0.0),
+            sequence: account
+                .sequence
+                .parse()
+                .map_err(|_| StellarError::Horizon("could not parse account sequence".into()))?,
+            subentry_count: account.subentry_count,
+            thresholds: account.thresholds,
+            signers: account.signers,
+        };
+
+        self.account_cache
+            .lock()
+            .unwrap()
+            .insert(public_key.to_string(), (Instant::now(), info.clone()));
+        Ok(info)
+    }
+
+    /// Verifies a document hash against Horizon.
+    ///
+    /// Reads `account.data_attr` for key `"doc_" + &hash[..58]`. Without
+    /// such an entry, the account's transactions are searched for a
+    /// `MEMO_HASH` carrying the hash.
+    pub async fn verify_hash(
+        &self,
+        hash: &str,
+        anchor_account_id: &str,
+    ) -> Result<VerificationRecord> {
+        let account = self.fetch_account(anchor_account_id).await?;
         let data_key = build_data_key(hash);
 
-        if let Some(b64_val) = account.data.get(&data_key) {
-            let decoded_bytes = base64::engine::general_purpose::STANDARD
-                .decode(b64_val)
-                .unwrap_or_else(|_| b64_val.as_bytes().to_vec());
-            let decoded_str = String::from_utf8_lossy(&decoded_bytes).to_string();
+        let anchor = if let Some(b64_val) = account.data.get(&data_key) {
+            // Evidence is best-effort: the data entry alone proves anchoring.
+            let evidence = match self.find_anchor_evidence(hash, anchor_account_id).await {
+                Ok(evidence) => evidence,
+                Err(e) => {
+                    warn!("Failed to capture anchor evidence for {}: {}", hash, e);
+                    None
+                }
+            };
+            Some((Some(b64_val.clone()), evidence))
+        } else {
+            // Memo anchors stay valid after switching to ManageData, so they
+            // are looked for whatever the configured method.
+            self.find_memo_evidence(hash, anchor_account_id)
+                .await?
+                .map(|evidence| (None, Some(evidence)))
+        };
+
+        if let Some((b64_val, evidence)) = anchor {
+            let decoded_str = b64_val.as_deref().map(|b64_val| {
+                let decoded_bytes = base64::engine::general_purpose::STANDARD
+                    .decode(b64_val)
+                    .unwrap_or_else(|_| b64_val.as_bytes().to_vec());
+                String::from_utf8_lossy(&decoded_bytes).to_string()
+            });
+
+            let transaction_id = evidence.as_ref().map(|e| e.transaction_id.clone());
+            let timestamp = evidence
+                .as_ref()
+                .and_then(|e| chrono::DateTime::parse_from_rfc3339(&e.created_at).ok())
+                .map(|dt| dt.timestamp());
+
+            let revocation = self
+                .revocation_from_account(&account, hash, anchor_account_id)
+                .await;
 
             Ok(VerificationRecord {
                 hash: hash.to_string(),
                 anchored: true,
                 data_key,
-                transaction_id: None,
-                timestamp: None,
-                raw_value_base64: Some(b64_val.clone()),
-                decoded_value: Some(decoded_str),
+                transaction_id,
+                timestamp,
+                raw_value_base64: b64_val,
+                decoded_value: decoded_str,
+                evidence,
+                revoked: revocation.revoked,
+                revoked_transaction_id: revocation.transaction_id,
+                revoked_at: revocation.revoked_at,
             })
         } else {
             Ok(VerificationRecord {
@@ -183,10 +588,286 @@ impl StellarClient {
                 timestamp: None,
                 raw_value_base64: None,
                 decoded_value: None,
+                evidence: None,
+                revoked: false,
+                revoked_transaction_id: None,
+                revoked_at: None,
             })
         }
     }
 
+    /// Looks up whether `hash` carries a `revoked_` entry on the anchoring
+    /// account, the same check [`Self::verify_hash`] performs.
+    pub async fn revocation_status(
+        &self,
+        hash: &str,
+        anchor_account_id: &str,
+    ) -> Result<RevocationStatus> {
+        let account = self.fetch_account(anchor_account_id).await?;
+        Ok(self
+            .revocation_from_account(&account, hash, anchor_account_id)
+            .await)
+    }
+
+    async fn fetch_account(&self, anchor_account_id: &str) -> Result<HorizonAccount> {
+        let account_url = format!("{}/accounts/{}", self.horizon_url, anchor_account_id);
+        let resp = self
+            .send(self.http_client.get(&account_url))
+            .await
+            .map_err(|e| anyhow!("Failed to fetch account info from Horizon: {}", e))?;
+
+        if !resp.status().is_success() {
+            let status = resp.status().as_u16();
+            return Err(anyhow!(
+                "Horizon account fetch failed with status {}",
+                status
+            ));
+        }
+
+        Ok(resp.json().await?)
+    }
+
+    /// Evidence for the revocation is best-effort, like anchor evidence.
+    async fn revocation_from_account(
+        &self,
+        account: &HorizonAccount,
+        hash: &str,
+        anchor_account_id: &str,
+    ) -> RevocationStatus {
+        if !account.data.contains_key(&build_revocation_key(hash)) {
+            return RevocationStatus::default();
+        }
+        let evidence = match self.find_revocation_evidence(hash, anchor_account_id).await {
+            Ok(evidence) => evidence,
+            Err(e) => {
+                warn!("Failed to capture revocation evidence for {}: {}", hash, e);
+                None
+            }
+        };
+        RevocationStatus {
+            revoked: true,
+            revoked_at: evidence
+                .as_ref()
+                .and_then(|e| chrono::DateTime::parse_from_rfc3339(&e.created_at).ok())
+                .map(|dt| dt.timestamp()),
+            transaction_id: evidence.map(|e| e.transaction_id),
+        }
+    }
+
+    /// Fetch a single transaction from Horizon as an evidence snapshot.
+    pub async fn transaction_evidence(&self, tx_hash: &str) -> Result<TransactionEvidence> {
+        self.fetch_transaction(tx_hash)
+            .await?
+            .ok_or_else(|| anyhow!("Horizon transaction fetch failed with status 404 Not Found"))
+    }
+
+    /// Check `hash` against one known transaction instead of scanning the
+    /// account. Matches a memo carrying the hash or a `ManageData` operation
+    /// writing its anchor entry, provided `anchor_account_id` submitted the
+    /// transaction.
+    pub async fn verify_hash_in_transaction(
+        &self,
+        hash: &str,
+        tx_hash: &str,
+        anchor_account_id: &str,
+    ) -> Result<TransactionVerification> {
+        match self.fetch_transaction(tx_hash).await? {
+            Some(evidence) => {
+                self.match_transaction(hash, evidence, anchor_account_id)
+                    .await
+            }
+            None => Ok(TransactionVerification::NotFound),
+        }
+    }
+
+    /// The matching half of [`Self::verify_hash_in_transaction`], for callers
+    /// that already hold the transaction record.
+    pub async fn match_transaction(
+        &self,
+        hash: &str,
+        evidence: TransactionEvidence,
+        anchor_account_id: &str,
+    ) -> Result<TransactionVerification> {
+        if !evidence.successful {
+            return Ok(TransactionVerification::MemoMismatch(evidence));
+        }
+        if evidence.source_account != anchor_account_id {
+            return Ok(TransactionVerification::ForeignSource(evidence));
+        }
+        if evidence.memo_matches(hash) {
+            return Ok(TransactionVerification::Matched(evidence));
+        }
+
+        let url = format!(
+            "{}/transactions/{}/operations",
+            self.horizon_url, evidence.transaction_id
+        );
+        let resp = self
+            .send(self.http_client.get(&url))
+            .await
+            .map_err(|e| anyhow!("Failed to fetch transaction operations: {}", e))?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "Horizon operations fetch failed with status {}",
+                resp.status()
+            ));
+        }
+
+        let data_key = build_data_key(hash);
+        let ops: OperationsResponse = resp.json().await?;
+        // An operation can carry its own source account, in which case the
+        // entry lands on that account rather than the anchoring one.
+        let writes_anchor = ops._embedded.records.iter().any(|op| {
+            op.op_type == "manage_data"
+                && op.name.as_deref() == Some(data_key.as_str())
+                && op
+                    .source_account
+                    .as_deref()
+                    .is_none_or(|source| source == anchor_account_id)
+        });
+        Ok(if writes_anchor {
+            TransactionVerification::Matched(evidence)
+        } else {
+            TransactionVerification::MemoMismatch(evidence)
+        })
+    }
+
+    /// `Ok(None)` when Horizon answers 404 for `tx_hash`.
+    async fn fetch_transaction(&self, tx_hash: &str) -> Result<Option<TransactionEvidence>> {
+        let url = format!("{}/transactions/{}", self.horizon_url, tx_hash);
+        let resp = self
+            .send(self.http_client.get(&url))
+            .await
+            .map_err(|e| anyhow!("Failed to fetch transaction from Horizon: {}", e))?;
+
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "Horizon transaction fetch failed with status {}",
+                resp.status()
+            ));
+        }
+
+        let tx: HorizonTransaction = resp.json().await?;
+        Ok(Some(tx.into()))
+    }
+
+    /// Find the most recent successful transaction on the anchoring account
+    /// whose memo carries `hash`, paging back through the account's whole
+    /// history until one is found.
+    pub async fn find_memo_evidence(
+        &self,
+        hash: &str,
+        anchor_account_id: &str,
+    ) -> Result<Option<TransactionEvidence>> {
+        self.search_account_history(
+            anchor_account_id,
+            "transactions",
+            |tx: HorizonTransaction| {
+                let evidence = TransactionEvidence::from(tx);
+                (evidence.successful && evidence.memo_matches(hash)).then_some(evidence)
+            },
+        )
+        .await
+    }
+
+    /// Walk `/accounts/{id}/{resource}` newest first, one page at a time,
+    /// until `find` picks a record or the history runs out.
+    async fn search_account_history<T, R>(
+        &self,
+        anchor_account_id: &str,
+        resource: &str,
+        mut find: impl FnMut(T) -> Option<R>,
+    ) -> Result<Option<R>>
+    where
+        T: PagedRecord,
+    {
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut url = format!(
+                "{}/accounts/{}/{}?order=desc&limit={}",
+                self.horizon_url, anchor_account_id, resource, HISTORY_PAGE_SIZE
+            );
+            if let Some(cursor) = &cursor {
+                url.push_str(&format!("&cursor={}", cursor));
+            }
+
+            let resp = self
+                .send(self.http_client.get(&url))
+                .await
+                .map_err(|e| anyhow!("Failed to fetch account {}: {}", resource, e))?;
+
+            if !resp.status().is_success() {
+                return Err(anyhow!(
+                    "Horizon {} fetch failed with status {}",
+                    resource,
+                    resp.status()
+                ));
+            }
+
+            let records = resp.json::<HistoryPage<T>>().await?._embedded.records;
+            let page_len = records.len();
+            let next = records
+                .last()
+                .and_then(|record| record.paging_token())
+                .map(str::to_string);
+            for record in records {
+                if let Some(found) = find(record) {
+                    return Ok(Some(found));
+                }
+            }
+            // A short page is the last one; an unchanged cursor would loop.
+            if page_len < HISTORY_PAGE_SIZE || next.is_none() || next == cursor {
+                return Ok(None);
+            }
+            cursor = next;
+        }
+    }
+
+    /// Locate the most recent `ManageData` operation that wrote the anchor
+    /// entry for `hash` and snapshot its transaction.
+    pub async fn find_anchor_evidence(
+        &self,
+        hash: &str,
+        anchor_account_id: &str,
+    ) -> Result<Option<TransactionEvidence>> {
+        self.find_data_entry_evidence(&build_data_key(hash), anchor_account_id)
+            .await
+    }
+
+    /// Locate the `ManageData` operation that wrote the revocation entry for
+    /// `hash` and snapshot its transaction.
+    pub async fn find_revocation_evidence(
+        &self,
+        hash: &str,
+        anchor_account_id: &str,
+    ) -> Result<Option<TransactionEvidence>> {
+        self.find_data_entry_evidence(&build_revocation_key(hash), anchor_account_id)
+            .await
+    }
+
+    async fn find_data_entry_evidence(
+        &self,
+        data_key: &str,
+        anchor_account_id: &str,
+    ) -> Result<Option<TransactionEvidence>> {
+        let matching_op = self
+            .search_account_history(anchor_account_id, "operations", |op: OperationRecord| {
+                (op.op_type == "manage_data"
+                    && op.name.as_deref() == Some(data_key)
+                    && op.value.is_some())
+                .then_some(op)
+            })
+            .await?;
+
+        match matching_op {
+            Some(op) => Ok(Some(self.transaction_evidence(&op.transaction_hash).await?)),
+            None => Ok(None),
+        }
+    }
+
     /// Fetches all ManageData history entries for a given document hash (anchors, updates, transfers).
     pub async fn get_hash_history(
         &self,
@@ -203,9 +884,7 @@ impl StellarClient {
         );
 
         let resp = self
-            .http_client
-            .get(&url)
-            .send()
+            .send(self.http_client.get(&url))
             .await
             .map_err(|e| anyhow!("Failed to fetch account operations: {}", e))?;
 
@@ -261,9 +940,7 @@ impl StellarClient {
 
         let account_url = format!("{}/accounts/{}", self.horizon_url, public_key);
         let acct_resp = self
-            .http_client
-            .get(&account_url)
-            .send()
+            .send(self.http_client.get(&account_url))
             .await
             .map_err(|e| anyhow!("Failed to fetch account info: {}", e))?;
 
@@ -318,11 +995,12 @@ impl StellarClient {
         let form_body = format!("tx={}", urlencoding::encode(&xdr_b64));
 
         let submit_resp = self
-            .http_client
-            .post(&submit_url)
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .body(form_body)
-            .send()
+            .send(
+                self.http_client
+                    .post(&submit_url)
+                    .header("Content-Type", "application/x-www-form-urlencoded")
+                    .body(form_body),
+            )
             .await
             .map_err(|e| anyhow!("Transaction submission failed: {}", e))?;
 
@@ -355,10 +1033,13 @@ impl StellarClient {
         }
     }
 
-    /// Anchor a document hash to Stellar using a `ManageData` operation.
+    /// Anchor a document hash to Stellar using the configured [`AnchorMethod`].
     ///
     /// # Key format
     /// `"doc_" + &hash[..58]` — matches NestJS `buildDataKey()`.
+    ///
+    /// With [`AnchorMethod::Memo`] the digest goes in a `MEMO_HASH` instead,
+    /// alongside a no-op `BumpSequence` since a transaction needs one operation.
     pub async fn anchor_hash(
         &self,
         hash: &str,
@@ -366,16 +1047,15 @@ impl StellarClient {
         secret_key: &str,
     ) -> Result<AnchorResult> {
         info!(
-            "Anchoring hash {} via ManageData (account: {})",
+            "Anchoring hash {} via {:?} (account: {})",
             &hash[..hash.len().min(16)],
+            self.anchor_method,
             public_key
         );
 
         let account_url = format!("{}/accounts/{}", self.horizon_url, public_key);
         let acct_resp = self
-            .http_client
-            .get(&account_url)
-            .send()
+            .send(self.http_client.get(&account_url))
             .await
             .map_err(|e| anyhow!("Failed to fetch account info: {}", e))?;
 
@@ -393,16 +1073,6 @@ impl StellarClient {
             .parse()
             .map_err(|_| anyhow!("Could not parse account sequence"))?;
 
-        let data_key = build_data_key(hash);
-        let data_value = DataValue::from_slice(hash.as_bytes())
-            .map_err(|e| anyhow!("DataValue error: {:?}", e))?;
-
-        let op = Operation::new_manage_data()
-            .with_data_name(data_key)
-            .with_data_value(Some(data_value))
-            .build()
-            .map_err(|e| anyhow!("Failed to build ManageData operation: {:?}", e))?;
-
         let keypair = KeyPair::from_secret_seed(secret_key)
             .map_err(|e| anyhow!("Invalid secret key: {:?}", e))?;
 
@@ -412,8 +1082,38 @@ impl StellarClient {
             Network::new_public()
         };
 
-        let mut tx = Transaction::builder(keypair.public_key().clone(), sequence, MIN_BASE_FEE)
-            .add_operation(op)
+        let builder = Transaction::builder(keypair.public_key().clone(), sequence, MIN_BASE_FEE);
+        let builder = match self.anchor_method {
+            AnchorMethod::ManageData => {
+                let data_key = build_data_key(hash);
+                let data_value = DataValue::from_slice(hash.as_bytes())
+                    .map_err(|e| anyhow!("DataValue error: {:?}", e))?;
+
+                let op = Operation::new_manage_data()
+                    .with_data_name(data_key)
+                    .with_data_value(Some(data_value))
+                    .build()
+                    .map_err(|e| anyhow!("Failed to build ManageData operation: {:?}", e))?;
+                builder.add_operation(op)
+            }
+            AnchorMethod::Memo => {
+                let digest = hex::decode(hash)
+                    .ok()
+                    .filter(|bytes| bytes.len() == 32)
+                    .ok_or_else(|| anyhow!("Memo anchoring requires a SHA-256 hash"))?;
+                let memo =
+                    Memo::new_hash(&digest).map_err(|e| anyhow!("Invalid memo hash: {:?}", e))?;
+
+                // Bumping to the current sequence is a no-op.
+                let op = Operation::new_bump_sequence()
+                    .with_bump_to(sequence)
+                    .build()
+                    .map_err(|e| anyhow!("Failed to build BumpSequence operation: {:?}", e))?;
+                builder.add_operation(op).with_memo(memo)
+            }
+        };
+
+        let mut tx = builder
             .into_transaction()
             .map_err(|e| anyhow!("Failed to build transaction: {:?}", e))?;
 
@@ -430,11 +1130,12 @@ impl StellarClient {
         let form_body = format!("tx={}", urlencoding::encode(&xdr_b64));
 
         let submit_resp = self
-            .http_client
-            .post(&submit_url)
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .body(form_body)
-            .send()
+            .send(
+                self.http_client
+                    .post(&submit_url)
+                    .header("Content-Type", "application/x-www-form-urlencoded")
+                    .body(form_body),
+            )
             .await
             .map_err(|e| anyhow!("Transaction submission failed: {}", e))?;
 
@@ -482,9 +1183,7 @@ impl StellarClient {
 
         let account_url = format!("{}/accounts/{}", self.horizon_url, public_key);
         let acct_resp = self
-            .http_client
-            .get(&account_url)
-            .send()
+            .send(self.http_client.get(&account_url))
             .await
             .map_err(|e| anyhow!("Failed to fetch account info: {}", e))?;
 
@@ -541,11 +1240,12 @@ impl StellarClient {
         let form_body = format!("tx={}", urlencoding::encode(&xdr_b64));
 
         let submit_resp = self
-            .http_client
-            .post(&submit_url)
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .body(form_body)
-            .send()
+            .send(
+                self.http_client
+                    .post(&submit_url)
+                    .header("Content-Type", "application/x-www-form-urlencoded")
+                    .body(form_body),
+            )
             .await
             .map_err(|e| anyhow!("Transaction submission failed: {}", e))?;
 
@@ -0,0 +1,584 @@
+//! In-process fake Horizon with an in-memory ledger for one account.
+//!
+//! `POST /transactions` decodes the submitted envelope, records its memo and
+//! `ManageData`/`BumpSequence` operations, applies the data entries to the
+//! account and bumps its sequence. The read endpoints the service uses are
+//! answered from that ledger, with Horizon-style `cursor`/`order`/`limit`
+//! paging, so a whole anchor → verify → revoke flow runs without mocks.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::{Path, Query, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Form, Json, Router};
+use base64::Engine as _;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+const B64: base64::engine::general_purpose::GeneralPurpose =
+    base64::engine::general_purpose::STANDARD;
+
+/// Ledger timestamps start here and advance five seconds per ledger.
+const GENESIS_TIMESTAMP: i64 = 1_704_067_200;
+
+/// Memo decoded from a submitted envelope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FakeMemo {
+    None,
+    Text(String),
+    Id(u64),
+    Hash([u8; 32]),
+    Return([u8; 32]),
+}
+
+/// Operation decoded from a submitted envelope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FakeOperation {
+    ManageData {
+        name: String,
+        value: Option<Vec<u8>>,
+    },
+    BumpSequence {
+        bump_to: i64,
+    },
+}
+
+/// A transaction accepted by `POST /transactions`.
+#[derive(Debug, Clone)]
+pub struct FakeTransaction {
+    pub hash: String,
+    pub ledger: u32,
+    pub created_at: String,
+    pub memo: FakeMemo,
+    pub operations: Vec<FakeOperation>,
+}
+
+#[derive(Debug)]
+struct Ledger {
+    account_id: String,
+    sequence: i64,
+    balance: String,
+    data: BTreeMap<String, String>,
+    transactions: Vec<FakeTransaction>,
+    last_ledger: u32,
+    fail_requests: usize,
+    fail_submissions: usize,
+    delay: Duration,
+}
+
+/// Handle to a running fake Horizon. The server stops when this is dropped.
+pub struct FakeHorizon {
+    url: String,
+    ledger: Arc<Mutex<Ledger>>,
+    task: JoinHandle<()>,
+}
+
+impl FakeHorizon {
+    /// Starts a fake Horizon on an ephemeral port serving `account_id`.
+    pub async fn start(account_id: &str) -> Self {
+        let ledger = Arc::new(Mutex::new(Ledger {
+            account_id: account_id.to_string(),
+            sequence: 1,
+            balance: "10000.0000000".to_string(),
+            data: BTreeMap::new(),
+            transactions: Vec::new(),
+            last_ledger: 1,
+            fail_requests: 0,
+            fail_submissions: 0,
+            delay: Duration::ZERO,
+        }));
+
+        let app = Router::new()
+            .route("/", get(root))
+            .route("/accounts/:id", get(account))
+            .route("/accounts/:id/transactions", get(account_transactions))
+            .route("/accounts/:id/operations", get(account_operations))
+            .route("/transactions", post(submit_transaction))
+            .route("/transactions/:hash", get(transaction))
+            .route(
+                "/transactions/:hash/operations",
+                get(transaction_operations),
+            )
+            .layer(middleware::from_fn_with_state(
+                ledger.clone(),
+                inject_faults,
+            ))
+            .with_state(ledger.clone());
+
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind fake horizon");
+        let url = format!("http://{}", listener.local_addr().expect("local addr"));
+        let task = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        Self { url, ledger, task }
+    }
+
+    /// Base URL to pass as `STELLAR_HORIZON_URL`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Fails the next `n` requests of any kind with a 500.
+    pub fn fail_next_requests(&self, n: usize) {
+        self.lock().fail_requests = n;
+    }
+
+    /// Fails the next `n` `POST /transactions` with a 500; reads still succeed.
+    pub fn fail_next_submissions(&self, n: usize) {
+        self.lock().fail_submissions = n;
+    }
+
+    /// Delays every response by `delay`.
+    pub fn set_delay(&self, delay: Duration) {
+        self.lock().delay = delay;
+    }
+
+    /// Sets the native balance reported by `GET /accounts/:id`.
+    pub fn set_balance(&self, balance: &str) {
+        self.lock().balance = balance.to_string();
+    }
+
+    /// Seeds a data entry on the account, as if set by an earlier ledger.
+    pub fn set_data(&self, name: &str, value: &[u8]) {
+        self.lock().data.insert(name.to_string(), B64.encode(value));
+    }
+
+    /// Current account sequence; starts at 1 and increments per submission.
+    pub fn sequence(&self) -> i64 {
+        self.lock().sequence
+    }
+
+    /// Transactions accepted so far, oldest first.
+    pub fn transactions(&self) -> Vec<FakeTransaction> {
+        self.lock().transactions.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Ledger> {
+        self.ledger.lock().expect("fake horizon ledger poisoned")
+    }
+}
+
+impl Drop for FakeHorizon {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+type Shared = Arc<Mutex<Ledger>>;
+
+async fn inject_faults(State(ledger): State<Shared>, req: Request, next: Next) -> Response {
+    let is_submission = req.method() == axum::http::Method::POST;
+    let (delay, fail) = {
+        let mut l = ledger.lock().unwrap();
+        let fail = if l.fail_requests > 0 {
+            l.fail_requests -= 1;
+            true
+        } else if is_submission && l.fail_submissions > 0 {
+            l.fail_submissions -= 1;
+            true
+        } else {
+            false
+        };
+        (l.delay, fail)
+    };
+
+    if !delay.is_zero() {
+        tokio::time::sleep(delay).await;
+    }
+    if fail {
+        return horizon_error(StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error");
+    }
+    next.run(req).await
+}
+
+fn horizon_error(status: StatusCode, title: &str) -> Response {
+    (
+        status,
+        Json(json!({ "title": title, "status": status.as_u16() })),
+    )
+        .into_response()
+}
+
+fn not_found() -> Response {
+    horizon_error(StatusCode::NOT_FOUND, "Resource Missing")
+}
+
+async fn root() -> Json<Value> {
+    Json(json!({ "horizon_version": "fake" }))
+}
+
+async fn account(State(ledger): State<Shared>, Path(id): Path<String>) -> Response {
+    let l = ledger.lock().unwrap();
+    if id != l.account_id {
+        return not_found();
+    }
+    Json(json!({
+        "id": l.account_id,
+        "account_id": l.account_id,
+        "sequence": l.sequence.to_string(),
+        "subentry_count": l.data.len(),
+        "balances": [{ "asset_type": "native", "balance": l.balance }],
+        "thresholds": { "low_threshold": 0, "med_threshold": 0, "high_threshold": 0 },
+        "signers": [{ "key": l.account_id, "weight": 1, "type": "ed25519_public_key" }],
+        "data": l.data,
+    }))
+    .into_response()
+}
+
+#[derive(Debug, Deserialize)]
+struct PageQuery {
+    cursor: Option<String>,
+    order: Option<String>,
+    limit: Option<usize>,
+}
+
+async fn account_transactions(
+    State(ledger): State<Shared>,
+    Path(id): Path<String>,
+    Query(query): Query<PageQuery>,
+) -> Response {
+    let l = ledger.lock().unwrap();
+    if id != l.account_id {
+        return not_found();
+    }
+    let records = l
+        .transactions
+        .iter()
+        .map(|tx| (tx.ledger as u64, transaction_json(&l.account_id, tx)))
+        .collect();
+    Json(page(
+        &format!("/accounts/{}/transactions", id),
+        records,
+        &query,
+    ))
+    .into_response()
+}
+
+async fn account_operations(
+    State(ledger): State<Shared>,
+    Path(id): Path<String>,
+    Query(query): Query<PageQuery>,
+) -> Response {
+    let l = ledger.lock().unwrap();
+    if id != l.account_id {
+        return not_found();
+    }
+    let records = l
+        .transactions
+        .iter()
+        .flat_map(|tx| operation_records(&l.account_id, tx))
+        .collect();
+    Json(page(
+        &format!("/accounts/{}/operations", id),
+        records,
+        &query,
+    ))
+    .into_response()
+}
+
+async fn transaction(State(ledger): State<Shared>, Path(hash): Path<String>) -> Response {
+    let l = ledger.lock().unwrap();
+    match l.transactions.iter().find(|tx| tx.hash == hash) {
+        Some(tx) => Json(transaction_json(&l.account_id, tx)).into_response(),
+        None => not_found(),
+    }
+}
+
+async fn transaction_operations(
+    State(ledger): State<Shared>,
+    Path(hash): Path<String>,
+    Query(query): Query<PageQuery>,
+) -> Response {
+    let l = ledger.lock().unwrap();
+    match l.transactions.iter().find(|tx| tx.hash == hash) {
+        Some(tx) => Json(page(
+            &format!("/transactions/{}/operations", hash),
+            operation_records(&l.account_id, tx),
+            &query,
+        ))
+        .into_response(),
+        None => not_found(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SubmitForm {
+    tx: String,
+}
+
+async fn submit_transaction(
+    State(ledger): State<Shared>,
+    Form(form): Form<SubmitForm>,
+) -> Response {
+    let envelope = match B64
+        .decode(form.tx.trim())
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| parse_envelope(&bytes).map(|parsed| (bytes, parsed)))
+    {
+        Ok(envelope) => envelope,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "title": "Transaction Malformed", "status": 400, "detail": e })),
+            )
+                .into_response()
+        }
+    };
+    let (bytes, (memo, operations)) = envelope;
+
+    let mut l = ledger.lock().unwrap();
+    l.last_ledger += 1;
+    l.sequence += 1;
+    for op in &operations {
+        match op {
+            FakeOperation::ManageData {
+                name,
+                value: Some(value),
+            } => {
+                l.data.insert(name.clone(), B64.encode(value));
+            }
+            FakeOperation::ManageData { name, value: None } => {
+                l.data.remove(name);
+            }
+            FakeOperation::BumpSequence { bump_to } => {
+                l.sequence = l.sequence.max(*bump_to);
+            }
+        }
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes);
+    hasher.update(l.last_ledger.to_be_bytes());
+    let tx = FakeTransaction {
+        hash: hex::encode(hasher.finalize()),
+        ledger: l.last_ledger,
+        created_at: ledger_time(l.last_ledger),
+        memo,
+        operations,
+    };
+    let body = json!({ "hash": tx.hash, "ledger": tx.ledger, "created_at": tx.created_at });
+    l.transactions.push(tx);
+    Json(body).into_response()
+}
+
+fn ledger_time(ledger: u32) -> String {
+    chrono::DateTime::from_timestamp(GENESIS_TIMESTAMP + ledger as i64 * 5, 0)
+        .expect("valid ledger timestamp")
+        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+fn transaction_json(account_id: &str, tx: &FakeTransaction) -> Value {
+    let (memo_type, memo) = match &tx.memo {
+        FakeMemo::None => ("none", None),
+        FakeMemo::Text(text) => ("text", Some(text.clone())),
+        FakeMemo::Id(id) => ("id", Some(id.to_string())),
+        FakeMemo::Hash(bytes) => ("hash", Some(B64.encode(bytes))),
+        FakeMemo::Return(bytes) => ("return", Some(B64.encode(bytes))),
+    };
+    json!({
+        "id": tx.hash,
+        "paging_token": tx.ledger.to_string(),
+        "hash": tx.hash,
+        "ledger": tx.ledger,
+        "created_at": tx.created_at,
+        "source_account": account_id,
+        "memo_type": memo_type,
+        "memo": memo,
+        "successful": true,
+        "operation_count": tx.operations.len(),
+    })
+}
+
+/// Operation ids follow Horizon's layout: the ledger in the high bits and
+/// the operation's position in the low bits, so they sort chronologically.
+fn operation_records(account_id: &str, tx: &FakeTransaction) -> Vec<(u64, Value)> {
+    tx.operations
+        .iter()
+        .enumerate()
+        .map(|(index, op)| {
+            let id = ((tx.ledger as u64) << 32) | (index as u64 + 1);
+            let mut record = json!({
+                "id": id.to_string(),
+                "paging_token": id.to_string(),
+                "transaction_hash": tx.hash,
+                "created_at": tx.created_at,
+                "source_account": account_id,
+                "transaction_successful": true,
+            });
+            match op {
+                FakeOperation::ManageData { name, value } => {
+                    record["type"] = json!("manage_data");
+                    record["name"] = json!(name);
+                    record["value"] = json!(value.as_ref().map(|v| B64.encode(v)));
+                }
+                FakeOperation::BumpSequence { bump_to } => {
+                    record["type"] = json!("bump_sequence");
+                    record["bump_to"] = json!(bump_to.to_string());
+                }
+            }
+            (id, record)
+        })
+        .collect()
+}
+
+/// Applies `cursor`/`order`/`limit` to records sorted oldest first and wraps
+/// them in Horizon's `_embedded`/`_links` envelope.
+fn page(path: &str, records: Vec<(u64, Value)>, query: &PageQuery) -> Value {
+    let descending = query.order.as_deref() == Some("desc");
+    let limit = query.limit.unwrap_or(10).clamp(1, 200);
+    let cursor = query.cursor.as_deref().and_then(|c| c.parse::<u64>().ok());
+
+    let mut records = records;
+    if descending {
+        records.reverse();
+    }
+    let page: Vec<(u64, Value)> = records
+        .into_iter()
+        .filter(|(token, _)| match cursor {
+            Some(c) if descending => *token < c,
+            Some(c) => *token > c,
+            None => true,
+        })
+        .take(limit)
+        .collect();
+
+    let order = if descending { "desc" } else { "asc" };
+    let reverse = if descending { "asc" } else { "desc" };
+    let link = |cursor: Option<u64>, order: &str| {
+        let cursor = cursor.map(|c| c.to_string()).unwrap_or_default();
+        json!({ "href": format!("{}?cursor={}&limit={}&order={}", path, cursor, limit, order) })
+    };
+    let first = page.first().map(|(token, _)| *token);
+    let last = page.last().map(|(token, _)| *token).or(cursor);
+
+    json!({
+        "_links": {
+            "self": link(cursor, order),
+            "next": link(last, order),
+            "prev": link(first.or(cursor), reverse),
+        },
+        "_embedded": { "records": page.into_iter().map(|(_, r)| r).collect::<Vec<_>>() },
+    })
+}
+
+/// Decodes the memo and operations of a `TransactionEnvelope`. Only the
+/// operation types this service submits are understood.
+fn parse_envelope(bytes: &[u8]) -> Result<(FakeMemo, Vec<FakeOperation>), String> {
+    let mut r = XdrReader { bytes, pos: 0 };
+    match r.u32()? {
+        // ENVELOPE_TYPE_TX_V0: raw ed25519 source key.
+        0 => r.skip(32)?,
+        // ENVELOPE_TYPE_TX: muxed source account.
+        2 => r.muxed_account()?,
+        other => return Err(format!("unsupported envelope type {}", other)),
+    }
+    r.skip(4 + 8)?; // fee, seqNum
+    match r.u32()? {
+        0 => {}
+        1 => r.skip(16)?, // time bounds
+        other => return Err(format!("unsupported preconditions type {}", other)),
+    }
+
+    let memo = match r.u32()? {
+        0 => FakeMemo::None,
+        1 => FakeMemo::Text(String::from_utf8_lossy(&r.var_opaque(28)?).into_owned()),
+        2 => FakeMemo::Id(r.u64()?),
+        3 => FakeMemo::Hash(r.hash()?),
+        4 => FakeMemo::Return(r.hash()?),
+        other => return Err(format!("unknown memo type {}", other)),
+    };
+
+    let count = r.u32()?;
+    if count > 100 {
+        return Err(format!("{} operations exceeds the limit of 100", count));
+    }
+    let mut operations = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        if r.u32()? == 1 {
+            r.muxed_account()?;
+        }
+        let op = match r.u32()? {
+            10 => {
+                let name = String::from_utf8_lossy(&r.var_opaque(64)?).into_owned();
+                let value = match r.u32()? {
+                    0 => None,
+                    _ => Some(r.var_opaque(64)?),
+                };
+                FakeOperation::ManageData { name, value }
+            }
+            11 => FakeOperation::BumpSequence {
+                bump_to: r.u64()? as i64,
+            },
+            other => return Err(format!("unsupported operation type {}", other)),
+        };
+        operations.push(op);
+    }
+
+    Ok((memo, operations))
+}
+
+struct XdrReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl XdrReader<'_> {
+    fn take(&mut self, n: usize) -> Result<&[u8], String> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| "envelope is truncated".to_string())?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn skip(&mut self, n: usize) -> Result<(), String> {
+        self.take(n).map(|_| ())
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(((self.u32()? as u64) << 32) | self.u32()? as u64)
+    }
+
+    fn hash(&mut self) -> Result<[u8; 32], String> {
+        let mut out = [0u8; 32];
+        out.copy_from_slice(self.take(32)?);
+        Ok(out)
+    }
+
+    fn var_opaque(&mut self, max: usize) -> Result<Vec<u8>, String> {
+        let len = self.u32()? as usize;
+        if len > max {
+            return Err(format!("field of {} bytes exceeds {}", len, max));
+        }
+        let data = self.take(len)?.to_vec();
+        self.skip((4 - len % 4) % 4)?;
+        Ok(data)
+    }
+
+    fn muxed_account(&mut self) -> Result<(), String> {
+        match self.u32()? {
+            0 => self.skip(32),
+            0x100 => self.skip(8 + 32),
+            other => Err(format!("unknown account type {}", other)),
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! In-process fake Redis speaking just enough RESP for [`crate::cache`].
+//!
+//! Supports the string, TTL and counter commands `RedisCache` issues
+//! (`GET`, `SET`/`SETEX`, `MGET`, `DEL`, `TTL`, `EXPIRE`, `INCR`, `PING`).
+//! Other commands are acknowledged with `+OK`. An outage is simulated by
+//! answering every command with an error until the server is brought back.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+#[derive(Debug, Default)]
+struct Store {
+    values: HashMap<String, (String, Option<Instant>)>,
+    down: bool,
+}
+
+impl Store {
+    fn live(&mut self, key: &str) -> Option<&mut (String, Option<Instant>)> {
+        let expired = matches!(
+            self.values.get(key),
+            Some((_, Some(expires_at))) if *expires_at <= Instant::now()
+        );
+        if expired {
+            self.values.remove(key);
+        }
+        self.values.get_mut(key)
+    }
+}
+
+type Shared = Arc<Mutex<Store>>;
+
+/// Handle to a running fake Redis. The server stops when this is dropped.
+pub struct FakeRedis {
+    url: String,
+    store: Shared,
+    task: JoinHandle<()>,
+}
+
+impl FakeRedis {
+    /// Starts a fake Redis on an ephemeral port.
+    pub async fn start() -> Self {
+        let store = Shared::default();
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind fake redis");
+        let url = format!("redis://{}", listener.local_addr().expect("local addr"));
+        let accept_store = store.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, accept_store.clone()));
+            }
+        });
+        Self { url, store, task }
+    }
+
+    /// URL to pass as `REDIS_URL`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// While down, every command is answered with an error.
+    pub fn set_down(&self, down: bool) {
+        self.lock().down = down;
+    }
+
+    /// Current value at `key`, bypassing the outage switch.
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.lock().live(key).map(|(value, _)| value.clone())
+    }
+
+    /// Stores `value` at `key` without expiry, as another instance would.
+    pub fn set(&self, key: &str, value: &str) {
+        self.lock()
+            .values
+            .insert(key.to_string(), (value.to_string(), None));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Store> {
+        self.store.lock().expect("fake redis store poisoned")
+    }
+}
+
+impl Drop for FakeRedis {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+enum Reply {
+    Ok,
+    Pong,
+    Bulk(Option<String>),
+    Int(i64),
+    Array(Vec<Option<String>>),
+    Error(String),
+}
+
+impl Reply {
+    fn encode(self) -> Vec<u8> {
+        fn bulk(value: Option<String>) -> String {
+            match value {
+                Some(v) => format!("${}\r\n{}\r\n", v.len(), v),
+                None => "$-1\r\n".to_string(),
+            }
+        }
+        match self {
+            Reply::Ok => "+OK\r\n".to_string(),
+            Reply::Pong => "+PONG\r\n".to_string(),
+            Reply::Bulk(value) => bulk(value),
+            Reply::Int(n) => format!(":{}\r\n", n),
+            Reply::Array(values) => {
+                let mut out = format!("*{}\r\n", values.len());
+                for value in values {
+                    out.push_str(&bulk(value));
+                }
+                out
+            }
+            Reply::Error(message) => format!("-ERR {}\r\n", message),
+        }
+        .into_bytes()
+    }
+}
+
+async fn serve(stream: TcpStream, store: Shared) {
+    let (read, mut write) = stream.into_split();
+    let mut reader = BufReader::new(read);
+    while let Some(args) = read_command(&mut reader).await {
+        let reply = execute(&store, &args);
+        if write.write_all(&reply.encode()).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Reads one RESP array of bulk strings; `None` on EOF or malformed input.
+async fn read_command<R>(reader: &mut BufReader<R>) -> Option<Vec<String>>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    let count: usize = read_header(reader, '*').await?;
+    let mut args = Vec::with_capacity(count);
+    for _ in 0..count {
+        let len: usize = read_header(reader, '$').await?;
+        let mut buf = vec![0; len + 2];
+        reader.read_exact(&mut buf).await.ok()?;
+        buf.truncate(len);
+        args.push(String::from_utf8(buf).ok()?);
+    }
+    Some(args)
+}
+
+async fn read_header<R>(reader: &mut BufReader<R>, marker: char) -> Option<usize>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    let mut line = String::new();
+    if reader.read_line(&mut line).await.ok()? == 0 {
+        return None;
+    }
+    line.trim_end().strip_prefix(marker)?.parse().ok()
+}
+
+fn execute(store: &Shared, args: &[String]) -> Reply {
+    let mut store = store.lock().expect("fake redis store poisoned");
+    if store.down {
+        return Reply::Error("fake outage".to_string());
+    }
+    let Some(command) = args.first() else {
+        return Reply::Error("empty command".to_string());
+    };
+    let expiry = |secs: &str| {
+        secs.parse::<u64>()
+            .ok()
+            .map(|secs| Instant::now() + Duration::from_secs(secs))
+    };
+    match (command.to_ascii_uppercase().as_str(), &args[1..]) {
+        ("PING", _) => Reply::Pong,
+        ("GET", [key]) => Reply::Bulk(store.live(key).map(|(value, _)| value.clone())),
+        ("MGET", keys) => Reply::Array(
+            keys.iter()
+                .map(|key| store.live(key).map(|(value, _)| value.clone()))
+                .collect(),
+        ),
+        ("SETEX", [key, secs, value]) => {
+            store
+                .values
+                .insert(key.clone(), (value.clone(), expiry(secs)));
+            Reply::Ok
+        }
+        ("SET", [key, value, rest @ ..]) => {
+            let expires_at = match rest {
+                [flag, secs] if flag.eq_ignore_ascii_case("EX") => expiry(secs),
+                _ => None,
+            };
+            store
+                .values
+                .insert(key.clone(), (value.clone(), expires_at));
+            Reply::Ok
+        }
+        ("DEL", keys) => {
+            let removed = keys.iter().filter(|key| store.live(key).is_some()).count();
+            for key in keys {
+                store.values.remove(key);
+            }
+            Reply::Int(removed as i64)
+        }
+        ("TTL", [key]) => Reply::Int(match store.live(key) {
+            Some((_, Some(expires_at))) => expires_at
+                .saturating_duration_since(Instant::now())
+                .as_secs() as i64,
+            Some((_, None)) => -1,
+            None => -2,
+        }),
+        ("EXPIRE", [key, secs]) => match store.live(key) {
+            Some(entry) => {
+                entry.1 = expiry(secs);
+                Reply::Int(1)
+            }
+            None => Reply::Int(0),
+        },
+        ("INCR", [key]) => {
+            let entry = store
+                .live(key)
+                .map(|(value, expires_at)| (value.parse::<i64>(), *expires_at));
+            match entry {
+                Some((Err(_), _)) => Reply::Error("value is not an integer".to_string()),
+                Some((Ok(n), expires_at)) => {
+                    store
+                        .values
+                        .insert(key.clone(), ((n + 1).to_string(), expires_at));
+                    Reply::Int(n + 1)
+                }
+                None => {
+                    store.values.insert(key.clone(), ("1".to_string(), None));
+                    Reply::Int(1)
+                }
+            }
+        }
+        _ => Reply::Ok,
+    }
+}
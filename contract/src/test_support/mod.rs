@@ -0,0 +1,5 @@
+//! Test doubles shared by this crate's integration tests and downstream
+//! suites. Only compiled with the `test-support` feature.
+
+pub mod fake_horizon;
+pub mod fake_redis;
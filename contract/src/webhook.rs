@@ -0,0 +1,178 @@
+use crate::config::TimeConfig;
+use chrono::Utc;
+use futures::future::join_all;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::time::Duration;
+use tracing::{info, warn};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Header carrying the hex HMAC-SHA256 of the request body.
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// Envelope POSTed to every configured webhook URL.
+#[derive(Debug, Serialize)]
+pub struct WebhookPayload<'a> {
+    pub event: &'a str,
+    pub timestamp: i64,
+    pub data: serde_json::Value,
+}
+
+/// Delivers signed event notifications to the configured webhook URLs.
+pub struct WebhookDispatcher {
+    urls: Vec<String>,
+    secret: Option<String>,
+    http_client: reqwest::Client,
+}
+
+impl WebhookDispatcher {
+    pub fn new(urls: Vec<String>, secret: Option<String>) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        Self {
+            urls,
+            secret,
+            http_client,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.urls.is_empty()
+    }
+
+    /// POST `event` to every URL concurrently. Delivery failures are logged
+    /// and never propagated to the caller.
+    pub async fn dispatch(&self, event: &str, data: serde_json::Value) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let payload = WebhookPayload {
+            event,
+            timestamp: Utc::now().timestamp(),
+            data,
+        };
+        let body = match serde_json::to_vec(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("Failed to serialize {} webhook: {}", event, e);
+                return;
+            }
+        };
+        let signature = self
+            .secret
+            .as_deref()
+            .map(|secret| sign_payload(secret, &body));
+
+        let deliveries = self.urls.iter().map(|url| {
+            let mut request = self
+                .http_client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .body(body.clone());
+            if let Some(signature) = &signature {
+                request = request.header(SIGNATURE_HEADER, signature);
+            }
+            async move {
+                match request.send().await {
+                    Ok(resp) if resp.status().is_success() => {
+                        info!("Delivered {} webhook to {}", event, url)
+                    }
+                    Ok(resp) => warn!(
+                        "Webhook {} to {} returned status {}",
+                        event,
+                        url,
+                        resp.status()
+                    ),
+                    Err(e) => warn!("Webhook {} to {} failed: {}", event, url, e),
+                }
+            }
+        });
+        join_all(deliveries).await;
+    }
+}
+
+/// Hex-encoded HMAC-SHA256 of `payload` keyed with the webhook secret.
+pub fn sign_payload(secret: &str, payload: &[u8]) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(payload);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Checks a hex signature produced by [`sign_payload`] in constant time.
+pub fn verify_signature(secret: &str, payload: &[u8], signature: &str) -> bool {
+    let expected = match hex::decode(signature) {
+        Ok(bytes) => bytes,
+        Err(_) => return false,
+    };
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(payload);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Receiver-side check for a delivered webhook: the signature must match the
+/// body and the payload `timestamp` must be within the clock skew of `now`,
+/// which rejects replays of old deliveries.
+pub fn verify_webhook(
+    secret: &str,
+    body: &[u8],
+    signature: &str,
+    time: &TimeConfig,
+    now: i64,
+) -> bool {
+    if !verify_signature(secret, body, signature) {
+        return false;
+    }
+    serde_json::from_slice::<serde_json::Value>(body)
+        .ok()
+        .and_then(|payload| payload.get("timestamp").and_then(|t| t.as_i64()))
+        .is_some_and(|timestamp| time.within_skew(timestamp, now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_round_trips() {
+        let sig = sign_payload("secret", b"payload");
+        assert_eq!(sig.len(), 64);
+        assert!(verify_signature("secret", b"payload", &sig));
+    }
+
+    #[test]
+    fn signature_rejects_other_secret_or_payload() {
+        let sig = sign_payload("secret", b"payload");
+        assert!(!verify_signature("other", b"payload", &sig));
+        assert!(!verify_signature("secret", b"payload2", &sig));
+        assert!(!verify_signature("secret", b"payload", "not-hex"));
+    }
+
+    #[test]
+    fn webhook_timestamp_checked_against_skew() {
+        let time = TimeConfig::default();
+        let now = 1_700_000_000;
+        let body = |timestamp: i64| {
+            serde_json::to_vec(&WebhookPayload {
+                event: "hash_revoked",
+                timestamp,
+                data: serde_json::json!({}),
+            })
+            .unwrap()
+        };
+
+        let within = body(now - 300);
+        let sig = sign_payload("secret", &within);
+        assert!(verify_webhook("secret", &within, &sig, &time, now));
+
+        let outside = body(now - 301);
+        let sig = sign_payload("secret", &outside);
+        assert!(!verify_webhook("secret", &outside, &sig, &time, now));
+    }
+}
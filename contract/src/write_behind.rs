@@ -0,0 +1,284 @@
+use crate::cache::CacheBackend;
+use crate::metrics::MetricsRegistry;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{watch, Notify};
+use tracing::{info, warn};
+
+/// Destination for deferred cache writes.
+#[async_trait]
+pub trait CacheWriter: Send + Sync {
+    async fn write_raw(&self, key: &str, value: &str, ttl: u64) -> Result<()>;
+}
+
+#[async_trait]
+impl CacheWriter for CacheBackend {
+    async fn write_raw(&self, key: &str, value: &str, ttl: u64) -> Result<()> {
+        self.set_raw(key, value, ttl).await
+    }
+}
+
+struct PendingWrite {
+    key: String,
+    value: String,
+    ttl: u64,
+    enqueued_at: Instant,
+    attempts: u32,
+    next_attempt: Instant,
+}
+
+impl PendingWrite {
+    /// An entry older than its TTL would already have expired from the cache.
+    fn is_expired(&self, now: Instant) -> bool {
+        now.duration_since(self.enqueued_at) >= Duration::from_secs(self.ttl)
+    }
+}
+
+/// Bounded queue of cache writes that failed and are retried in the background.
+///
+/// When full, the oldest entry is dropped to make room for the newest.
+pub struct WriteBehindQueue {
+    pending: Mutex<VecDeque<PendingWrite>>,
+    capacity: usize,
+    base_backoff: Duration,
+    max_backoff: Duration,
+    notify: Notify,
+    metrics: Arc<MetricsRegistry>,
+}
+
+impl WriteBehindQueue {
+    pub fn new(capacity: usize, metrics: Arc<MetricsRegistry>) -> Self {
+        Self {
+            pending: Mutex::new(VecDeque::new()),
+            capacity,
+            base_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            notify: Notify::new(),
+            metrics,
+        }
+    }
+
+    /// Override the retry backoff range (mainly for tests).
+    pub fn with_backoff(mut self, base: Duration, max: Duration) -> Self {
+        self.base_backoff = base;
+        self.max_backoff = max;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Queue a write for retry.
+    pub fn push(&self, key: &str, value: String, ttl: u64) {
+        if self.capacity == 0 {
+            self.metrics.increment_write_behind_dropped("overflow");
+            return;
+        }
+
+        let now = Instant::now();
+        {
+            let mut pending = self.pending.lock().unwrap();
+            while pending.len() >= self.capacity {
+                pending.pop_front();
+                self.metrics.increment_write_behind_dropped("overflow");
+            }
+            pending.push_back(PendingWrite {
+                key: key.to_string(),
+                value,
+                ttl,
+                enqueued_at: now,
+                attempts: 0,
+                next_attempt: now,
+            });
+        }
+        self.notify.notify_one();
+    }
+
+    /// Attempt every entry whose backoff has elapsed (or every entry when
+    /// `force` is set). Failures are re-queued with exponential backoff and
+    /// expired entries are dropped. Returns the number of entries written.
+    pub async fn flush(&self, cache: &dyn CacheWriter, force: bool) -> usize {
+        let now = Instant::now();
+        let due: Vec<PendingWrite> = {
+            let mut pending = self.pending.lock().unwrap();
+            let (due, waiting): (VecDeque<_>, VecDeque<_>) = pending
+                .drain(..)
+                .partition(|entry| force || entry.next_attempt <= now);
+            *pending = waiting;
+            due.into()
+        };
+
+        let mut written = 0;
+        for mut entry in due {
+            if entry.is_expired(Instant::now()) {
+                self.metrics.increment_write_behind_dropped("expired");
+                continue;
+            }
+            match cache.write_raw(&entry.key, &entry.value, entry.ttl).await {
+                Ok(()) => written += 1,
+                Err(e) => {
+                    entry.attempts += 1;
+                    let backoff = self
+                        .base_backoff
+                        .saturating_mul(2u32.saturating_pow(entry.attempts - 1))
+                        .min(self.max_backoff);
+                    warn!(
+                        "Deferred cache write for {} failed (attempt {}): {}",
+                        entry.key, entry.attempts, e
+                    );
+                    entry.next_attempt = Instant::now() + backoff;
+                    self.requeue(entry);
+                }
+            }
+        }
+        written
+    }
+
+    fn requeue(&self, entry: PendingWrite) {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.len() >= self.capacity {
+            self.metrics.increment_write_behind_dropped("overflow");
+            return;
+        }
+        pending.push_back(entry);
+    }
+
+    fn next_due(&self) -> Option<Instant> {
+        let pending = self.pending.lock().unwrap();
+        pending.iter().map(|entry| entry.next_attempt).min()
+    }
+
+    /// Retry queued writes until `shutdown` flips to `true`, then make one
+    /// final attempt at everything still pending.
+    pub async fn run(
+        self: Arc<Self>,
+        cache: Arc<dyn CacheWriter>,
+        mut shutdown: watch::Receiver<bool>,
+    ) {
+        loop {
+            if *shutdown.borrow() {
+                break;
+            }
+            let wait = self
+                .next_due()
+                .map(|due| due.saturating_duration_since(Instant::now()))
+                .unwrap_or(self.max_backoff);
+
+            tokio::select! {
+                _ = self.notify.notified() => {}
+                _ = tokio::time::sleep(wait) => {}
+                changed = shutdown.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                }
+            }
+
+            self.flush(cache.as_ref(), false).await;
+        }
+
+        let remaining = self.len();
+        if remaining > 0 {
+            info!("Draining {} deferred cache writes", remaining);
+            self.flush(cache.as_ref(), true).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct FlakyCache {
+        failures_left: AtomicUsize,
+        writes: Mutex<Vec<String>>,
+    }
+
+    impl FlakyCache {
+        fn failing(times: usize) -> Self {
+            Self {
+                failures_left: AtomicUsize::new(times),
+                writes: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl CacheWriter for FlakyCache {
+        async fn write_raw(&self, key: &str, _value: &str, _ttl: u64) -> Result<()> {
+            let left = self.failures_left.load(Ordering::SeqCst);
+            if left > 0 {
+                self.failures_left.store(left - 1, Ordering::SeqCst);
+                anyhow::bail!("connection reset");
+            }
+            self.writes.lock().unwrap().push(key.to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_until_write_succeeds() {
+        let queue = Arc::new(
+            WriteBehindQueue::new(8, Arc::new(MetricsRegistry::new()))
+                .with_backoff(Duration::from_millis(1), Duration::from_millis(5)),
+        );
+        let cache = Arc::new(FlakyCache::failing(2));
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let task = tokio::spawn(queue.clone().run(cache.clone(), shutdown_rx));
+
+        queue.push("hash-a", "{}".to_string(), 3600);
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while cache.writes.lock().unwrap().is_empty() {
+                tokio::time::sleep(Duration::from_millis(2)).await;
+            }
+        })
+        .await
+        .expect("entry should eventually be written");
+
+        assert_eq!(*cache.writes.lock().unwrap(), vec!["hash-a".to_string()]);
+        assert!(queue.is_empty());
+
+        shutdown_tx.send(true).unwrap();
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn overflow_drops_oldest_entries() {
+        let metrics = Arc::new(MetricsRegistry::new());
+        let queue = WriteBehindQueue::new(2, metrics.clone());
+        queue.push("first", "{}".to_string(), 3600);
+        queue.push("second", "{}".to_string(), 3600);
+        queue.push("third", "{}".to_string(), 3600);
+        assert_eq!(queue.len(), 2);
+
+        let cache = FlakyCache::failing(0);
+        assert_eq!(queue.flush(&cache, true).await, 2);
+        assert_eq!(
+            *cache.writes.lock().unwrap(),
+            vec!["second".to_string(), "third".to_string()]
+        );
+        assert_eq!(metrics.write_behind_dropped("overflow"), 1);
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_dropped() {
+        let metrics = Arc::new(MetricsRegistry::new());
+        let queue = WriteBehindQueue::new(4, metrics.clone());
+        queue.push("stale", "{}".to_string(), 0);
+
+        let cache = FlakyCache::failing(0);
+        assert_eq!(queue.flush(&cache, true).await, 0);
+        assert!(cache.writes.lock().unwrap().is_empty());
+        assert_eq!(metrics.write_behind_dropped("expired"), 1);
+    }
+}
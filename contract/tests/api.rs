@@ -0,0 +1,2639 @@
+use axum::http::{header, HeaderValue};
+use axum_test::TestServer;
+use httpmock::prelude::*;
+use serde_json::json;
+use std::future::IntoFuture;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use stellar_doc_verifier::api_error::ApiErrorBody;
+use stellar_doc_verifier::cache::{CacheBackend, FallbackCache, InMemoryCache};
+use stellar_doc_verifier::config::TimeConfig;
+use stellar_doc_verifier::event::{Event, EventStore};
+use stellar_doc_verifier::hash_validator::HashAlgorithm;
+use stellar_doc_verifier::metrics::MetricsRegistry;
+use stellar_doc_verifier::proof::VerificationProof;
+use stellar_doc_verifier::public::{PublicRecentFeed, RecentAnchorsResponse, ANCHORED_EVENT};
+use stellar_doc_verifier::server;
+use stellar_doc_verifier::singleflight::SingleFlight;
+use stellar_doc_verifier::stellar::{build_data_key, build_revocation_key, StellarClient};
+use stellar_doc_verifier::test_support::fake_horizon::{FakeHorizon, FakeOperation};
+use stellar_doc_verifier::test_support::fake_redis::FakeRedis;
+use stellar_doc_verifier::webhook::WebhookDispatcher;
+use stellar_doc_verifier::write_behind::WriteBehindQueue;
+use stellar_doc_verifier::{
+    app, AppState, BatchSubmitResponse, BatchVerifySummary, CachePurgeResponse,
+    DocumentEventsResponse, DocumentStatus, DuplicatesResponse, RevokeResponse, SimilarityResponse,
+    SubmitResponse, TargetedVerifyResponse, TransferHistoryResponse, TransferRecord,
+    VerifyResponse,
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Valid testnet seed used only by these tests.
+const TEST_SECRET_KEY: &str = "SAAQEAYEAUDAOCAJBIFQYDIOB4IBCEQTCQKRMFYYDENBWHA5DYPSBF5K";
+/// Account id derived from [`TEST_SECRET_KEY`].
+const TEST_ACCOUNT_ID: &str = "GB43KVROR7TFJ6KAPCYRF2FJROTZAH4FHLTJLPWX4DRZCC5NASLGITR6";
+
+const TEST_WEBHOOK_SECRET: &str = "test-webhook-secret";
+const TEST_ADMIN_TOKEN: &str = "test-admin-token";
+
+const ANCHORED_HASH: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+fn test_state(horizon_url: &str) -> AppState {
+    test_state_with_webhooks(horizon_url, Vec::new())
+}
+
+fn test_state_with_webhooks(horizon_url: &str, webhook_urls: Vec<String>) -> AppState {
+    let metrics = Arc::new(MetricsRegistry::new());
+    let cache = Arc::new(CacheBackend::InMemory(InMemoryCache::new()));
+    AppState {
+        stellar: Arc::new(StellarClient::new(horizon_url)),
+        cache: cache.clone(),
+        metrics: metrics.clone(),
+        stellar_secret_key: TEST_SECRET_KEY.to_string(),
+        webhook_secret: Some(TEST_WEBHOOK_SECRET.to_string()),
+        webhooks: Arc::new(WebhookDispatcher::new(
+            webhook_urls,
+            Some(TEST_WEBHOOK_SECRET.to_string()),
+        )),
+        listeners: Arc::new(Vec::new()),
+        write_behind: Arc::new(WriteBehindQueue::new(16, metrics)),
+        events: Arc::new(EventStore::new(cache)),
+        accepted_algorithms: Arc::new(HashAlgorithm::ALL.to_vec()),
+        request_timeout: Duration::from_secs(30),
+        draining: Arc::new(AtomicBool::new(false)),
+        public_recent: Some(Arc::new(PublicRecentFeed::new(100))),
+        batch_concurrency: 8,
+        verify_flights: Arc::new(SingleFlight::new()),
+        cache_ttl: 3600,
+        cache_negative_ttl: 300,
+        admin_token: Some(TEST_ADMIN_TOKEN.to_string()),
+        time: TimeConfig::default(),
+    }
+}
+
+/// Horizon account body for the test account, able to pay for and sign
+/// a ManageData transaction.
+fn funded_account(data: serde_json::Value) -> serde_json::Value {
+    json!({
+        "sequence": "100",
+        "subentry_count": 1,
+        "balances": [{ "asset_type": "native", "balance": "100.0000000" }],
+        "thresholds": { "low_threshold": 0, "med_threshold": 0, "high_threshold": 0 },
+        "signers": [{ "key": TEST_ACCOUNT_ID, "weight": 1, "type": "ed25519_public_key" }],
+        "data": data
+    })
+}
+
+fn mock_anchored_account<'a>(server: &'a MockServer, hash: &str) -> httpmock::Mock<'a> {
+    server.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200).json_body(json!({
+            "sequence": "100",
+            "data": { build_data_key(hash): "YW5jaG9yZWQ=" }
+        }));
+    })
+}
+
+/// An account history with no memo anchors, for hashes without a data entry.
+fn mock_no_memo_anchors(server: &MockServer) {
+    server.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}/transactions", TEST_ACCOUNT_ID));
+        then.status(200)
+            .json_body(json!({ "_embedded": { "records": [] } }));
+    });
+}
+
+/// Operations and transaction lookups backing the anchor of `hash` in `tx`.
+fn mock_anchor_evidence(server: &MockServer, hash: &str, tx: &str) {
+    server.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}/operations", TEST_ACCOUNT_ID));
+        then.status(200).json_body(json!({
+            "_embedded": { "records": [{
+                "id": "op-1",
+                "transaction_hash": tx,
+                "created_at": "2024-03-01T12:00:00Z",
+                "type": "manage_data",
+                "name": build_data_key(hash),
+                "value": "YW5jaG9yZWQ="
+            }]}
+        }));
+    });
+    server.mock(|when, then| {
+        when.method(GET).path(format!("/transactions/{}", tx));
+        then.status(200).json_body(json!({
+            "hash": tx,
+            "ledger": 4242,
+            "created_at": "2024-03-01T12:00:00Z",
+            "source_account": TEST_ACCOUNT_ID,
+            "memo_type": "none",
+            "successful": true
+        }));
+    });
+}
+
+#[tokio::test]
+async fn verify_returns_json_by_default() {
+    let horizon = MockServer::start();
+    mock_anchored_account(&horizon, ANCHORED_HASH);
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+
+    let response = server.get(&format!("/verify/{}", ANCHORED_HASH)).await;
+
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["verified"], true);
+}
+
+#[tokio::test]
+async fn verify_returns_xml_when_accept_is_xml() {
+    let horizon = MockServer::start();
+    mock_anchored_account(&horizon, ANCHORED_HASH);
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+
+    let response = server
+        .post("/verify")
+        .add_header(header::ACCEPT, HeaderValue::from_static("application/xml"))
+        .json(&json!({ "document_hash": ANCHORED_HASH }))
+        .await;
+
+    response.assert_status_ok();
+    assert_eq!(
+        response.header(header::CONTENT_TYPE),
+        HeaderValue::from_static("application/xml")
+    );
+
+    let body = response.text();
+    let mut reader = quick_xml::Reader::from_str(&body);
+    let mut elements = Vec::new();
+    loop {
+        match reader.read_event().expect("body should be well-formed XML") {
+            quick_xml::events::Event::Start(e) => {
+                elements.push(String::from_utf8(e.name().as_ref().to_vec()).unwrap())
+            }
+            quick_xml::events::Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    assert_eq!(elements.first().map(String::as_str), Some("VerifyResponse"));
+    assert!(elements.iter().any(|e| e == "verified"));
+    assert!(elements.iter().any(|e| e == "cached"));
+    assert!(body.contains("<verified>true</verified>"));
+}
+
+#[tokio::test]
+async fn stale_cache_entry_is_rechecked_when_max_age_exceeded() {
+    let horizon = MockServer::start();
+    let account_mock = horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200).json_body(json!({
+            "sequence": "100",
+            "data": { build_data_key(ANCHORED_HASH): "YW5jaG9yZWQ=" }
+        }));
+    });
+
+    let state = test_state(&horizon.base_url());
+    let cache = state.cache.clone();
+    let stale_checked_at = chrono::Utc::now().timestamp() - 600;
+    cache
+        .set(
+            ANCHORED_HASH,
+            &VerifyResponse {
+                verified: true,
+                transaction_id: None,
+                timestamp: None,
+                cached: false,
+                revoked: false,
+                revoked_at: None,
+                revoked_transaction_id: None,
+                last_checked_at: stale_checked_at,
+                evidence: None,
+                error: None,
+            },
+            3600,
+        )
+        .await
+        .unwrap();
+    let server = TestServer::new(app(state)).unwrap();
+
+    // Without a freshness bound the stale entry is served as-is.
+    let response = server.get(&format!("/verify/{}", ANCHORED_HASH)).await;
+    let body: VerifyResponse = response.json();
+    assert_eq!(body.last_checked_at, stale_checked_at);
+    assert!(body.cached);
+    account_mock.assert_hits(0);
+
+    let response = server
+        .get(&format!("/verify/{}?max_age_secs=60", ANCHORED_HASH))
+        .await;
+    response.assert_status_ok();
+    let body: VerifyResponse = response.json();
+    account_mock.assert_hits(1);
+    assert!(body.verified);
+    assert!(!body.cached);
+    assert!(body.last_checked_at > stale_checked_at);
+
+    let stored: VerifyResponse = cache.get(ANCHORED_HASH).await.unwrap().unwrap();
+    assert_eq!(stored.last_checked_at, body.last_checked_at);
+    assert!(!stored.cached);
+
+    let response = server.get(&format!("/verify/{}", ANCHORED_HASH)).await;
+    let body: VerifyResponse = response.json();
+    assert!(body.cached);
+    account_mock.assert_hits(1);
+}
+
+#[tokio::test]
+async fn verify_caches_unverified_results_with_the_negative_ttl() {
+    let unanchored = "ab".repeat(32);
+    let horizon = MockServer::start();
+    mock_no_memo_anchors(&horizon);
+    let account = mock_anchored_account(&horizon, ANCHORED_HASH);
+    let mut state = test_state(&horizon.base_url());
+    state.cache_ttl = 3600;
+    state.cache_negative_ttl = 1;
+    let cache = state.cache.clone();
+    let server = TestServer::new(app(state)).unwrap();
+
+    let body: VerifyResponse = server
+        .get(&format!("/verify/{}", ANCHORED_HASH))
+        .await
+        .json();
+    assert!(body.verified);
+    let body: VerifyResponse = server.get(&format!("/verify/{}", unanchored)).await.json();
+    assert!(!body.verified);
+
+    let verified_ttl = cache.ttl(ANCHORED_HASH).await.unwrap().unwrap();
+    let negative_ttl = cache.ttl(&unanchored).await.unwrap().unwrap();
+    assert!(verified_ttl > Duration::from_secs(3500));
+    assert!(negative_ttl <= Duration::from_secs(1));
+
+    // Once the negative entry expires the unverified hash goes back to
+    // Horizon, while the verified one is still served from the cache.
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    account.assert_hits(2);
+    server
+        .get(&format!("/verify/{}", unanchored))
+        .await
+        .assert_status_ok();
+    account.assert_hits(3);
+    server
+        .get(&format!("/verify/{}", ANCHORED_HASH))
+        .await
+        .assert_status_ok();
+    account.assert_hits(3);
+}
+
+#[tokio::test]
+async fn verification_results_stay_cached_while_redis_is_down() {
+    let horizon = MockServer::start();
+    let account = mock_anchored_account(&horizon, ANCHORED_HASH);
+    horizon.mock(|when, then| {
+        when.method(GET).path("/");
+        then.status(200).json_body(json!({}));
+    });
+    let mut state = test_state(&horizon.base_url());
+    state.cache = Arc::new(CacheBackend::Layered(
+        FallbackCache::connect(
+            "redis://127.0.0.1:1",
+            InMemoryCache::new(),
+            Duration::from_secs(30),
+        )
+        .await,
+    ));
+    let server = TestServer::new(app(state)).unwrap();
+
+    for _ in 0..2 {
+        let response = server.get(&format!("/verify/{}", ANCHORED_HASH)).await;
+        response.assert_status_ok();
+        assert!(response.json::<VerifyResponse>().verified);
+    }
+    account.assert_hits(1);
+
+    // Health still reports the outage while requests are served from memory.
+    let health: serde_json::Value = server.get("/health").await.json();
+    assert_eq!(health["redis_connected"], false);
+    assert_eq!(health["status"], "degraded");
+}
+
+#[tokio::test]
+async fn deletes_during_a_redis_outage_are_not_undone_when_it_recovers() {
+    let horizon = MockServer::start();
+    let account = mock_anchored_account(&horizon, ANCHORED_HASH);
+    let redis = FakeRedis::start().await;
+    let mut state = test_state(&horizon.base_url());
+    state.cache = Arc::new(CacheBackend::Layered(
+        FallbackCache::connect(redis.url(), InMemoryCache::new(), Duration::ZERO).await,
+    ));
+    let write_behind = state.write_behind.clone();
+    let server = TestServer::new(app(state)).unwrap();
+    let verify_url = format!("/verify/{}", ANCHORED_HASH);
+
+    server.get(&verify_url).await.assert_status_ok();
+    assert!(redis.get(ANCHORED_HASH).is_some());
+
+    redis.set_down(true);
+    server
+        .delete(&format!("/cache/{}", ANCHORED_HASH))
+        .add_header(header::AUTHORIZATION, admin_auth(TEST_ADMIN_TOKEN))
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    assert!(redis.get(ANCHORED_HASH).is_some());
+
+    // Redis still holds the entry from before the outage; it must not be
+    // served once Redis is back.
+    redis.set_down(false);
+    server.get(&verify_url).await.assert_status_ok();
+    account.assert_hits(2);
+
+    // A fresh result that cannot reach Redis is queued for retry.
+    redis.set_down(true);
+    server
+        .delete(&format!("/cache/{}", ANCHORED_HASH))
+        .add_header(header::AUTHORIZATION, admin_auth(TEST_ADMIN_TOKEN))
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    server.get(&verify_url).await.assert_status_ok();
+    account.assert_hits(3);
+    assert_eq!(write_behind.len(), 1);
+}
+
+#[tokio::test]
+async fn verify_accepts_sha512_and_rejects_misrouted_sha256() {
+    let sha512 = "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+                  47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e";
+    let horizon = MockServer::start();
+    mock_anchored_account(&horizon, sha512);
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+
+    let response = server
+        .post("/verify")
+        .json(&json!({ "document_hash": sha512, "algorithm": "sha512" }))
+        .await;
+    response.assert_status_ok();
+    let body: VerifyResponse = response.json();
+    assert!(body.verified);
+
+    let response = server
+        .post("/verify")
+        .json(&json!({ "document_hash": ANCHORED_HASH, "algorithm": "sha512" }))
+        .await;
+    response.assert_status_bad_request();
+    let body: serde_json::Value = response.json();
+    assert!(body["error"]
+        .as_str()
+        .unwrap()
+        .contains("expected 128 characters, got 64"));
+}
+
+#[tokio::test]
+async fn proof_endpoint_returns_verifiable_signature() {
+    let horizon = MockServer::start();
+    mock_anchored_account(&horizon, ANCHORED_HASH);
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+
+    let response = server
+        .get(&format!("/verify/{}/proof", ANCHORED_HASH))
+        .await;
+    response.assert_status_ok();
+
+    let mut proof: VerificationProof = response.json();
+    assert_eq!(proof.hash, ANCHORED_HASH);
+    assert_eq!(proof.horizon_url, horizon.base_url());
+    assert!(proof.verify(TEST_WEBHOOK_SECRET));
+
+    proof.hash = proof.hash.replace('e', "f");
+    assert!(!proof.verify(TEST_WEBHOOK_SECRET));
+}
+
+#[tokio::test]
+async fn proof_validation_applies_configured_clock_skew() {
+    let proof_at = |offset: i64| {
+        VerificationProof::new_signed(
+            ANCHORED_HASH.to_string(),
+            Some(TX_ANCHOR.to_string()),
+            Some(42),
+            Some(chrono::Utc::now().timestamp() + offset),
+            "https://horizon-testnet.stellar.org".to_string(),
+            "testnet".to_string(),
+            TEST_WEBHOOK_SECRET,
+        )
+    };
+    let valid = |server: &TestServer, proof: VerificationProof| {
+        let request = server.post("/verify/proof").json(&proof);
+        async move { request.await.json::<serde_json::Value>()["valid"] == true }
+    };
+
+    let server = TestServer::new(app(test_state("http://127.0.0.1:1"))).unwrap();
+    assert!(valid(&server, proof_at(200)).await);
+    assert!(!valid(&server, proof_at(400)).await);
+    let mut forged = proof_at(0);
+    forged.ledger = Some(43);
+    assert!(!valid(&server, forged).await);
+
+    let mut state = test_state("http://127.0.0.1:1");
+    state.time = TimeConfig {
+        skew_tolerance_secs: 600,
+    };
+    let server = TestServer::new(app(state)).unwrap();
+    assert!(valid(&server, proof_at(400)).await);
+}
+
+/// `ANCHORED_HASH` as a base64 `MEMO_HASH`, the way Horizon returns it.
+const ANCHORED_HASH_MEMO: &str = "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=";
+
+const TX_ANCHOR: &str = "a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1a1";
+const TX_OTHER: &str = "b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2";
+const TX_MISSING: &str = "c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3c3";
+const TX_FORGED: &str = "d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4d4";
+
+/// `tx`'s operations, none of which touch the anchor entry.
+fn mock_unrelated_operations<'a>(server: &'a MockServer, tx: &str) -> httpmock::Mock<'a> {
+    server.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/transactions/{}/operations", tx));
+        then.status(200)
+            .json_body(json!({ "_embedded": { "records": [] } }));
+    })
+}
+
+fn mock_transaction<'a>(
+    server: &'a MockServer,
+    id: &str,
+    memo_type: &str,
+    memo: &str,
+) -> httpmock::Mock<'a> {
+    mock_transaction_from(server, id, TEST_ACCOUNT_ID, memo_type, memo)
+}
+
+fn mock_transaction_from<'a>(
+    server: &'a MockServer,
+    id: &str,
+    source_account: &str,
+    memo_type: &str,
+    memo: &str,
+) -> httpmock::Mock<'a> {
+    server.mock(|when, then| {
+        when.method(GET).path(format!("/transactions/{}", id));
+        then.status(200).json_body(json!({
+            "hash": id,
+            "ledger": 42,
+            "created_at": "2024-01-01T00:00:00Z",
+            "source_account": source_account,
+            "memo_type": memo_type,
+            "memo": memo,
+            "successful": true
+        }));
+    })
+}
+
+#[tokio::test]
+async fn targeted_verify_matches_second_transaction_and_caches_lookups() {
+    let horizon = MockServer::start();
+    let first = mock_transaction(&horizon, TX_OTHER, "text", "TRANSFER:abc");
+    mock_unrelated_operations(&horizon, TX_OTHER);
+    let second = mock_transaction(&horizon, TX_ANCHOR, "hash", ANCHORED_HASH_MEMO);
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+    let request = json!({
+        "hash": ANCHORED_HASH,
+        "transaction_ids": [TX_OTHER, TX_ANCHOR]
+    });
+
+    for _ in 0..2 {
+        let response = server.post("/verify/targeted").json(&request).await;
+        response.assert_status_ok();
+        let body: TargetedVerifyResponse = response.json();
+        assert!(body.verified);
+        assert_eq!(body.matching_transaction_ids, vec![TX_ANCHOR]);
+        assert!(!body.results[0].matched);
+        assert!(body.results[1].matched);
+    }
+
+    first.assert_hits(1);
+    second.assert_hits(1);
+}
+
+#[tokio::test]
+async fn verify_uses_transaction_id_hint_without_scanning() {
+    let horizon = MockServer::start();
+    mock_transaction(&horizon, TX_ANCHOR, "hash", ANCHORED_HASH_MEMO);
+    mock_transaction(&horizon, TX_OTHER, "text", "TRANSFER:abc");
+    mock_unrelated_operations(&horizon, TX_OTHER);
+    let account = horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200).json_body(funded_account(json!({})));
+    });
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+
+    let response = server
+        .post("/verify")
+        .json(&json!({ "document_hash": ANCHORED_HASH, "transaction_id": TX_ANCHOR }))
+        .await;
+    response.assert_status_ok();
+    let body: VerifyResponse = response.json();
+    assert!(body.verified);
+    assert_eq!(body.transaction_id.as_deref(), Some(TX_ANCHOR));
+
+    let response = server
+        .post("/verify")
+        .json(&json!({ "document_hash": ANCHORED_HASH, "transaction_id": TX_OTHER }))
+        .await;
+    response.assert_status_ok();
+    let body: VerifyResponse = response.json();
+    assert!(!body.verified);
+    assert!(body.error.unwrap().contains("memo mismatch"));
+    // Only the match is checked for revocation.
+    account.assert_hits(1);
+}
+
+#[tokio::test]
+async fn transaction_id_hint_rejects_transaction_from_foreign_account() {
+    let horizon = MockServer::start();
+    mock_transaction_from(
+        &horizon,
+        TX_FORGED,
+        "GFOREIGNFOREIGNFOREIGNFOREIGNFOREIGNFOREIGNFOREIGNFOREIG",
+        "hash",
+        ANCHORED_HASH_MEMO,
+    );
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+
+    let response = server
+        .post("/verify")
+        .json(&json!({ "document_hash": ANCHORED_HASH, "transaction_id": TX_FORGED }))
+        .await;
+    response.assert_status_ok();
+    let body: VerifyResponse = response.json();
+    assert!(!body.verified);
+    assert!(body
+        .error
+        .unwrap()
+        .contains("not submitted by the anchoring account"));
+}
+
+#[tokio::test]
+async fn transaction_id_hint_reports_revoked_hash() {
+    let horizon = MockServer::start();
+    mock_transaction(&horizon, TX_ANCHOR, "hash", ANCHORED_HASH_MEMO);
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200).json_body(funded_account(json!({
+            build_revocation_key(ANCHORED_HASH): "cmV2b2tlZA=="
+        })));
+    });
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}/operations", TEST_ACCOUNT_ID));
+        then.status(200).json_body(json!({
+            "_embedded": { "records": [{
+                "id": "op-2",
+                "transaction_hash": "tx-revoke",
+                "created_at": "2024-04-01T00:00:00Z",
+                "type": "manage_data",
+                "name": build_revocation_key(ANCHORED_HASH),
+                "value": "cmV2b2tlZA=="
+            }]}
+        }));
+    });
+    mock_transaction(&horizon, "tx-revoke", "none", "");
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+
+    let response = server
+        .post("/verify")
+        .json(&json!({ "document_hash": ANCHORED_HASH, "transaction_id": TX_ANCHOR }))
+        .await;
+    response.assert_status_ok();
+    let body: VerifyResponse = response.json();
+    assert!(body.verified);
+    assert!(body.revoked);
+    assert_eq!(body.revoked_transaction_id.as_deref(), Some("tx-revoke"));
+    assert!(body.revoked_at.is_some());
+}
+
+#[tokio::test]
+async fn targeted_verify_reports_no_match() {
+    let horizon = MockServer::start();
+    mock_transaction(&horizon, TX_OTHER, "text", "TRANSFER:abc");
+    mock_unrelated_operations(&horizon, TX_OTHER);
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+
+    let response = server
+        .post("/verify/targeted")
+        .json(&json!({
+            "hash": ANCHORED_HASH,
+            "transaction_ids": [TX_OTHER, TX_MISSING]
+        }))
+        .await;
+    response.assert_status_ok();
+    let body: TargetedVerifyResponse = response.json();
+    assert!(!body.verified);
+    assert!(body.matching_transaction_ids.is_empty());
+    assert!(body.results[0].error.is_none());
+    assert!(body.results[1].error.is_some());
+}
+
+#[tokio::test]
+async fn targeted_verify_matches_manage_data_anchor_from_anchoring_account_only() {
+    let horizon = MockServer::start();
+    mock_transaction(&horizon, TX_ANCHOR, "none", "");
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/transactions/{}/operations", TX_ANCHOR));
+        then.status(200).json_body(json!({
+            "_embedded": { "records": [{
+                "id": "op-1",
+                "transaction_hash": TX_ANCHOR,
+                "created_at": "2024-01-01T00:00:00Z",
+                "type": "manage_data",
+                "source_account": TEST_ACCOUNT_ID,
+                "name": build_data_key(ANCHORED_HASH),
+                "value": "YW5jaG9yZWQ="
+            }]}
+        }));
+    });
+    mock_transaction_from(
+        &horizon,
+        TX_FORGED,
+        "GFOREIGNFOREIGNFOREIGNFOREIGNFOREIGNFOREIGNFOREIGNFOREIG",
+        "hash",
+        ANCHORED_HASH_MEMO,
+    );
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+
+    let response = server
+        .post("/verify/targeted")
+        .json(&json!({
+            "hash": ANCHORED_HASH,
+            "transaction_ids": [TX_FORGED, TX_ANCHOR.to_uppercase()]
+        }))
+        .await;
+    response.assert_status_ok();
+    let body: TargetedVerifyResponse = response.json();
+    assert!(body.verified);
+    assert_eq!(body.matching_transaction_ids, vec![TX_ANCHOR]);
+    assert!(!body.results[0].matched);
+}
+
+#[tokio::test]
+async fn targeted_verify_rejects_malformed_transaction_ids() {
+    let horizon = MockServer::start();
+    let lookups = horizon.mock(|when, then| {
+        when.method(GET);
+        then.status(404);
+    });
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+
+    let response = server
+        .post("/verify/targeted")
+        .json(&json!({
+            "hash": ANCHORED_HASH,
+            "transaction_ids": [TX_ANCHOR, "../accounts/GABC"]
+        }))
+        .await;
+    response.assert_status_bad_request();
+    lookups.assert_hits(0);
+}
+
+#[tokio::test]
+async fn targeted_verify_accepts_sha512_hashes() {
+    let sha512 = "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+                  47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e";
+    let horizon = MockServer::start();
+    mock_transaction(&horizon, TX_OTHER, "text", "TRANSFER:abc");
+    mock_unrelated_operations(&horizon, TX_OTHER);
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+
+    let response = server
+        .post("/verify/targeted")
+        .json(&json!({ "hash": sha512, "transaction_ids": [TX_OTHER] }))
+        .await;
+    response.assert_status_ok();
+    let body: TargetedVerifyResponse = response.json();
+    assert!(!body.verified);
+}
+
+#[tokio::test]
+async fn evidence_is_captured_cached_and_returned_on_request() {
+    let horizon = MockServer::start();
+    mock_anchored_account(&horizon, ANCHORED_HASH);
+    mock_anchor_evidence(&horizon, ANCHORED_HASH, "tx-anchor");
+
+    let state = test_state(&horizon.base_url());
+    let cache = state.cache.clone();
+    let server = TestServer::new(app(state)).unwrap();
+
+    let response = server.get(&format!("/verify/{}", ANCHORED_HASH)).await;
+    let body: serde_json::Value = response.json();
+    assert!(body.get("evidence").is_none());
+    assert_eq!(body["transaction_id"], "tx-anchor");
+
+    let stored: VerifyResponse = cache.get(ANCHORED_HASH).await.unwrap().unwrap();
+    let evidence = stored.evidence.expect("evidence should be cached");
+    assert_eq!(evidence.transaction_id, "tx-anchor");
+    assert_eq!(evidence.ledger, 4242);
+    assert_eq!(evidence.created_at, "2024-03-01T12:00:00Z");
+    assert_eq!(evidence.source_account, TEST_ACCOUNT_ID);
+    assert_eq!(evidence.memo_type, "none");
+    assert_eq!(evidence.memo, None);
+    assert!(evidence.successful);
+
+    // Served from cache with the evidence section requested.
+    let response = server
+        .get(&format!("/verify/{}?include=evidence", ANCHORED_HASH))
+        .await;
+    let body: VerifyResponse = response.json();
+    assert_eq!(body.evidence.unwrap().ledger, 4242);
+}
+
+#[tokio::test]
+async fn revoke_fires_hash_revoked_webhook_and_invalidates_cache() {
+    let horizon = MockServer::start();
+    mock_anchored_account(&horizon, ANCHORED_HASH);
+    mock_anchor_evidence(&horizon, ANCHORED_HASH, "tx-anchor");
+    horizon.mock(|when, then| {
+        when.method(POST).path("/transactions");
+        then.status(200).json_body(json!({
+            "hash": "tx-revoke",
+            "ledger": 77,
+            "created_at": "2024-03-02T12:00:00Z"
+        }));
+    });
+
+    let receiver = MockServer::start();
+    let webhook_mock = receiver.mock(|when, then| {
+        when.method(POST)
+            .path("/hooks")
+            .header_exists("X-Webhook-Signature")
+            .json_body_partial(
+                json!({
+                    "event": "hash_revoked",
+                    "data": {
+                        "hash": ANCHORED_HASH,
+                        "reason": "superseded",
+                        "revoked_by": TEST_ACCOUNT_ID,
+                        "transaction_id": "tx-revoke"
+                    }
+                })
+                .to_string(),
+            );
+        then.status(200);
+    });
+
+    let state = test_state_with_webhooks(&horizon.base_url(), vec![receiver.url("/hooks")]);
+    let cache = state.cache.clone();
+    let events = state.events.clone();
+    cache
+        .set(
+            ANCHORED_HASH,
+            &json!({ "verified": true, "transaction_id": null, "timestamp": null, "cached": false }),
+            3600,
+        )
+        .await
+        .unwrap();
+    let server = TestServer::new(app(state)).unwrap();
+
+    let response = server
+        .post("/revoke")
+        .add_header(
+            header::HeaderName::from_static("x-actor"),
+            HeaderValue::from_static("registrar-7"),
+        )
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "reason": "superseded",
+            "revoked_by": TEST_ACCOUNT_ID
+        }))
+        .await;
+    response.assert_status_ok();
+
+    // Delivery happens in the background.
+    for _ in 0..50 {
+        if webhook_mock.hits() > 0 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    webhook_mock.assert_hits(1);
+    assert!(cache
+        .get::<VerifyResponse>(ANCHORED_HASH)
+        .await
+        .unwrap()
+        .is_none());
+
+    let recorded = events.list(ANCHORED_HASH).await.unwrap();
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(recorded[0].sequence, 1);
+    assert_eq!(recorded[0].event_type, "Revoked");
+    assert_eq!(recorded[0].actor, "registrar-7");
+    assert_eq!(recorded[0].data["revoked_by"], TEST_ACCOUNT_ID);
+    assert_eq!(recorded[0].data["transaction_id"], "tx-revoke");
+}
+
+#[tokio::test]
+async fn revoke_returns_not_found_for_unanchored_hash() {
+    let horizon = MockServer::start();
+    mock_no_memo_anchors(&horizon);
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200)
+            .json_body(json!({ "sequence": "100", "data": {} }));
+    });
+    let submit = horizon.mock(|when, then| {
+        when.method(POST).path("/transactions");
+        then.status(200);
+    });
+
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+    let response = server
+        .post("/revoke")
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "reason": "superseded",
+            "revoked_by": TEST_ACCOUNT_ID
+        }))
+        .await;
+
+    response.assert_status_not_found();
+    let body: ApiErrorBody = response.json();
+    assert_eq!(body.error, "Document hash not found");
+    assert_eq!(body.code, "NOT_FOUND");
+    submit.assert_hits(0);
+}
+
+#[tokio::test]
+async fn revoke_returns_conflict_for_already_revoked_hash() {
+    let horizon = MockServer::start();
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200).json_body(funded_account(json!({
+            build_data_key(ANCHORED_HASH): "YW5jaG9yZWQ=",
+            build_revocation_key(ANCHORED_HASH): "cmV2b2tlZA=="
+        })));
+    });
+    mock_anchor_evidence(&horizon, ANCHORED_HASH, "tx-anchor");
+    let submit = horizon.mock(|when, then| {
+        when.method(POST).path("/transactions");
+        then.status(200);
+    });
+
+    let state = test_state(&horizon.base_url());
+    let events = state.events.clone();
+    let server = TestServer::new(app(state)).unwrap();
+    let response = server
+        .post("/revoke")
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "reason": "superseded",
+            "revoked_by": TEST_ACCOUNT_ID
+        }))
+        .await;
+
+    response.assert_status(axum::http::StatusCode::CONFLICT);
+    let body: ApiErrorBody = response.json();
+    assert_eq!(body.error, "Document hash is already revoked");
+    assert_eq!(body.code, "CONFLICT");
+    submit.assert_hits(0);
+    assert!(events.list(ANCHORED_HASH).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn validation_failures_are_counted_by_kind() {
+    let state = test_state("http://127.0.0.1:1");
+    let metrics = state.metrics.clone();
+    let server = TestServer::new(app(state)).unwrap();
+
+    server
+        .get("/verify/abc123")
+        .await
+        .assert_status_bad_request();
+    server
+        .post("/verify/batch")
+        .json(&json!({ "hashes": ["abc"] }))
+        .await
+        .assert_status_ok();
+
+    assert_eq!(metrics.validation_failures("wrong_length"), 2);
+    assert_eq!(metrics.validation_failures("invalid_character"), 0);
+}
+
+#[tokio::test]
+async fn sha256_only_deployment_rejects_sha512_hashes() {
+    let horizon = MockServer::start();
+    let account = horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200)
+            .json_body(json!({ "sequence": "100", "data": {} }));
+    });
+    let mut state = test_state(&horizon.base_url());
+    state.accepted_algorithms = Arc::new(vec![HashAlgorithm::SHA256]);
+    let server = TestServer::new(app(state)).unwrap();
+    let sha512 = "a".repeat(128);
+    let expected = "hash algorithm sha512 is not accepted by this deployment";
+
+    let response = server.get(&format!("/verify/{}", sha512)).await;
+    response.assert_status_bad_request();
+    assert_eq!(response.json::<serde_json::Value>()["error"], expected);
+
+    let response = server
+        .post("/submit")
+        .json(&json!({
+            "document_hash": sha512,
+            "document_id": "doc-1",
+            "submitter": TEST_ACCOUNT_ID
+        }))
+        .await;
+    response.assert_status_bad_request();
+    assert_eq!(response.json::<serde_json::Value>()["error"], expected);
+
+    let response = server
+        .post("/verify/batch")
+        .json(&json!({ "hashes": [sha512] }))
+        .await;
+    response.assert_status_ok();
+    assert_eq!(
+        response.json::<serde_json::Value>()["results"][0]["error"],
+        expected
+    );
+    account.assert_hits(0);
+}
+
+#[tokio::test]
+async fn batch_verify_accepts_sha512_while_submit_requires_sha256() {
+    let horizon = MockServer::start();
+    mock_no_memo_anchors(&horizon);
+    let sha512 = "c".repeat(128);
+    let account = horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200).json_body(funded_account(json!({
+            build_data_key(&sha512): "YW5jaG9yZWQ="
+        })));
+    });
+    let submit = horizon.mock(|when, then| {
+        when.method(POST).path("/transactions");
+        then.status(200);
+    });
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+
+    let response = server
+        .post("/verify/batch")
+        .json(&json!({ "hashes": [sha512] }))
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["results"][0]["verified"], true);
+    assert!(body["results"][0]["error"].is_null());
+    account.assert_hits(1);
+
+    let expected = "hash algorithm sha512 is unsupported for this endpoint";
+    let response = server
+        .post("/submit")
+        .json(&json!({
+            "document_hash": sha512,
+            "document_id": "doc-1",
+            "submitter": TEST_ACCOUNT_ID
+        }))
+        .await;
+    response.assert_status_bad_request();
+    assert_eq!(response.json::<serde_json::Value>()["error"], expected);
+
+    let response = server
+        .post("/submit/batch")
+        .json(&json!({ "hashes": [sha512] }))
+        .await;
+    response.assert_status_ok();
+    assert_eq!(
+        response.json::<serde_json::Value>()["results"][0]["error"],
+        expected
+    );
+    submit.assert_hits(0);
+}
+
+#[tokio::test]
+async fn verify_reports_revocation_recorded_on_chain() {
+    let horizon = MockServer::start();
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200).json_body(json!({
+            "sequence": "100",
+            "data": {
+                build_data_key(ANCHORED_HASH): "YW5jaG9yZWQ=",
+                build_revocation_key(ANCHORED_HASH): "cmV2b2tlZA=="
+            }
+        }));
+    });
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}/operations", TEST_ACCOUNT_ID));
+        then.status(200).json_body(json!({
+            "_embedded": { "records": [
+                {
+                    "id": "op-2",
+                    "transaction_hash": "tx-revoke",
+                    "created_at": "2024-04-01T12:00:00Z",
+                    "type": "manage_data",
+                    "name": build_revocation_key(ANCHORED_HASH),
+                    "value": "cmV2b2tlZA=="
+                },
+                {
+                    "id": "op-1",
+                    "transaction_hash": "tx-anchor",
+                    "created_at": "2024-03-01T12:00:00Z",
+                    "type": "manage_data",
+                    "name": build_data_key(ANCHORED_HASH),
+                    "value": "YW5jaG9yZWQ="
+                }
+            ]}
+        }));
+    });
+    for (tx, created_at) in [
+        ("tx-anchor", "2024-03-01T12:00:00Z"),
+        ("tx-revoke", "2024-04-01T12:00:00Z"),
+    ] {
+        horizon.mock(|when, then| {
+            when.method(GET).path(format!("/transactions/{}", tx));
+            then.status(200).json_body(json!({
+                "hash": tx,
+                "ledger": 4242,
+                "created_at": created_at,
+                "source_account": TEST_ACCOUNT_ID,
+                "memo_type": "none",
+                "successful": true
+            }));
+        });
+    }
+
+    let state = test_state(&horizon.base_url());
+    let cache = state.cache.clone();
+    let server = TestServer::new(app(state)).unwrap();
+
+    let body: serde_json::Value = server
+        .get(&format!("/verify/{}", ANCHORED_HASH))
+        .await
+        .json();
+    assert_eq!(body["verified"], true);
+    assert_eq!(body["transaction_id"], "tx-anchor");
+    assert_eq!(body["revoked"], true);
+    assert_eq!(body["revoked_transaction_id"], "tx-revoke");
+    assert_eq!(
+        body["revoked_at"],
+        chrono::DateTime::parse_from_rfc3339("2024-04-01T12:00:00Z")
+            .unwrap()
+            .timestamp()
+    );
+
+    let cached: VerifyResponse = cache.get(ANCHORED_HASH).await.unwrap().unwrap();
+    assert!(cached.revoked);
+    assert_eq!(cached.revoked_transaction_id.as_deref(), Some("tx-revoke"));
+}
+
+#[tokio::test]
+async fn metrics_bucket_verifications_by_document_age() {
+    let recent_hash = ANCHORED_HASH;
+    let old_hash = "d".repeat(64);
+    let recent_created_at = (chrono::Utc::now() - chrono::Duration::hours(2)).to_rfc3339();
+
+    let horizon = MockServer::start();
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200).json_body(json!({
+            "sequence": "100",
+            "data": {
+                build_data_key(recent_hash): "YW5jaG9yZWQ=",
+                build_data_key(&old_hash): "YW5jaG9yZWQ="
+            }
+        }));
+    });
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}/operations", TEST_ACCOUNT_ID));
+        then.status(200).json_body(json!({
+            "_embedded": { "records": [
+                {
+                    "id": "op-2", "transaction_hash": "tx-recent",
+                    "created_at": recent_created_at, "type": "manage_data",
+                    "name": build_data_key(recent_hash), "value": "YW5jaG9yZWQ="
+                },
+                {
+                    "id": "op-1", "transaction_hash": "tx-old",
+                    "created_at": "2020-01-01T00:00:00Z", "type": "manage_data",
+                    "name": build_data_key(&old_hash), "value": "YW5jaG9yZWQ="
+                }
+            ]}
+        }));
+    });
+    for (tx, created_at) in [
+        ("tx-recent", recent_created_at.as_str()),
+        ("tx-old", "2020-01-01T00:00:00Z"),
+    ] {
+        horizon.mock(|when, then| {
+            when.method(GET).path(format!("/transactions/{}", tx));
+            then.status(200).json_body(json!({
+                "hash": tx, "ledger": 1, "created_at": created_at,
+                "source_account": TEST_ACCOUNT_ID, "memo_type": "none", "successful": true
+            }));
+        });
+    }
+
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+    server
+        .get(&format!("/verify/{}", recent_hash))
+        .await
+        .assert_status_ok();
+    server
+        .get(&format!("/verify/{}", old_hash))
+        .await
+        .assert_status_ok();
+
+    let metrics = server.get("/metrics").await.text();
+    assert!(metrics.contains(r#"verifications_by_age_total{bucket="lt_1d"} 1"#));
+    assert!(metrics.contains(r#"verifications_by_age_total{bucket="gt_365d"} 1"#));
+    assert!(metrics.contains("verification_document_age_days_count 2"));
+}
+
+async fn raw_get<S: AsyncRead + AsyncWrite + Unpin>(mut stream: S, path: &str) -> String {
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        path
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_serves_on_ipv6_and_unix_socket() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket_path = dir.path().join("verifier.sock");
+
+    let mut listeners = server::bind_tcp(&["[::1]:0".parse().unwrap()])
+        .await
+        .unwrap();
+    listeners.push(server::bind_unix(&socket_path, 0o600).unwrap());
+    let tcp_addr = match &listeners[0] {
+        server::BoundListener::Tcp(listener) => listener.local_addr().unwrap(),
+        _ => unreachable!(),
+    };
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+    let handle = tokio::spawn(server::serve_all(
+        app(test_state("http://127.0.0.1:1")),
+        listeners,
+        shutdown_rx,
+    ));
+
+    let tcp = tokio::net::TcpStream::connect(tcp_addr).await.unwrap();
+    let response = raw_get(tcp, "/metrics").await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+
+    let uds = tokio::net::UnixStream::connect(&socket_path).await.unwrap();
+    let response = raw_get(uds, "/metrics").await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+
+    shutdown_tx.send(true).unwrap();
+    handle.await.unwrap().unwrap();
+    assert!(!socket_path.exists());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn unix_socket_is_created_with_mode_and_refuses_to_replace_other_files() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let socket_path = dir.path().join("verifier.sock");
+    let listener = server::bind_unix(&socket_path, 0o600).unwrap();
+    let mode = std::fs::metadata(&socket_path)
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o600);
+    drop(listener);
+    // A stale socket from a previous run is replaced.
+    server::bind_unix(&socket_path, 0o600).unwrap();
+
+    let file_path = dir.path().join("not-a-socket");
+    std::fs::write(&file_path, "keep me").unwrap();
+    let err = server::bind_unix(&file_path, 0o600).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "keep me");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn unix_socket_drains_in_flight_requests_before_removing_the_socket() {
+    let horizon = MockServer::start();
+    mock_no_memo_anchors(&horizon);
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200)
+            .json_body(funded_account(json!({})))
+            .delay(Duration::from_millis(300));
+    });
+    let dir = tempfile::tempdir().unwrap();
+    let socket_path = dir.path().join("verifier.sock");
+    let listeners = vec![server::bind_unix(&socket_path, 0o600).unwrap()];
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+    let handle = tokio::spawn(server::serve_all_with_drain(
+        app(test_state(&horizon.base_url())),
+        listeners,
+        shutdown_rx,
+        Duration::from_secs(5),
+    ));
+
+    let uds = tokio::net::UnixStream::connect(&socket_path).await.unwrap();
+    let path = format!("/verify/{}", ANCHORED_HASH);
+    let request = tokio::spawn(async move { raw_get(uds, &path).await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    shutdown_tx.send(true).unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(socket_path.exists());
+
+    let response = request.await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    assert!(handle.await.unwrap().unwrap());
+    assert!(!socket_path.exists());
+}
+
+#[tokio::test]
+async fn slow_request_times_out_with_json_error() {
+    let horizon = MockServer::start();
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200)
+            .json_body(funded_account(json!({})))
+            .delay(Duration::from_secs(2));
+    });
+    let mut state = test_state(&horizon.base_url());
+    state.request_timeout = Duration::from_millis(200);
+    let server = TestServer::new(app(state)).unwrap();
+
+    let response = server.get(&format!("/verify/{}", ANCHORED_HASH)).await;
+    response.assert_status(axum::http::StatusCode::REQUEST_TIMEOUT);
+    let body: ApiErrorBody = response.json();
+    assert_eq!(body.code, "TIMEOUT");
+}
+
+#[tokio::test]
+async fn client_deadline_header_returns_gateway_timeout() {
+    let horizon = MockServer::start();
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200)
+            .json_body(funded_account(json!({})))
+            .delay(Duration::from_secs(2));
+    });
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+
+    let started = std::time::Instant::now();
+    let response = server
+        .get(&format!("/verify/{}", ANCHORED_HASH))
+        .add_header(
+            header::HeaderName::from_static("x-request-timeout-ms"),
+            HeaderValue::from_static("250"),
+        )
+        .await;
+    let elapsed = started.elapsed();
+    response.assert_status(axum::http::StatusCode::GATEWAY_TIMEOUT);
+    let body: ApiErrorBody = response.json();
+    assert_eq!(body.code, "DEADLINE_EXCEEDED");
+    assert!(body.error.contains("250 ms"));
+    assert!(elapsed < Duration::from_secs(1), "took {:?}", elapsed);
+
+    let response = server
+        .get(&format!("/verify/{}", ANCHORED_HASH))
+        .add_header(
+            header::HeaderName::from_static("x-request-timeout-ms"),
+            HeaderValue::from_static("soon"),
+        )
+        .await;
+    response.assert_status_bad_request();
+}
+
+fn admin_auth(token: &str) -> HeaderValue {
+    HeaderValue::from_str(&format!("Bearer {}", token)).unwrap()
+}
+
+#[tokio::test]
+async fn cache_invalidation_requires_the_admin_token() {
+    let state = test_state("http://127.0.0.1:1");
+    let server = TestServer::new(app(state)).unwrap();
+    let url = format!("/cache/{}", ANCHORED_HASH);
+
+    let response = server.delete(&url).await;
+    response.assert_status(axum::http::StatusCode::UNAUTHORIZED);
+    assert_eq!(response.json::<ApiErrorBody>().code, "UNAUTHORIZED");
+
+    server
+        .delete(&url)
+        .add_header(header::AUTHORIZATION, admin_auth("wrong-token"))
+        .await
+        .assert_status(axum::http::StatusCode::UNAUTHORIZED);
+    server
+        .delete("/cache?prefix=status:")
+        .add_header(header::AUTHORIZATION, admin_auth("wrong-token"))
+        .await
+        .assert_status(axum::http::StatusCode::UNAUTHORIZED);
+    let response = server
+        .get(&format!("/admin/cache/history:{}", ANCHORED_HASH))
+        .await;
+    response.assert_status(axum::http::StatusCode::UNAUTHORIZED);
+    assert_eq!(response.json::<ApiErrorBody>().code, "UNAUTHORIZED");
+
+    let mut state = test_state("http://127.0.0.1:1");
+    state.admin_token = None;
+    let server = TestServer::new(app(state)).unwrap();
+    server
+        .delete(&url)
+        .add_header(header::AUTHORIZATION, admin_auth(TEST_ADMIN_TOKEN))
+        .await
+        .assert_status(axum::http::StatusCode::SERVICE_UNAVAILABLE);
+    server
+        .get(&format!("/admin/cache/history:{}", ANCHORED_HASH))
+        .add_header(header::AUTHORIZATION, admin_auth(TEST_ADMIN_TOKEN))
+        .await
+        .assert_status(axum::http::StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn invalidating_a_cached_verification_forces_a_stellar_recheck() {
+    let horizon = MockServer::start();
+    let account = mock_anchored_account(&horizon, ANCHORED_HASH);
+    let state = test_state(&horizon.base_url());
+    let cache = state.cache.clone();
+    let server = TestServer::new(app(state)).unwrap();
+    let verify_url = format!("/verify/{}", ANCHORED_HASH);
+
+    server.get(&verify_url).await.assert_status_ok();
+    server.get(&verify_url).await.assert_status_ok();
+    account.assert_hits(1);
+
+    server
+        .delete(&format!("/cache/{}", ANCHORED_HASH.to_uppercase()))
+        .add_header(header::AUTHORIZATION, admin_auth(TEST_ADMIN_TOKEN))
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    assert!(cache.get_raw(ANCHORED_HASH).await.unwrap().is_none());
+
+    server.get(&verify_url).await.assert_status_ok();
+    account.assert_hits(2);
+
+    server
+        .delete("/cache/not-a-hash")
+        .add_header(header::AUTHORIZATION, admin_auth(TEST_ADMIN_TOKEN))
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn cache_prefix_purge_deletes_only_matching_namespace_keys() {
+    let other = "ab".repeat(32);
+    let state = test_state("http://127.0.0.1:1");
+    let cache = state.cache.clone();
+    for key in [
+        format!("status:{}", ANCHORED_HASH),
+        format!("status:{}", other),
+        format!("history:{}", ANCHORED_HASH),
+        ANCHORED_HASH.to_string(),
+        "status:e3b0-not-a-hash".to_string(),
+    ] {
+        cache.set_raw(&key, "{}", 600).await.unwrap();
+    }
+    let server = TestServer::new(app(state)).unwrap();
+
+    let response = server
+        .delete("/cache?prefix=status:e3b0")
+        .add_header(header::AUTHORIZATION, admin_auth(TEST_ADMIN_TOKEN))
+        .await;
+    response.assert_status_ok();
+    let body: CachePurgeResponse = response.json();
+    assert_eq!(body.deleted, 1);
+    assert!(cache
+        .get_raw(&format!("status:{}", ANCHORED_HASH))
+        .await
+        .unwrap()
+        .is_none());
+    assert!(cache
+        .get_raw(&format!("status:{}", other))
+        .await
+        .unwrap()
+        .is_some());
+    assert!(cache
+        .get_raw("status:e3b0-not-a-hash")
+        .await
+        .unwrap()
+        .is_some());
+
+    let body: CachePurgeResponse = server
+        .delete("/cache?prefix=e3")
+        .add_header(header::AUTHORIZATION, admin_auth(TEST_ADMIN_TOKEN))
+        .await
+        .json();
+    assert_eq!(body.deleted, 1);
+    assert!(cache.get_raw(ANCHORED_HASH).await.unwrap().is_none());
+    assert!(cache
+        .get_raw(&format!("history:{}", ANCHORED_HASH))
+        .await
+        .unwrap()
+        .is_some());
+
+    for prefix in ["", "events:", "transfer:"] {
+        server
+            .delete(&format!("/cache?prefix={}", prefix))
+            .add_header(header::AUTHORIZATION, admin_auth(TEST_ADMIN_TOKEN))
+            .await
+            .assert_status_bad_request();
+    }
+}
+
+#[tokio::test]
+async fn health_reports_draining_once_shutdown_begins() {
+    let state = test_state("http://127.0.0.1:1");
+    let draining = state.draining.clone();
+    let server = TestServer::new(app(state)).unwrap();
+
+    server.get("/health").await.assert_status_ok();
+
+    draining.store(true, Ordering::SeqCst);
+    let response = server.get("/health").await;
+    response.assert_status(axum::http::StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.json::<serde_json::Value>()["status"], "draining");
+}
+
+#[tokio::test]
+async fn shutdown_keeps_serving_draining_health_until_prestop_elapses() {
+    let state = test_state("http://127.0.0.1:1");
+    let draining = state.draining.clone();
+    let listeners = server::bind_tcp(&["127.0.0.1:0".parse().unwrap()])
+        .await
+        .unwrap();
+    let addr = match &listeners[0] {
+        server::BoundListener::Tcp(listener) => listener.local_addr().unwrap(),
+        _ => unreachable!(),
+    };
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+    let serving = tokio::spawn(server::serve_all_with_drain(
+        app(state),
+        listeners,
+        shutdown_rx,
+        Duration::from_secs(5),
+    ));
+
+    let started = std::time::Instant::now();
+    let prestop = Duration::from_millis(300);
+    let shutdown = tokio::spawn(async move {
+        server::begin_shutdown(&draining, prestop, &shutdown_tx).await;
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // Still accepting during the pre-stop window, but failing readiness.
+    let tcp = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let response = raw_get(tcp, "/health").await;
+    assert!(response.starts_with("HTTP/1.1 503"), "{}", response);
+    assert!(response.contains("draining"), "{}", response);
+
+    shutdown.await.unwrap();
+    assert!(serving.await.unwrap().unwrap());
+    assert!(started.elapsed() >= prestop);
+    assert!(tokio::net::TcpStream::connect(addr).await.is_err());
+}
+
+async fn record_anchor(state: &AppState, hash: &str, ledger: u32, anchored_at: i64) {
+    state
+        .events
+        .append_unchecked(Event::new(
+            hash.to_string(),
+            ANCHORED_EVENT.to_string(),
+            json!({ "transaction_id": "tx", "ledger": ledger, "anchored_at": anchored_at }),
+            TEST_ACCOUNT_ID.to_string(),
+        ))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn public_recent_lists_truncated_hashes_newest_first() {
+    let state = test_state("http://127.0.0.1:1");
+    record_anchor(&state, ANCHORED_HASH, 100, 1_700_000_000).await;
+    record_anchor(&state, &"ab".repeat(32), 101, 1_700_000_100).await;
+    let server = TestServer::new(app(state)).unwrap();
+
+    let response = server.get("/public/recent").await;
+    response.assert_status_ok();
+    assert_eq!(response.header(header::CACHE_CONTROL), "public, max-age=30");
+    let body: RecentAnchorsResponse = response.json();
+    assert_eq!(body.anchors.len(), 2);
+    assert_eq!(body.anchors[0].hash, "abababab...abab");
+    assert_eq!(body.anchors[0].ledger, Some(101));
+    assert_eq!(body.anchors[1].hash, "e3b0c442...b855");
+    assert_eq!(body.anchors[1].anchored_at, Some(1_700_000_000));
+    assert!(!response.text().contains(ANCHORED_HASH));
+}
+
+#[tokio::test]
+async fn public_recent_enforces_limit_bounds() {
+    let state = test_state("http://127.0.0.1:1");
+    record_anchor(&state, ANCHORED_HASH, 100, 1_700_000_000).await;
+    record_anchor(&state, &"ab".repeat(32), 101, 1_700_000_100).await;
+    let server = TestServer::new(app(state)).unwrap();
+
+    let response = server.get("/public/recent?limit=1").await;
+    response.assert_status_ok();
+    assert_eq!(response.json::<RecentAnchorsResponse>().anchors.len(), 1);
+
+    for limit in ["0", "51", "many"] {
+        let response = server.get(&format!("/public/recent?limit={}", limit)).await;
+        response.assert_status_bad_request();
+        assert_eq!(response.json::<ApiErrorBody>().code, "VALIDATION");
+    }
+}
+
+#[tokio::test]
+async fn public_recent_is_not_found_when_disabled() {
+    let mut state = test_state("http://127.0.0.1:1");
+    state.public_recent = None;
+    let server = TestServer::new(app(state)).unwrap();
+
+    server.get("/public/recent").await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn transfer_round_trips_through_history() {
+    let horizon = MockServer::start();
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200)
+            .json_body(json!({ "sequence": "100", "data": {} }));
+    });
+    let submit = horizon.mock(|when, then| {
+        when.method(POST).path("/transactions");
+        then.status(200).json_body(json!({
+            "hash": "tx-transfer",
+            "ledger": 88,
+            "created_at": "2024-04-01T09:00:00Z"
+        }));
+    });
+
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+    let response = server
+        .post("/transfer")
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "from_owner": "alice",
+            "to_owner": "bob",
+            "transfer_date": "2024-04-01",
+            "transfer_reference": "REF-1"
+        }))
+        .await;
+
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["transaction_id"], "tx-transfer");
+    submit.assert();
+
+    let history = server.get(&format!("/transfer/{}", ANCHORED_HASH)).await;
+    history.assert_status_ok();
+    let history: serde_json::Value = history.json();
+    assert_eq!(history["total"], 1);
+    let records = &history["records"];
+    assert_eq!(records.as_array().unwrap().len(), 1);
+    assert_eq!(records[0]["from_owner"], "alice");
+    assert_eq!(records[0]["to_owner"], "bob");
+    assert_eq!(records[0]["transfer_hash"], body["transfer_hash"]);
+}
+
+#[tokio::test]
+async fn transfer_with_stale_expected_sequence_is_a_conflict() {
+    let horizon = MockServer::start();
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200)
+            .json_body(json!({ "sequence": "100", "data": {} }));
+    });
+    let submit = horizon.mock(|when, then| {
+        when.method(POST).path("/transactions");
+        then.status(200).json_body(json!({
+            "hash": "tx-transfer",
+            "ledger": 88,
+            "created_at": "2024-04-01T09:00:00Z"
+        }));
+    });
+
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+    let transfer = |expected_sequence: u64| {
+        json!({
+            "document_hash": ANCHORED_HASH,
+            "from_owner": "alice",
+            "to_owner": "bob",
+            "transfer_date": "2024-04-01",
+            "transfer_reference": "REF-1",
+            "expected_sequence": expected_sequence
+        })
+    };
+
+    server
+        .post("/transfer")
+        .json(&transfer(0))
+        .await
+        .assert_status_ok();
+    // A second clerk still working from the empty history.
+    let response = server.post("/transfer").json(&transfer(0)).await;
+    response.assert_status(axum::http::StatusCode::CONFLICT);
+    let body: ApiErrorBody = response.json();
+    assert_eq!(body.code, "CONFLICT");
+    submit.assert_hits(1);
+
+    let events: DocumentEventsResponse = server
+        .get(&format!("/documents/{}/events", ANCHORED_HASH))
+        .await
+        .json();
+    assert_eq!(events.last_sequence, 1);
+    assert_eq!(events.events[0].event_type, "Transferred");
+}
+
+#[tokio::test]
+async fn transfer_history_is_paginated_newest_first() {
+    let state = test_state("http://127.0.0.1:1");
+    let records: Vec<TransferRecord> = (1..=3)
+        .map(|day| TransferRecord {
+            document_hash: ANCHORED_HASH.to_string(),
+            from_owner: format!("owner-{}", day - 1),
+            to_owner: format!("owner-{}", day),
+            transfer_date: format!("2024-04-0{}", day),
+            transfer_reference: format!("REF-{}", day),
+            transfer_hash: format!("hash-{}", day),
+            memo: format!("TRANSFER:hash-{}", day),
+            anchored_at: format!("2024-04-0{}T09:00:00+00:00", day),
+            hash_version: 2,
+        })
+        .collect();
+    state
+        .cache
+        .set(&format!("transfer:{}", ANCHORED_HASH), &records, 3600)
+        .await
+        .unwrap();
+    let server = TestServer::new(app(state)).unwrap();
+
+    let first: TransferHistoryResponse = server
+        .get(&format!("/transfer/{}?per_page=2", ANCHORED_HASH))
+        .await
+        .json();
+    assert_eq!(first.total, 3);
+    assert_eq!((first.page, first.per_page), (1, 2));
+    let refs: Vec<&str> = first
+        .records
+        .iter()
+        .map(|r| r.transfer_reference.as_str())
+        .collect();
+    assert_eq!(refs, ["REF-3", "REF-2"]);
+
+    let second: TransferHistoryResponse = server
+        .get(&format!("/transfer/{}?page=2&per_page=2", ANCHORED_HASH))
+        .await
+        .json();
+    assert_eq!(second.records.len(), 1);
+    assert_eq!(second.records[0].transfer_reference, "REF-1");
+
+    let beyond: TransferHistoryResponse = server
+        .get(&format!("/transfer/{}?page=9", ANCHORED_HASH))
+        .await
+        .json();
+    assert!(beyond.records.is_empty());
+    assert_eq!(beyond.total, 3);
+
+    for query in ["page=0", "per_page=201", "page=abc"] {
+        let response = server
+            .get(&format!("/transfer/{}?{}", ANCHORED_HASH, query))
+            .await;
+        response.assert_status_bad_request();
+        assert!(response.json::<serde_json::Value>()["error"].is_string());
+    }
+}
+
+#[tokio::test]
+async fn transfer_rejects_invalid_date() {
+    let horizon = MockServer::start();
+    let submit = horizon.mock(|when, then| {
+        when.method(POST).path("/transactions");
+        then.status(200);
+    });
+
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+    let response = server
+        .post("/transfer")
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "from_owner": "alice",
+            "to_owner": "bob",
+            "transfer_date": "01/04/2024",
+            "transfer_reference": "REF-1"
+        }))
+        .await;
+
+    response.assert_status_bad_request();
+    let body: ApiErrorBody = response.json();
+    assert_eq!(body.code, "VALIDATION");
+    submit.assert_hits(0);
+}
+
+#[tokio::test]
+async fn error_responses_carry_code_and_status() {
+    // Nothing listens on port 1, so every Horizon call fails.
+    let server = TestServer::new(app(test_state("http://127.0.0.1:1"))).unwrap();
+    let cases = [
+        (server.get("/verify/abc123").await, 400, "VALIDATION"),
+        (
+            server
+                .post("/verify/batch")
+                .json(&json!({ "hashes": [] }))
+                .await,
+            400,
+            "VALIDATION",
+        ),
+        (
+            server.get(&format!("/verify/{}", ANCHORED_HASH)).await,
+            502,
+            "STELLAR_UNAVAILABLE",
+        ),
+        (
+            server
+                .post("/revoke")
+                .json(&json!({
+                    "document_hash": ANCHORED_HASH,
+                    "reason": "superseded",
+                    "revoked_by": TEST_ACCOUNT_ID
+                }))
+                .await,
+            502,
+            "STELLAR_UNAVAILABLE",
+        ),
+        (
+            server
+                .get(&format!("/transfer/{}?page=0", ANCHORED_HASH))
+                .await,
+            400,
+            "VALIDATION",
+        ),
+        (
+            server
+                .get("/admin/cache/session:abc")
+                .add_header(header::AUTHORIZATION, admin_auth(TEST_ADMIN_TOKEN))
+                .await,
+            403,
+            "FORBIDDEN",
+        ),
+    ];
+
+    for (response, status, code) in cases {
+        assert_eq!(response.status_code().as_u16(), status);
+        let body: ApiErrorBody = response.json();
+        assert_eq!(body.code, code);
+        assert!(!body.error.is_empty());
+    }
+}
+
+#[tokio::test]
+async fn admin_cache_reports_and_extends_ttl() {
+    let state = test_state("http://127.0.0.1:1");
+    let key = format!("history:{}", ANCHORED_HASH);
+    state.cache.set_raw(&key, "[]", 600).await.unwrap();
+    let server = TestServer::new(app(state)).unwrap();
+
+    let response = server
+        .get(&format!("/admin/cache/{}", key))
+        .add_header(header::AUTHORIZATION, admin_auth(TEST_ADMIN_TOKEN))
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["size_bytes"], 2);
+    let ttl = body["ttl_secs"].as_u64().unwrap();
+    assert!((598..=600).contains(&ttl), "ttl {}", ttl);
+
+    let response = server
+        .post(&format!("/admin/cache/{}/expire", key))
+        .add_header(header::AUTHORIZATION, admin_auth(TEST_ADMIN_TOKEN))
+        .json(&json!({ "ttl_secs": 60 }))
+        .await;
+    response.assert_status_ok();
+    let ttl = response.json::<serde_json::Value>()["ttl_secs"]
+        .as_u64()
+        .unwrap();
+    assert!((58..=60).contains(&ttl), "ttl {}", ttl);
+
+    // Oversized TTLs are clamped to a year rather than overflowing.
+    let response = server
+        .post(&format!("/admin/cache/{}/expire", key))
+        .add_header(header::AUTHORIZATION, admin_auth(TEST_ADMIN_TOKEN))
+        .json(&json!({ "ttl_secs": u64::MAX }))
+        .await;
+    response.assert_status_ok();
+    let ttl = response.json::<serde_json::Value>()["ttl_secs"]
+        .as_u64()
+        .unwrap();
+    assert!(
+        ttl <= 60 * 60 * 24 * 365 && ttl > 60 * 60 * 24 * 364,
+        "ttl {}",
+        ttl
+    );
+
+    server
+        .get(&format!("/admin/cache/stellar:verify:{}", "ab".repeat(32)))
+        .add_header(header::AUTHORIZATION, admin_auth(TEST_ADMIN_TOKEN))
+        .await
+        .assert_status_not_found();
+}
+
+#[tokio::test]
+async fn admin_cache_requires_the_admin_token() {
+    let state = test_state("http://127.0.0.1:1");
+    let key = format!("history:{}", ANCHORED_HASH);
+    state.cache.set_raw(&key, "[]", 600).await.unwrap();
+    let server = TestServer::new(app(state)).unwrap();
+
+    server
+        .get(&format!("/admin/cache/{}", key))
+        .await
+        .assert_status_unauthorized();
+    server
+        .post(&format!("/admin/cache/{}/expire", key))
+        .add_header(header::AUTHORIZATION, admin_auth("wrong-token"))
+        .json(&json!({ "ttl_secs": 60 }))
+        .await
+        .assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn admin_cache_rejects_keys_outside_known_namespaces() {
+    let server = TestServer::new(app(test_state("http://127.0.0.1:1"))).unwrap();
+
+    for key in ["session:abc", "history:not-a-hash", "transfer:", "config"] {
+        server
+            .get(&format!("/admin/cache/{}", key))
+            .add_header(header::AUTHORIZATION, admin_auth(TEST_ADMIN_TOKEN))
+            .await
+            .assert_status(axum::http::StatusCode::FORBIDDEN);
+    }
+    server
+        .post("/admin/cache/session:abc/expire")
+        .add_header(header::AUTHORIZATION, admin_auth(TEST_ADMIN_TOKEN))
+        .json(&json!({ "ttl_secs": 60 }))
+        .await
+        .assert_status(axum::http::StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn similarity_scores_candidates_in_request_order() {
+    let server = TestServer::new(app(test_state("http://127.0.0.1:1"))).unwrap();
+
+    let response = server
+        .post("/similarity")
+        .json(&json!({
+            "reference": "title deed for plot 12",
+            "candidates": ["survey of the river bank", "title deed for plot 12"]
+        }))
+        .await;
+    response.assert_status_ok();
+    let body: SimilarityResponse = response.json();
+    assert_eq!(body.results.len(), 2);
+    assert_eq!(body.results[1].index, 1);
+    assert_eq!(body.results[1].combined, 1.0);
+    assert!(body.results[0].combined < body.results[1].combined);
+
+    let response = server
+        .post("/similarity")
+        .json(&json!({ "reference": "title deed", "candidates": [] }))
+        .await;
+    response.assert_status_ok();
+    assert!(response.json::<SimilarityResponse>().results.is_empty());
+
+    let response = server
+        .post("/similarity")
+        .json(&json!({ "reference": "x".repeat(5000), "candidates": ["a"] }))
+        .await;
+    response.assert_status_bad_request();
+    let body: ApiErrorBody = response.json();
+    assert!(
+        body.error.starts_with("reference exceeds"),
+        "{}",
+        body.error
+    );
+
+    let response = server
+        .post("/similarity")
+        .json(&json!({ "reference": "a", "candidates": ["a", "x".repeat(5000)] }))
+        .await;
+    response.assert_status_bad_request();
+    let body: ApiErrorBody = response.json();
+    assert!(
+        body.error.starts_with("candidate 1 exceeds"),
+        "{}",
+        body.error
+    );
+}
+
+#[tokio::test]
+async fn duplicates_returns_pairs_above_threshold() {
+    let server = TestServer::new(app(test_state("http://127.0.0.1:1"))).unwrap();
+
+    let response = server
+        .post("/duplicates")
+        .json(&json!({
+            "documents": ["title deed for plot 12", "survey report", "title deed for plot 12"],
+            "threshold": 0.9
+        }))
+        .await;
+    response.assert_status_ok();
+    let body: DuplicatesResponse = response.json();
+    assert_eq!(body.pairs.len(), 1);
+    assert_eq!((body.pairs[0].first, body.pairs[0].second), (0, 2));
+
+    let response = server
+        .post("/duplicates")
+        .json(&json!({ "documents": [], "threshold": 0.9 }))
+        .await;
+    response.assert_status_ok();
+    assert!(response.json::<DuplicatesResponse>().pairs.is_empty());
+
+    let too_many: Vec<String> = (0..51).map(|i| format!("doc {}", i)).collect();
+    server
+        .post("/duplicates")
+        .json(&json!({ "documents": too_many, "threshold": 0.9 }))
+        .await
+        .assert_status_bad_request();
+    server
+        .post("/duplicates")
+        .json(&json!({ "documents": ["a", "b"], "threshold": 1.5 }))
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn submit_anchors_new_hash_and_invalidates_verify_cache() {
+    let horizon = MockServer::start();
+    mock_no_memo_anchors(&horizon);
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200).json_body(funded_account(json!({})));
+    });
+    let submit = horizon.mock(|when, then| {
+        when.method(POST).path("/transactions");
+        then.status(200).json_body(json!({
+            "hash": "tx-submit",
+            "ledger": 99,
+            "created_at": "2024-05-01T08:00:00Z"
+        }));
+    });
+
+    let state = test_state(&horizon.base_url());
+    let cache = state.cache.clone();
+    let server = TestServer::new(app(state)).unwrap();
+
+    // A stale "not verified" result is cached before submission.
+    server
+        .get(&format!("/verify/{}", ANCHORED_HASH))
+        .await
+        .assert_status_ok();
+    assert!(cache.get_raw(ANCHORED_HASH).await.unwrap().is_some());
+
+    let response = server
+        .post("/submit")
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "document_id": "deed-1",
+            "submitter": "registrar"
+        }))
+        .await;
+
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let body: SubmitResponse = response.json();
+    assert!(body.success);
+    assert_eq!(body.transaction_id.as_deref(), Some("tx-submit"));
+    submit.assert();
+    assert!(cache.get_raw(ANCHORED_HASH).await.unwrap().is_none());
+
+    // Resubmitting is caught from the anchor record without touching Horizon.
+    let response = server
+        .post("/submit")
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "document_id": "deed-1",
+            "submitter": "registrar"
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CONFLICT);
+    assert_eq!(
+        response.json::<SubmitResponse>().transaction_id.as_deref(),
+        Some("tx-submit")
+    );
+    submit.assert_hits(1);
+}
+
+#[tokio::test]
+async fn submit_returns_conflict_for_hash_already_on_chain() {
+    let horizon = MockServer::start();
+    mock_anchored_account(&horizon, ANCHORED_HASH);
+    mock_anchor_evidence(&horizon, ANCHORED_HASH, "tx-existing");
+    let submit = horizon.mock(|when, then| {
+        when.method(POST).path("/transactions");
+        then.status(200);
+    });
+
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+    let response = server
+        .post("/submit")
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "document_id": "deed-1",
+            "submitter": "registrar"
+        }))
+        .await;
+
+    response.assert_status(axum::http::StatusCode::CONFLICT);
+    let body: SubmitResponse = response.json();
+    assert!(!body.success);
+    assert_eq!(body.transaction_id.as_deref(), Some("tx-existing"));
+    submit.assert_hits(0);
+}
+
+#[tokio::test]
+async fn submit_without_signing_key_is_unavailable() {
+    let mut state = test_state("http://127.0.0.1:1");
+    state.stellar_secret_key = String::new();
+    let server = TestServer::new(app(state)).unwrap();
+
+    let response = server
+        .post("/submit")
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "document_id": "deed-1",
+            "submitter": "registrar"
+        }))
+        .await;
+
+    response.assert_status(axum::http::StatusCode::SERVICE_UNAVAILABLE);
+    let body: ApiErrorBody = response.json();
+    assert_eq!(body.error, "stellar signing key is not configured");
+    assert_eq!(body.code, "NOT_CONFIGURED");
+}
+
+#[tokio::test]
+async fn batch_submit_reports_each_item_and_continues_after_failure() {
+    let failing = "a".repeat(64);
+    let succeeding = "b".repeat(64);
+    let horizon = FakeHorizon::start(TEST_ACCOUNT_ID).await;
+    horizon.set_data(&build_data_key(ANCHORED_HASH), b"anchored");
+    horizon.fail_next_submissions(1);
+    let state = test_state(horizon.url());
+    let metrics = state.metrics.clone();
+    let server = TestServer::new(app(state)).unwrap();
+
+    let response = server
+        .post("/submit/batch")
+        .json(&json!({
+            "hashes": [failing, ANCHORED_HASH, succeeding, "not-a-hash"]
+        }))
+        .await;
+
+    response.assert_status_ok();
+    let body: BatchSubmitResponse = response.json();
+    assert_eq!(body.total, 4);
+    assert_eq!(
+        (body.submitted_count, body.skipped_count, body.failed_count),
+        (1, 1, 2)
+    );
+
+    let [failed, skipped, submitted, invalid] = &body.results[..] else {
+        panic!("expected four results");
+    };
+    assert!(failed.error.as_deref().unwrap().contains("500"));
+    assert!(failed.transaction_id.is_none());
+    assert!(skipped.skipped);
+    assert!(skipped.error.is_none());
+    let accepted = horizon.transactions();
+    assert_eq!(accepted.len(), 1);
+    assert_eq!(
+        submitted.transaction_id.as_deref(),
+        Some(accepted[0].hash.as_str())
+    );
+    assert!(!submitted.skipped);
+    assert!(invalid.error.as_deref().unwrap().contains("wrong length"));
+    assert_eq!(metrics.validation_failures("wrong_length"), 1);
+
+    let oversized: Vec<String> = (0..26).map(|i| format!("{:064x}", i)).collect();
+    server
+        .post("/submit/batch")
+        .json(&json!({ "hashes": oversized }))
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn anchor_verify_revoke_lifecycle_against_fake_horizon() {
+    let horizon = FakeHorizon::start(TEST_ACCOUNT_ID).await;
+    let server = TestServer::new(app(test_state(horizon.url()))).unwrap();
+
+    let before: VerifyResponse = server
+        .get(&format!("/verify/{}", ANCHORED_HASH))
+        .await
+        .json();
+    assert!(!before.verified);
+
+    let response = server
+        .post("/submit")
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "document_id": "deed-1",
+            "submitter": "registrar"
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let anchor_tx = response.json::<SubmitResponse>().transaction_id.unwrap();
+
+    let anchored: VerifyResponse = server
+        .get(&format!("/verify/{}", ANCHORED_HASH))
+        .await
+        .json();
+    assert!(anchored.verified);
+    assert!(!anchored.revoked);
+    assert_eq!(anchored.transaction_id.as_deref(), Some(anchor_tx.as_str()));
+
+    let revoke: RevokeResponse = server
+        .post("/revoke")
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "reason": "superseded",
+            "revoked_by": "registrar"
+        }))
+        .await
+        .json();
+    assert!(revoke.revoked);
+
+    let revoked: VerifyResponse = server
+        .get(&format!("/verify/{}", ANCHORED_HASH))
+        .await
+        .json();
+    assert!(revoked.revoked);
+    assert_eq!(
+        revoked.revoked_transaction_id.as_deref(),
+        Some(revoke.transaction_id.as_str())
+    );
+
+    let accepted = horizon.transactions();
+    assert_eq!(accepted.len(), 2);
+    assert_eq!(horizon.sequence(), 3);
+    assert!(matches!(
+        &accepted[1].operations[..],
+        [FakeOperation::ManageData { name, value: Some(_) }]
+            if *name == build_revocation_key(ANCHORED_HASH)
+    ));
+}
+
+#[tokio::test]
+async fn document_status_combines_verification_revocation_and_transfers() {
+    let horizon = FakeHorizon::start(TEST_ACCOUNT_ID).await;
+    let server = TestServer::new(app(test_state(horizon.url()))).unwrap();
+    let status_url = format!("/documents/{}/status", ANCHORED_HASH);
+
+    server
+        .post("/submit")
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "document_id": "deed-1",
+            "submitter": "registrar"
+        }))
+        .await
+        .assert_status(axum::http::StatusCode::CREATED);
+    server
+        .post("/transfer")
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "from_owner": "alice",
+            "to_owner": "bob",
+            "transfer_date": "2024-04-01",
+            "transfer_reference": "REF-1"
+        }))
+        .await
+        .assert_status_ok();
+
+    let status: DocumentStatus = server.get(&status_url).await.json();
+    assert!(status.verification.verified);
+    assert!(!status.cached);
+    assert!(status.anchored_at.is_some());
+    assert!(status.revocation.is_none());
+    assert_eq!(status.transfer_count, 1);
+    assert_eq!(status.latest_transfer.unwrap().to_owner, "bob");
+    assert!(status.last_transferred_at.is_some());
+
+    let again: DocumentStatus = server.get(&status_url).await.json();
+    assert!(again.cached);
+    assert_eq!(again.generated_at, status.generated_at);
+
+    // Revoking drops the cached status.
+    let revoke: RevokeResponse = server
+        .post("/revoke")
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "reason": "superseded",
+            "revoked_by": "registrar"
+        }))
+        .await
+        .json();
+    let revoked: DocumentStatus = server.get(&status_url).await.json();
+    assert!(!revoked.cached);
+    assert!(revoked.verification.revoked);
+    assert!(revoked.revoked_at.is_some());
+    assert_eq!(
+        revoked.revocation.unwrap().transaction_id,
+        revoke.transaction_id
+    );
+
+    let body: ApiErrorBody = server.get("/documents/not-a-hash/status").await.json();
+    assert_eq!(body.code, "VALIDATION");
+}
+
+#[tokio::test]
+async fn fully_cached_batch_makes_no_horizon_requests() {
+    let unanchored = "ab".repeat(32);
+    let horizon = MockServer::start();
+    let account = horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200).json_body(funded_account(json!({})));
+    });
+    let state = test_state(&horizon.base_url());
+    let checked_at = chrono::Utc::now().timestamp();
+    let cached = |verified: bool| VerifyResponse {
+        verified,
+        transaction_id: verified.then(|| "tx-anchor".to_string()),
+        timestamp: None,
+        cached: false,
+        revoked: false,
+        revoked_at: None,
+        revoked_transaction_id: None,
+        last_checked_at: checked_at,
+        evidence: None,
+        error: None,
+    };
+    state
+        .cache
+        .set_many(&[
+            (ANCHORED_HASH.to_string(), cached(true), 3600),
+            (unanchored.clone(), cached(false), 300),
+        ])
+        .await
+        .unwrap();
+    let server = TestServer::new(app(state)).unwrap();
+
+    let response = server
+        .post("/verify/batch")
+        .json(&json!({ "hashes": [ANCHORED_HASH, unanchored, ANCHORED_HASH.to_uppercase()] }))
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["verified_count"], 2);
+    assert_eq!(body["results"][0]["transaction_id"], "tx-anchor");
+    assert_eq!(body["results"][1]["verified"], false);
+    account.assert_hits(0);
+}
+
+#[tokio::test]
+async fn batch_verify_summary_counts_mixed_batch() {
+    let horizon = FakeHorizon::start(TEST_ACCOUNT_ID).await;
+    horizon.set_data(&build_data_key(ANCHORED_HASH), b"anchored");
+    let server = TestServer::new(app(test_state(horizon.url()))).unwrap();
+
+    let mut hashes = vec![ANCHORED_HASH.to_string(), "not-a-hash".to_string()];
+    hashes.extend((0..58).map(|i| format!("{:064x}", i)));
+    let response = server
+        .post("/verify/batch/summary")
+        .json(&json!({ "hashes": hashes }))
+        .await;
+
+    response.assert_status_ok();
+    let summary: BatchVerifySummary = response.json();
+    assert_eq!(summary.total, 60);
+    assert_eq!(summary.verified_count, 1);
+    assert_eq!(summary.failed_count, 59);
+    assert_eq!(summary.error_count, 1);
+    assert!(response
+        .json::<serde_json::Value>()
+        .get("results")
+        .is_none());
+
+    let oversized: Vec<String> = (0..501).map(|i| format!("{:064x}", i)).collect();
+    server
+        .post("/verify/batch/summary")
+        .json(&json!({ "hashes": oversized }))
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn document_events_record_lifecycle_with_increasing_sequences() {
+    let horizon = FakeHorizon::start(TEST_ACCOUNT_ID).await;
+    let server = TestServer::new(app(test_state(horizon.url()))).unwrap();
+    let actor = || {
+        (
+            header::HeaderName::from_static("x-actor"),
+            HeaderValue::from_static("clerk-1"),
+        )
+    };
+
+    let (name, value) = actor();
+    server
+        .post("/submit")
+        .add_header(name, value)
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "document_id": "deed-1",
+            "submitter": "registrar"
+        }))
+        .await
+        .assert_status(axum::http::StatusCode::CREATED);
+    let (name, value) = actor();
+    server
+        .post("/revoke")
+        .add_header(name, value)
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "reason": "superseded",
+            "revoked_by": "registrar"
+        }))
+        .await
+        .assert_status_ok();
+    // No X-Actor header: recorded as anonymous.
+    server
+        .get(&format!("/verify/{}", ANCHORED_HASH))
+        .await
+        .assert_status_ok();
+
+    let url = format!("/documents/{}/events", ANCHORED_HASH);
+    let body: DocumentEventsResponse = server.get(&url).await.json();
+    let summary: Vec<(u64, &str, &str)> = body
+        .events
+        .iter()
+        .map(|e| (e.sequence, e.event_type.as_str(), e.actor.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (1, ANCHORED_EVENT, "clerk-1"),
+            (2, "Revoked", "clerk-1"),
+            (3, "Verified", "anonymous"),
+        ]
+    );
+    assert_eq!(body.last_sequence, 3);
+
+    // Lookups of hashes that were never anchored are not recorded.
+    let unanchored_hash = "1".repeat(64);
+    server
+        .get(&format!("/verify/{}", unanchored_hash))
+        .await
+        .assert_status_ok();
+    let unanchored: DocumentEventsResponse = server
+        .get(&format!("/documents/{}/events", unanchored_hash))
+        .await
+        .json();
+    assert!(unanchored.events.is_empty());
+    assert_eq!(unanchored.last_sequence, 0);
+
+    let page: DocumentEventsResponse = server
+        .get(&url)
+        .add_query_param("after_sequence", 1)
+        .add_query_param("limit", 1)
+        .await
+        .json();
+    assert_eq!(page.events.len(), 1);
+    assert_eq!(page.events[0].sequence, 2);
+
+    server
+        .get(&url)
+        .add_query_param("limit", 0)
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn batch_verify_looks_up_case_variant_duplicates_once() {
+    let horizon = MockServer::start();
+    mock_no_memo_anchors(&horizon);
+    let account = horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200).json_body(funded_account(json!({})));
+    });
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+
+    let upper = ANCHORED_HASH.to_uppercase();
+    let hashes = [upper.as_str(), ANCHORED_HASH, upper.as_str()];
+    let body: serde_json::Value = server
+        .post("/verify/batch")
+        .json(&json!({ "hashes": hashes }))
+        .await
+        .json();
+
+    account.assert_hits(1);
+    let results = body["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    for (item, hash) in results.iter().zip(hashes) {
+        assert_eq!(item["hash"], hash);
+        assert_eq!(item["verified"], false);
+        assert!(item["error"].is_null());
+    }
+    assert_eq!(
+        (body["total"].as_u64(), body["failed_count"].as_u64()),
+        (Some(3), Some(3))
+    );
+}
+
+#[tokio::test]
+async fn verify_stream_returns_one_ndjson_result_per_line() {
+    let horizon = FakeHorizon::start(TEST_ACCOUNT_ID).await;
+    horizon.set_data(&build_data_key(ANCHORED_HASH), b"anchored");
+    let server = TestServer::new(app(test_state(horizon.url()))).unwrap();
+
+    // More lines than /verify/batch accepts, in all three line formats.
+    let mut lines = vec![
+        ANCHORED_HASH.to_string(),
+        json!(ANCHORED_HASH).to_string(),
+        json!({ "hash": "not-a-hash" }).to_string(),
+    ];
+    lines.extend((0..60).map(|i| format!("{:064x}", i)));
+    let response = server
+        .post("/verify/stream")
+        .bytes(lines.join("\n").into())
+        .await;
+
+    response.assert_status_ok();
+    assert_eq!(
+        response.header(header::CONTENT_TYPE),
+        "application/x-ndjson"
+    );
+    let results: Vec<serde_json::Value> = response
+        .text()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(results.len(), 63);
+    assert_eq!(results[0]["verified"], true);
+    assert_eq!(results[1]["verified"], true);
+    assert_eq!(results[2]["hash"], "not-a-hash");
+    assert!(results[2]["error"].is_string());
+    assert_eq!(results[62]["hash"], format!("{:064x}", 59));
+    assert!(results[3..].iter().all(|r| r["verified"] == false));
+}
+
+#[tokio::test]
+async fn concurrent_verifications_of_one_hash_share_a_horizon_query() {
+    let horizon = MockServer::start();
+    mock_no_memo_anchors(&horizon);
+    let account = horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200)
+            .delay(Duration::from_millis(200))
+            .json_body(funded_account(json!({})));
+    });
+    let state = test_state(&horizon.base_url());
+    let flights = state.verify_flights.clone();
+    let server = TestServer::new(app(state)).unwrap();
+
+    let url = format!("/verify/{}", ANCHORED_HASH);
+    let responses =
+        futures::future::join_all((0..20).map(|_| server.get(&url).into_future())).await;
+
+    account.assert_hits(1);
+    for response in responses {
+        response.assert_status_ok();
+        assert!(!response.json::<VerifyResponse>().verified);
+    }
+    assert_eq!(flights.in_flight(), 0);
+}
+
+#[tokio::test]
+async fn failed_coalesced_verification_is_retried_by_the_next_request() {
+    let horizon = MockServer::start();
+    let account = horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(500);
+    });
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+
+    let url = format!("/verify/{}", ANCHORED_HASH);
+    server
+        .get(&url)
+        .await
+        .assert_status(axum::http::StatusCode::BAD_GATEWAY);
+    server
+        .get(&url)
+        .await
+        .assert_status(axum::http::StatusCode::BAD_GATEWAY);
+    account.assert_hits(2);
+}
+
+/// Horizon whose account lookups take 50ms and record the highest number
+/// served at once.
+async fn spawn_slow_horizon(max_in_flight: Arc<AtomicUsize>) -> String {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let router = axum::Router::new().route(
+        "/accounts/:id",
+        axum::routing::get(move || {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                axum::Json(funded_account(json!({})))
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn batch_verify_bounds_concurrent_horizon_queries() {
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+    let horizon = spawn_slow_horizon(max_in_flight.clone()).await;
+    let mut state = test_state(&horizon);
+    state.batch_concurrency = 3;
+    let metrics = state.metrics.clone();
+    let server = TestServer::new(app(state)).unwrap();
+
+    let hashes: Vec<String> = (0..50).map(|i| format!("{:064x}", i)).collect();
+    let body: serde_json::Value = server
+        .post("/verify/batch")
+        .json(&json!({ "hashes": hashes }))
+        .await
+        .json();
+
+    let returned: Vec<&str> = body["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["hash"].as_str().unwrap())
+        .collect();
+    assert_eq!(returned, hashes);
+    let peak = max_in_flight.load(Ordering::SeqCst);
+    assert!((2..=3).contains(&peak), "peak concurrency was {}", peak);
+    assert_eq!(metrics.batch_inflight_queries(), 0);
+}
+
+#[tokio::test]
+async fn submit_fails_fast_when_account_cannot_pay_reserve() {
+    let horizon = MockServer::start();
+    mock_no_memo_anchors(&horizon);
+    let mut account = funded_account(json!({}));
+    account["balances"][0]["balance"] = json!("1.2000000");
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200).json_body(account);
+    });
+    let submit = horizon.mock(|when, then| {
+        when.method(POST).path("/transactions");
+        then.status(200);
+    });
+
+    let server = TestServer::new(app(test_state(&horizon.base_url()))).unwrap();
+    let response = server
+        .post("/submit")
+        .json(&json!({
+            "document_hash": ANCHORED_HASH,
+            "document_id": "deed-1",
+            "submitter": "registrar"
+        }))
+        .await;
+
+    response.assert_status(axum::http::StatusCode::SERVICE_UNAVAILABLE);
+    let body: ApiErrorBody = response.json();
+    assert_eq!(body.code, "ACCOUNT_NOT_READY");
+    assert!(body.error.contains("below the 2 XLM needed"));
+    submit.assert_hits(0);
+}
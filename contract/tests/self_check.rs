@@ -0,0 +1,139 @@
+use httpmock::prelude::*;
+use serde_json::json;
+use std::time::Duration;
+use stellar_doc_verifier::cache::{CacheBackend, FallbackCache, InMemoryCache, RedisCache};
+use stellar_doc_verifier::self_check::{run_self_check, CheckStatus};
+use stellar_doc_verifier::stellar::StellarClient;
+use stellar_doc_verifier::test_support::fake_redis::FakeRedis;
+
+const TEST_SECRET_KEY: &str = "SAAQEAYEAUDAOCAJBIFQYDIOB4IBCEQTCQKRMFYYDENBWHA5DYPSBF5K";
+const TEST_ACCOUNT_ID: &str = "GB43KVROR7TFJ6KAPCYRF2FJROTZAH4FHLTJLPWX4DRZCC5NASLGITR6";
+
+fn mock_account(server: &MockServer, balance: &str) {
+    mock_account_with_signers(server, balance, 1, 1);
+}
+
+fn mock_account_with_signers(server: &MockServer, balance: &str, weight: u32, med_threshold: u32) {
+    server.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200).json_body(json!({
+            "sequence": "100",
+            "subentry_count": 0,
+            "balances": [{ "asset_type": "native", "balance": balance }],
+            "thresholds": {
+                "low_threshold": 1,
+                "med_threshold": med_threshold,
+                "high_threshold": 2
+            },
+            "signers": [{ "key": TEST_ACCOUNT_ID, "weight": weight, "type": "ed25519_public_key" }]
+        }));
+    });
+}
+
+#[tokio::test]
+async fn self_check_passes_with_funded_account_and_working_cache() {
+    let horizon = MockServer::start();
+    mock_account(&horizon, "100.0000000");
+    let stellar = StellarClient::new(&horizon.base_url());
+    let cache = CacheBackend::InMemory(InMemoryCache::new());
+
+    let report = run_self_check(&stellar, &cache, Some(TEST_SECRET_KEY), Some("secret"), 5.0).await;
+
+    assert!(report.passed(), "{}", report);
+    assert!(report.checks.iter().all(|c| c.status == CheckStatus::Pass));
+}
+
+#[tokio::test]
+async fn self_check_warns_on_low_balance_without_failing() {
+    let horizon = MockServer::start();
+    mock_account(&horizon, "1.5000000");
+    let stellar = StellarClient::new(&horizon.base_url());
+    let cache = CacheBackend::InMemory(InMemoryCache::new());
+
+    let report = run_self_check(&stellar, &cache, Some(TEST_SECRET_KEY), None, 5.0).await;
+
+    assert!(report.passed(), "{}", report);
+    assert_eq!(
+        report.check("horizon_account").unwrap().status,
+        CheckStatus::Warn
+    );
+    assert_eq!(
+        report.check("webhook_signing").unwrap().status,
+        CheckStatus::Warn
+    );
+}
+
+#[tokio::test]
+async fn self_check_fails_when_account_is_missing() {
+    let horizon = MockServer::start();
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(404);
+    });
+    let stellar = StellarClient::new(&horizon.base_url());
+    let cache = CacheBackend::InMemory(InMemoryCache::new());
+
+    let report = run_self_check(&stellar, &cache, Some(TEST_SECRET_KEY), Some("secret"), 5.0).await;
+
+    assert!(!report.passed());
+    let account = report.check("horizon_account").unwrap();
+    assert_eq!(account.status, CheckStatus::Fail);
+    assert!(account.detail.contains("does not exist"));
+    assert!(report.to_string().contains("[FAIL] horizon_account"));
+}
+
+#[tokio::test]
+async fn self_check_fails_when_cache_round_trip_fails() {
+    let horizon = MockServer::start();
+    mock_account(&horizon, "100.0000000");
+    let stellar = StellarClient::new(&horizon.base_url());
+    let redis = FakeRedis::start().await;
+    redis.set_down(true);
+    let cache = CacheBackend::Redis(RedisCache::new(redis.url()).await.unwrap());
+
+    let report = run_self_check(&stellar, &cache, Some(TEST_SECRET_KEY), Some("secret"), 5.0).await;
+
+    assert!(!report.passed());
+    assert_eq!(report.check("cache").unwrap().status, CheckStatus::Fail);
+    assert_eq!(
+        report.check("horizon_account").unwrap().status,
+        CheckStatus::Pass
+    );
+}
+
+#[tokio::test]
+async fn self_check_warns_when_layered_cache_cannot_reach_redis() {
+    let horizon = MockServer::start();
+    mock_account(&horizon, "100.0000000");
+    let stellar = StellarClient::new(&horizon.base_url());
+    let cache = CacheBackend::Layered(
+        FallbackCache::connect(
+            "redis://127.0.0.1:1",
+            InMemoryCache::new(),
+            Duration::from_secs(30),
+        )
+        .await,
+    );
+
+    let report = run_self_check(&stellar, &cache, Some(TEST_SECRET_KEY), Some("secret"), 5.0).await;
+
+    assert!(report.passed(), "{}", report);
+    assert_eq!(report.check("cache").unwrap().status, CheckStatus::Warn);
+}
+
+#[tokio::test]
+async fn self_check_fails_when_signing_key_cannot_meet_threshold() {
+    let horizon = MockServer::start();
+    mock_account_with_signers(&horizon, "100.0000000", 1, 2);
+    let stellar = StellarClient::new(&horizon.base_url());
+    let cache = CacheBackend::InMemory(InMemoryCache::new());
+
+    let report = run_self_check(&stellar, &cache, Some(TEST_SECRET_KEY), Some("secret"), 5.0).await;
+
+    assert!(!report.passed());
+    let account = report.check("horizon_account").unwrap();
+    assert_eq!(account.status, CheckStatus::Fail);
+    assert!(account.detail.contains("requires 2"), "{}", account.detail);
+}
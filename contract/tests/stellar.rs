@@ -0,0 +1,547 @@
+use httpmock::prelude::*;
+use serde_json::json;
+use std::time::Duration;
+use stellar_doc_verifier::stellar::{
+    build_data_key, AnchorMethod, LatencyEma, StellarClient, StellarError, TransactionVerification,
+};
+use stellar_doc_verifier::test_support::fake_horizon::{FakeHorizon, FakeMemo};
+
+const TEST_SECRET_KEY: &str = "SAAQEAYEAUDAOCAJBIFQYDIOB4IBCEQTCQKRMFYYDENBWHA5DYPSBF5K";
+const TEST_ACCOUNT_ID: &str = "GB43KVROR7TFJ6KAPCYRF2FJROTZAH4FHLTJLPWX4DRZCC5NASLGITR6";
+const COSIGNER: &str = "GCOSIGNERCOSIGNERCOSIGNERCOSIGNERCOSIGNERCOSIGNERCOSIGN";
+
+fn mock_account(server: &MockServer, body: serde_json::Value) -> httpmock::Mock<'_> {
+    server.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(200).json_body(body);
+    })
+}
+
+fn account_body(balance: &str, master_weight: u32, med_threshold: u32) -> serde_json::Value {
+    json!({
+        "sequence": "4242",
+        "subentry_count": 3,
+        "balances": [
+            { "asset_type": "credit_alphanum4", "balance": "999.0000000" },
+            { "asset_type": "native", "balance": balance }
+        ],
+        "thresholds": { "low_threshold": 1, "med_threshold": med_threshold, "high_threshold": 3 },
+        "signers": [
+            { "key": COSIGNER, "weight": 1, "type": "ed25519_public_key" },
+            { "key": TEST_ACCOUNT_ID, "weight": master_weight, "type": "ed25519_public_key" }
+        ]
+    })
+}
+
+#[tokio::test]
+async fn account_info_parses_healthy_account_and_is_cached() {
+    let horizon = MockServer::start();
+    let mock = mock_account(&horizon, account_body("25.5000000", 2, 2));
+    let stellar = StellarClient::new(&horizon.base_url());
+
+    let info = stellar.account_info(TEST_ACCOUNT_ID).await.unwrap();
+    assert_eq!(info.balance_xlm, 25.5);
+    assert_eq!(info.sequence, 4242);
+    assert_eq!(info.subentry_count, 3);
+    assert_eq!(info.thresholds.med_threshold, 2);
+    assert_eq!(info.signers.len(), 2);
+    assert!(info.check_can_anchor(TEST_ACCOUNT_ID).is_ok());
+
+    stellar.account_info(TEST_ACCOUNT_ID).await.unwrap();
+    mock.assert_hits(1);
+}
+
+#[tokio::test]
+async fn underfunded_account_reports_insufficient_balance() {
+    let horizon = MockServer::start();
+    mock_account(&horizon, account_body("2.9000000", 2, 2));
+    let stellar = StellarClient::new(&horizon.base_url());
+
+    let info = stellar.account_info(TEST_ACCOUNT_ID).await.unwrap();
+    match info.check_can_anchor(TEST_ACCOUNT_ID) {
+        Err(StellarError::InsufficientBalance {
+            balance_xlm,
+            required_xlm,
+        }) => {
+            assert_eq!(balance_xlm, 2.9);
+            assert_eq!(required_xlm, 3.0);
+        }
+        other => panic!("expected InsufficientBalance, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn multisig_account_reports_signer_mismatch() {
+    let horizon = MockServer::start();
+    mock_account(&horizon, account_body("100.0000000", 1, 2));
+    let stellar = StellarClient::new(&horizon.base_url());
+
+    let info = stellar.account_info(TEST_ACCOUNT_ID).await.unwrap();
+    match info.check_can_anchor(TEST_ACCOUNT_ID) {
+        Err(StellarError::SignerMismatch {
+            signer,
+            weight,
+            required,
+        }) => {
+            assert_eq!(signer, TEST_ACCOUNT_ID);
+            assert_eq!((weight, required), (1, 2));
+        }
+        other => panic!("expected SignerMismatch, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn missing_account_is_reported_as_not_found() {
+    let horizon = MockServer::start();
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}", TEST_ACCOUNT_ID));
+        then.status(404);
+    });
+    let stellar = StellarClient::new(&horizon.base_url());
+
+    assert!(matches!(
+        stellar.account_info(TEST_ACCOUNT_ID).await,
+        Err(StellarError::AccountNotFound(_))
+    ));
+}
+
+const HASH: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+/// `HASH` as a base64 `MEMO_HASH`.
+const HASH_MEMO: &str = "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=";
+
+#[test]
+fn latency_ema_converges_toward_observed_durations() {
+    let ema = LatencyEma::default();
+    assert_eq!(ema.avg_ms(), 0.0);
+
+    ema.record(Duration::from_millis(100));
+    assert_eq!(ema.avg_ms(), 100.0);
+
+    // Horizon slows down: the average climbs toward the new latency.
+    let mut previous = ema.avg_ms();
+    for _ in 0..30 {
+        ema.record(Duration::from_millis(500));
+        assert!(ema.avg_ms() > previous);
+        previous = ema.avg_ms();
+    }
+    assert!((ema.avg_ms() - 500.0).abs() < 1.0);
+
+    for _ in 0..30 {
+        ema.record(Duration::from_millis(50));
+    }
+    assert!((ema.avg_ms() - 50.0).abs() < 1.0);
+}
+
+#[tokio::test]
+async fn client_tracks_latency_and_derives_adaptive_timeout() {
+    let horizon = MockServer::start();
+    horizon.mock(|when, then| {
+        when.method(GET).path("/");
+        then.status(200).delay(Duration::from_millis(50));
+    });
+    let stellar = StellarClient::new(&horizon.base_url()).with_adaptive_timeout(100.0);
+    assert_eq!(stellar.adaptive_timeout(), None);
+
+    assert!(stellar.check_connection().await);
+    assert!(stellar.avg_latency_ms() >= 50.0);
+    let timeout = stellar.adaptive_timeout().unwrap();
+    assert!(timeout >= Duration::from_secs(5));
+
+    let plain = StellarClient::new(&horizon.base_url());
+    assert!(plain.check_connection().await);
+    assert_eq!(plain.adaptive_timeout(), None);
+}
+
+fn mock_submission(server: &MockServer) -> httpmock::Mock<'_> {
+    server.mock(|when, then| {
+        when.method(POST).path("/transactions");
+        then.status(200).json_body(json!({
+            "hash": "tx-anchor",
+            "ledger": 77,
+            "created_at": "2024-03-01T12:00:00Z"
+        }));
+    })
+}
+
+#[tokio::test]
+async fn manage_data_anchor_round_trips_through_verify() {
+    let horizon = MockServer::start();
+    let submit = mock_submission(&horizon);
+    let stellar = StellarClient::new(&horizon.base_url());
+    assert_eq!(stellar.anchor_method(), AnchorMethod::ManageData);
+
+    let mut account = mock_account(&horizon, json!({ "sequence": "1", "data": {} }));
+    let anchored = stellar
+        .anchor_hash(HASH, TEST_ACCOUNT_ID, TEST_SECRET_KEY)
+        .await
+        .unwrap();
+    assert_eq!(anchored.tx_hash, "tx-anchor");
+    submit.assert();
+
+    account.delete();
+    mock_account(
+        &horizon,
+        json!({ "sequence": "2", "data": { build_data_key(HASH): "YW5jaG9yZWQ=" } }),
+    );
+    let record = stellar.verify_hash(HASH, TEST_ACCOUNT_ID).await.unwrap();
+    assert!(record.anchored);
+    assert_eq!(record.decoded_value.as_deref(), Some("anchored"));
+}
+
+#[tokio::test]
+async fn memo_anchor_round_trips_through_verify() {
+    let horizon = MockServer::start();
+    let submit = mock_submission(&horizon);
+    mock_account(&horizon, json!({ "sequence": "1", "data": {} }));
+    let stellar = StellarClient::new(&horizon.base_url()).with_anchor_method(AnchorMethod::Memo);
+
+    let anchored = stellar
+        .anchor_hash(HASH, TEST_ACCOUNT_ID, TEST_SECRET_KEY)
+        .await
+        .unwrap();
+    assert_eq!(anchored.ledger, 77);
+    submit.assert();
+
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}/transactions", TEST_ACCOUNT_ID));
+        then.status(200).json_body(json!({
+            "_embedded": { "records": [
+                {
+                    "hash": "tx-other",
+                    "ledger": 78,
+                    "created_at": "2024-03-01T12:05:00Z",
+                    "source_account": TEST_ACCOUNT_ID,
+                    "memo_type": "text",
+                    "memo": "TRANSFER:abc",
+                    "successful": true
+                },
+                {
+                    "hash": "tx-anchor",
+                    "ledger": 77,
+                    "created_at": "2024-03-01T12:00:00Z",
+                    "source_account": TEST_ACCOUNT_ID,
+                    "memo_type": "hash",
+                    "memo": HASH_MEMO,
+                    "successful": true
+                }
+            ] }
+        }));
+    });
+
+    let record = stellar.verify_hash(HASH, TEST_ACCOUNT_ID).await.unwrap();
+    assert!(record.anchored);
+    assert_eq!(record.transaction_id.as_deref(), Some("tx-anchor"));
+    assert_eq!(record.evidence.unwrap().ledger, 77);
+
+    let other = "ab".repeat(32);
+    assert!(
+        !stellar
+            .verify_hash(&other, TEST_ACCOUNT_ID)
+            .await
+            .unwrap()
+            .anchored
+    );
+}
+
+#[tokio::test]
+async fn verify_hash_pages_back_to_old_memo_anchors_under_manage_data() {
+    let horizon = MockServer::start();
+    mock_account(&horizon, json!({ "sequence": "1", "data": {} }));
+    let older = horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}/transactions", TEST_ACCOUNT_ID))
+            .query_param("cursor", "401");
+        then.status(200).json_body(json!({
+            "_embedded": { "records": [{
+                "hash": "tx-anchor",
+                "ledger": 7,
+                "created_at": "2023-01-01T00:00:00Z",
+                "source_account": TEST_ACCOUNT_ID,
+                "memo_type": "hash",
+                "memo": HASH_MEMO,
+                "successful": true,
+                "paging_token": "199"
+            }] }
+        }));
+    });
+    let recent: Vec<serde_json::Value> = (0..200)
+        .map(|i| {
+            json!({
+                "hash": format!("tx-{}", i),
+                "ledger": 600 - i,
+                "created_at": "2024-01-01T00:00:00Z",
+                "source_account": TEST_ACCOUNT_ID,
+                "memo_type": "text",
+                "memo": "TRANSFER:abc",
+                "successful": true,
+                "paging_token": (600 - i).to_string()
+            })
+        })
+        .collect();
+    let newest = horizon.mock(|when, then| {
+        when.method(GET)
+            .path(format!("/accounts/{}/transactions", TEST_ACCOUNT_ID));
+        then.status(200)
+            .json_body(json!({ "_embedded": { "records": recent } }));
+    });
+    let stellar = StellarClient::new(&horizon.base_url());
+
+    let record = stellar.verify_hash(HASH, TEST_ACCOUNT_ID).await.unwrap();
+    assert!(record.anchored);
+    assert_eq!(record.transaction_id.as_deref(), Some("tx-anchor"));
+    newest.assert_hits(1);
+    older.assert_hits(1);
+}
+
+#[tokio::test]
+async fn verify_hash_finds_anchors_older_than_one_history_page() {
+    let horizon = FakeHorizon::start(TEST_ACCOUNT_ID).await;
+    let by_memo = StellarClient::new(horizon.url()).with_anchor_method(AnchorMethod::Memo);
+    let by_data = StellarClient::new(horizon.url());
+
+    // Distinct leading bytes, so no two hashes share a data entry key.
+    let hashes: Vec<String> = (1..=202u8)
+        .map(|i| format!("{:02x}", i).repeat(32))
+        .collect();
+    by_memo
+        .anchor_hash(&hashes[0], TEST_ACCOUNT_ID, TEST_SECRET_KEY)
+        .await
+        .unwrap();
+    by_data
+        .anchor_hash(&hashes[1], TEST_ACCOUNT_ID, TEST_SECRET_KEY)
+        .await
+        .unwrap();
+    for hash in &hashes[2..] {
+        by_memo
+            .anchor_hash(hash, TEST_ACCOUNT_ID, TEST_SECRET_KEY)
+            .await
+            .unwrap();
+    }
+    let accepted = horizon.transactions();
+
+    // The memo anchor is only reachable past the first page of transactions,
+    // and the data entry's evidence past the first page of operations.
+    let record = by_data
+        .verify_hash(&hashes[0], TEST_ACCOUNT_ID)
+        .await
+        .unwrap();
+    assert!(record.anchored);
+    assert_eq!(
+        record.transaction_id.as_deref(),
+        Some(accepted[0].hash.as_str())
+    );
+
+    let record = by_data
+        .verify_hash(&hashes[1], TEST_ACCOUNT_ID)
+        .await
+        .unwrap();
+    assert!(record.anchored);
+    assert_eq!(
+        record.transaction_id.as_deref(),
+        Some(accepted[1].hash.as_str())
+    );
+
+    let never = "ff".repeat(32);
+    assert!(
+        !by_data
+            .verify_hash(&never, TEST_ACCOUNT_ID)
+            .await
+            .unwrap()
+            .anchored
+    );
+}
+
+#[tokio::test]
+async fn fake_horizon_records_memos_and_pages_transactions() {
+    let horizon = FakeHorizon::start(TEST_ACCOUNT_ID).await;
+    let stellar = StellarClient::new(horizon.url()).with_anchor_method(AnchorMethod::Memo);
+
+    let hashes: Vec<String> = (1..=3).map(|i| format!("{:064x}", i)).collect();
+    for hash in &hashes {
+        stellar
+            .anchor_hash(hash, TEST_ACCOUNT_ID, TEST_SECRET_KEY)
+            .await
+            .unwrap();
+    }
+    assert_eq!(horizon.sequence(), 4);
+    let accepted = horizon.transactions();
+    assert!(matches!(accepted[0].memo, FakeMemo::Hash(digest) if hex::encode(digest) == hashes[0]));
+
+    for hash in &hashes {
+        assert!(
+            stellar
+                .verify_hash(hash, TEST_ACCOUNT_ID)
+                .await
+                .unwrap()
+                .anchored
+        );
+    }
+
+    let http = reqwest::Client::new();
+    let url = format!(
+        "{}/accounts/{}/transactions?order=desc&limit=2",
+        horizon.url(),
+        TEST_ACCOUNT_ID
+    );
+    let first: serde_json::Value = http.get(&url).send().await.unwrap().json().await.unwrap();
+    let records = first["_embedded"]["records"].as_array().unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["hash"], accepted[2].hash.as_str());
+
+    let next = first["_links"]["next"]["href"].as_str().unwrap();
+    let second: serde_json::Value = http
+        .get(format!("{}{}", horizon.url(), next))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let records = second["_embedded"]["records"].as_array().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["hash"], accepted[0].hash.as_str());
+}
+
+#[tokio::test]
+async fn fake_horizon_injects_failures() {
+    let horizon = FakeHorizon::start(TEST_ACCOUNT_ID).await;
+    let stellar = StellarClient::new(horizon.url());
+
+    horizon.fail_next_requests(1);
+    assert!(stellar.verify_hash(HASH, TEST_ACCOUNT_ID).await.is_err());
+    assert!(
+        !stellar
+            .verify_hash(HASH, TEST_ACCOUNT_ID)
+            .await
+            .unwrap()
+            .anchored
+    );
+
+    horizon.fail_next_submissions(1);
+    let err = stellar
+        .anchor_hash(HASH, TEST_ACCOUNT_ID, TEST_SECRET_KEY)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("500"));
+    assert!(horizon.transactions().is_empty());
+}
+
+fn mock_transaction(server: &MockServer, id: &str, memo_type: &str, memo: &str) {
+    server.mock(|when, then| {
+        when.method(GET).path(format!("/transactions/{}", id));
+        then.status(200).json_body(json!({
+            "hash": id,
+            "ledger": 90,
+            "created_at": "2023-06-01T00:00:00Z",
+            "source_account": TEST_ACCOUNT_ID,
+            "memo_type": memo_type,
+            "memo": memo,
+            "successful": true
+        }));
+    });
+}
+
+#[tokio::test]
+async fn verify_hash_in_transaction_matches_memo() {
+    let horizon = MockServer::start();
+    mock_transaction(&horizon, "tx-old", "hash", HASH_MEMO);
+    let stellar = StellarClient::new(&horizon.base_url());
+
+    match stellar
+        .verify_hash_in_transaction(HASH, "tx-old", TEST_ACCOUNT_ID)
+        .await
+        .unwrap()
+    {
+        TransactionVerification::Matched(evidence) => assert_eq!(evidence.ledger, 90),
+        other => panic!("expected a match, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn verify_hash_in_transaction_reports_unknown_transaction() {
+    let horizon = MockServer::start();
+    horizon.mock(|when, then| {
+        when.method(GET).path("/transactions/tx-missing");
+        then.status(404)
+            .json_body(json!({ "title": "Resource Missing" }));
+    });
+    let stellar = StellarClient::new(&horizon.base_url());
+
+    assert_eq!(
+        stellar
+            .verify_hash_in_transaction(HASH, "tx-missing", TEST_ACCOUNT_ID)
+            .await
+            .unwrap(),
+        TransactionVerification::NotFound
+    );
+}
+
+#[tokio::test]
+async fn verify_hash_in_transaction_reports_memo_mismatch() {
+    let horizon = MockServer::start();
+    mock_transaction(&horizon, "tx-other", "text", "TRANSFER:abc");
+    horizon.mock(|when, then| {
+        when.method(GET).path("/transactions/tx-other/operations");
+        then.status(200)
+            .json_body(json!({ "_embedded": { "records": [
+            {
+                "id": "1",
+                "transaction_hash": "tx-other",
+                "created_at": "2023-06-01T00:00:00Z",
+                "type": "manage_data",
+                "name": "transfer_abc",
+                "value": "eA=="
+            }
+        ] } }));
+    });
+    let stellar = StellarClient::new(&horizon.base_url());
+
+    assert!(matches!(
+        stellar
+            .verify_hash_in_transaction(HASH, "tx-other", TEST_ACCOUNT_ID)
+            .await
+            .unwrap(),
+        TransactionVerification::MemoMismatch(_)
+    ));
+}
+
+#[tokio::test]
+async fn verify_hash_in_transaction_ignores_entry_written_for_another_account() {
+    let horizon = MockServer::start();
+    mock_transaction(&horizon, "tx-sponsored", "none", "");
+    horizon.mock(|when, then| {
+        when.method(GET)
+            .path("/transactions/tx-sponsored/operations");
+        then.status(200)
+            .json_body(json!({ "_embedded": { "records": [
+            {
+                "id": "1",
+                "transaction_hash": "tx-sponsored",
+                "created_at": "2023-06-01T00:00:00Z",
+                "type": "manage_data",
+                "source_account": COSIGNER,
+                "name": build_data_key(HASH),
+                "value": "eA=="
+            }
+        ] } }));
+    });
+    let stellar = StellarClient::new(&horizon.base_url());
+
+    assert!(matches!(
+        stellar
+            .verify_hash_in_transaction(HASH, "tx-sponsored", TEST_ACCOUNT_ID)
+            .await
+            .unwrap(),
+        TransactionVerification::MemoMismatch(_)
+    ));
+    assert!(matches!(
+        stellar
+            .verify_hash_in_transaction(HASH, "tx-sponsored", COSIGNER)
+            .await
+            .unwrap(),
+        TransactionVerification::ForeignSource(_)
+    ));
+}